@@ -0,0 +1,32 @@
+//! 播放时钟模块
+//!
+//! 统计音频回调实际消耗的样本数，作为计算播放位置的依据。
+
+use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
+
+/// 播放时钟
+///
+/// 由音频流回调在持有音频缓冲区锁时累加，因此在持有同一把锁时读取到的值
+/// 与缓冲区中剩余的样本数是一致的。
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackClock {
+    /// 已从音频缓冲区取出的样本数（所有声道合计）
+    consumed_samples: Arc<AtomicU64>,
+}
+
+impl PlaybackClock {
+    /// 创建播放时钟
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录音频回调取出的样本数
+    pub fn advance(&self, samples: u64) {
+        self.consumed_samples.fetch_add(samples, Ordering::Relaxed);
+    }
+
+    /// 获取已消耗的样本总数
+    pub fn consumed_samples(&self) -> u64 {
+        self.consumed_samples.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,127 @@
+//! 交叉淡化模块
+//!
+//! 在当前曲目的最后一段与下一首的开头之间按等功率曲线混音。
+
+use std::collections::VecDeque;
+use std::f32::consts::FRAC_PI_2;
+
+/// 计算等功率交叉淡化的增益
+///
+/// # 参数
+/// * `progress` - 淡化进度（0.0 - 1.0）
+///
+/// # 返回
+/// (当前曲目增益, 下一首增益)，两者平方和恒为1
+pub fn equal_power_gains(progress: f32) -> (f32, f32) {
+    let angle = progress.clamp(0.0, 1.0) * FRAC_PI_2;
+    (angle.cos(), angle.sin())
+}
+
+/// 一次交叉淡化的区间
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crossfade {
+    /// 淡化开始的帧位置（相对于当前曲目）
+    start_frame: u64,
+    /// 淡化持续的帧数
+    length_frames: u64,
+}
+
+impl Crossfade {
+    /// 根据当前曲目的总帧数创建淡化区间
+    ///
+    /// # 参数
+    /// * `total_frames` - 当前曲目的总帧数
+    /// * `duration` - 淡化时长（秒）
+    /// * `sample_rate` - 采样率
+    ///
+    /// # 返回
+    /// 淡化时长为0或曲目过短（不足淡化时长的两倍）时返回None
+    pub fn new(total_frames: u64, duration: f64, sample_rate: u32) -> Option<Self> {
+        let length_frames = (duration.max(0.0) * sample_rate as f64) as u64;
+        if length_frames == 0 || total_frames < length_frames * 2 {
+            return None;
+        }
+        Some(Self {
+            start_frame: total_frames - length_frames,
+            length_frames,
+        })
+    }
+
+    /// 淡化开始的帧位置
+    pub fn start_frame(&self) -> u64 {
+        self.start_frame
+    }
+
+    /// 将下一首的样本混入当前曲目的一段交错样本
+    ///
+    /// 位于淡化区间之前的帧保持不变；之后的每一帧从 `incoming` 中取出一帧混入，
+    /// `incoming` 不足时按静音处理。
+    ///
+    /// # 参数
+    /// * `current` - 当前曲目的交错样本（原地修改）
+    /// * `position` - `current` 第一帧在当前曲目中的帧位置
+    /// * `channels` - 声道数
+    /// * `incoming` - 下一首已解码的交错样本
+    ///
+    /// # 返回
+    /// 从 `incoming` 中消耗的帧数
+    pub fn mix(&self, current: &mut [f32], position: u64, channels: usize, incoming: &mut VecDeque<f32>) -> u64 {
+        let mut consumed = 0;
+        for (offset, frame) in current.chunks_mut(channels).enumerate() {
+            let frame_position = position + offset as u64;
+            if frame_position < self.start_frame {
+                continue;
+            }
+            let progress = (frame_position - self.start_frame) as f32 / self.length_frames as f32;
+            let (current_gain, incoming_gain) = equal_power_gains(progress);
+            for sample in frame.iter_mut() {
+                let next_sample = incoming.pop_front().unwrap_or(0.0);
+                *sample = *sample * current_gain + next_sample * incoming_gain;
+            }
+            consumed += 1;
+        }
+        consumed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_power_gains() {
+        let (a, b) = equal_power_gains(0.0);
+        assert!((a - 1.0).abs() < 1e-6 && b.abs() < 1e-6);
+        let (a, b) = equal_power_gains(1.0);
+        assert!(a.abs() < 1e-6 && (b - 1.0).abs() < 1e-6);
+        for i in 0..=10 {
+            let (a, b) = equal_power_gains(i as f32 / 10.0);
+            assert!((a * a + b * b - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_crossfade_range() {
+        assert!(Crossfade::new(44100 * 10, 0.0, 44100).is_none());
+        assert!(Crossfade::new(44100 * 3, 2.0, 44100).is_none());
+        let fade = Crossfade::new(44100 * 10, 2.0, 44100).unwrap();
+        assert_eq!(fade.start_frame(), 44100 * 8);
+    }
+
+    #[test]
+    fn test_mix_only_after_start() {
+        // 10帧的曲目，最后4帧淡化，单声道
+        let fade = Crossfade::new(10, 4.0, 1).unwrap();
+        let mut current = vec![1.0; 4];
+        let mut incoming: VecDeque<f32> = vec![1.0; 8].into();
+        // 第4-7帧：前2帧在淡化区间之前
+        let consumed = fade.mix(&mut current, 4, 1, &mut incoming);
+        assert_eq!(consumed, 2);
+        assert_eq!(incoming.len(), 6);
+        assert_eq!(&current[..2], &[1.0, 1.0]);
+        // 淡化开始处仍为当前曲目的原始电平
+        assert!((current[2] - 1.0).abs() < 1e-6);
+        // 等功率曲线在中途叠加两个相关信号时略高于原电平
+        assert!(current[3] > 1.0);
+    }
+}
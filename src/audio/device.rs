@@ -63,35 +63,62 @@ pub fn list_audio_devices() {
     println!("\nTip: Use --device <index> to select a specific device for better audio quality.");
 }
 
+/// 获取所有音频输出设备的名称
+/// 
+/// 枚举顺序与 `list_audio_devices` 输出的设备索引一致。
+/// 
+/// # 返回
+/// 成功时返回设备名称列表，失败时返回错误
+pub fn output_device_names() -> Result<Vec<String>> {
+    let host = cpal::default_host();
+    let devices = host.output_devices()
+        .map_err(|e| PlayerError::AudioDeviceError(e.to_string()))?;
+    
+    Ok(devices
+        .map(|device| device.name().unwrap_or_else(|_| "Unknown Device".to_string()))
+        .collect())
+}
+
+/// 按名称查找音频输出设备
+/// 
+/// 设备名称找不到时（设备已拔出或被重新枚举）回退到默认设备。
+/// 
+/// # 参数
+/// * `device_name` - 设备名称，None表示使用默认设备
+/// 
+/// # 返回
+/// 成功时返回音频设备，失败时返回错误
+fn find_output_device(device_name: Option<&str>) -> Result<Device> {
+    let host = cpal::default_host();
+    
+    if let Some(name) = device_name {
+        let mut devices = host.output_devices()
+            .map_err(|e| PlayerError::AudioDeviceError(e.to_string()))?;
+        if let Some(device) = devices.find(|device| device.name().is_ok_and(|n| n == name)) {
+            return Ok(device);
+        }
+        eprintln!("Audio device '{}' not found, using default device", name);
+    }
+    
+    host.default_output_device()
+        .ok_or_else(|| PlayerError::AudioDeviceError("No default output device available".to_string()))
+}
+
 /// 设置音频设备
 /// 
 /// # 参数
-/// * `device_index` - 设备索引，None表示使用默认设备
+/// * `device_name` - 设备名称，None表示使用默认设备
 /// * `sample_rate` - 所需采样率
 /// * `source_channels` - 源音频声道数
 /// 
 /// # 返回
 /// 返回设备、配置和样本格式
 pub fn setup_audio_device(
-    device_index: Option<usize>, 
+    device_name: Option<&str>, 
     sample_rate: u32, 
     source_channels: usize
 ) -> Result<(Device, StreamConfig, SampleFormat)> {
-    let host = cpal::default_host();
-    let device = if let Some(index) = device_index {
-        let devices: Vec<_> = host.output_devices()
-            .map_err(|e| PlayerError::AudioDeviceError(e.to_string()))?
-            .collect();
-        
-        if index >= devices.len() {
-            return Err(PlayerError::AudioDeviceError(format!("Device index {} out of range", index)));
-        }
-        
-        devices[index].clone()
-    } else {
-        host.default_output_device()
-            .ok_or_else(|| PlayerError::AudioDeviceError("No default output device available".to_string()))?
-    };
+    let device = find_output_device(device_name)?;
 
     println!("Using audio device: {}", device.name().unwrap_or("Unknown".to_string()));
     
@@ -0,0 +1,309 @@
+//! 均衡器模块
+//!
+//! 10段双二阶滤波器均衡器：最低和最高频段为搁架滤波器，其余为峰值滤波器。
+
+use std::f64::consts::PI;
+use std::sync::{Arc, Mutex, atomic::{AtomicU64, Ordering}};
+
+/// 均衡器频段数
+pub const EQ_BAND_COUNT: usize = 10;
+
+/// 各频段的中心频率（Hz）
+pub const EQ_FREQUENCIES: [f32; EQ_BAND_COUNT] = [
+    31.0, 62.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0,
+];
+
+/// 单个频段的最大增益（dB）
+pub const MAX_EQ_GAIN_DB: f32 = 12.0;
+
+/// 内置预设（名称，各频段增益）
+pub const BUILTIN_PRESETS: &[(&str, [f32; EQ_BAND_COUNT])] = &[
+    ("Flat", [0.0; EQ_BAND_COUNT]),
+    ("Rock", [5.0, 4.0, 3.0, 1.0, -1.0, -1.0, 1.0, 3.0, 4.0, 5.0]),
+    ("Vocal", [-3.0, -2.0, -1.0, 1.0, 3.0, 4.0, 4.0, 3.0, 1.0, 0.0]),
+    ("Bass Boost", [7.0, 6.0, 5.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+];
+
+/// 每倍频程一个频段对应的Q值
+const BAND_Q: f64 = std::f64::consts::SQRT_2;
+
+/// 查找内置预设
+///
+/// # 参数
+/// * `name` - 预设名称
+///
+/// # 返回
+/// 找到时返回各频段增益
+pub fn builtin_preset(name: &str) -> Option<[f32; EQ_BAND_COUNT]> {
+    BUILTIN_PRESETS
+        .iter()
+        .find(|(preset, _)| *preset == name)
+        .map(|(_, gains)| *gains)
+}
+
+/// 均衡器增益控制
+///
+/// 在界面和解码线程之间共享，解码线程通过版本号发现增益变化。
+#[derive(Debug, Clone, Default)]
+pub struct EqualizerControl {
+    gains: Arc<Mutex<[f32; EQ_BAND_COUNT]>>,
+    version: Arc<AtomicU64>,
+}
+
+impl EqualizerControl {
+    /// 创建均衡器控制
+    ///
+    /// # 参数
+    /// * `gains` - 各频段初始增益（dB）
+    pub fn new(gains: [f32; EQ_BAND_COUNT]) -> Self {
+        let control = Self::default();
+        control.set_gains(gains);
+        control
+    }
+
+    /// 设置各频段增益（dB），超出范围的值会被限制
+    pub fn set_gains(&self, gains: [f32; EQ_BAND_COUNT]) {
+        *self.gains.lock().unwrap() = gains.map(|g| g.clamp(-MAX_EQ_GAIN_DB, MAX_EQ_GAIN_DB));
+        self.version.fetch_add(1, Ordering::Release);
+    }
+
+    /// 获取各频段增益（dB）
+    pub fn gains(&self) -> [f32; EQ_BAND_COUNT] {
+        *self.gains.lock().unwrap()
+    }
+
+    /// 增益版本号，每次设置增益后递增
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+}
+
+/// 双二阶滤波器系数（已按a0归一化）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BiquadCoefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl BiquadCoefficients {
+    /// 直通（不改变信号）
+    const IDENTITY: Self = Self { b0: 1.0, b1: 0.0, b2: 0.0, a1: 0.0, a2: 0.0 };
+
+    /// 根据RBJ Audio EQ Cookbook计算滤波器系数
+    fn new(kind: FilterKind, frequency: f64, gain_db: f64, sample_rate: u32) -> Self {
+        let nyquist = sample_rate as f64 / 2.0;
+        if gain_db == 0.0 || frequency >= nyquist * 0.9 {
+            return Self::IDENTITY;
+        }
+
+        let a = 10f64.powf(gain_db / 40.0);
+        let w0 = 2.0 * PI * frequency / sample_rate as f64;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * BAND_Q);
+
+        let (b0, b1, b2, a0, a1, a2) = match kind {
+            FilterKind::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            FilterKind::LowShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos + k),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - k),
+                    (a + 1.0) + (a - 1.0) * cos + k,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - k,
+                )
+            }
+            FilterKind::HighShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos + k),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - k),
+                    (a + 1.0) - (a - 1.0) * cos + k,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - k,
+                )
+            }
+        };
+
+        Self {
+            b0: (b0 / a0) as f32,
+            b1: (b1 / a0) as f32,
+            b2: (b2 / a0) as f32,
+            a1: (a1 / a0) as f32,
+            a2: (a2 / a0) as f32,
+        }
+    }
+}
+
+/// 滤波器类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FilterKind {
+    LowShelf,
+    Peaking,
+    HighShelf,
+}
+
+/// 单声道单频段的滤波器状态（直接II型转置）
+#[derive(Debug, Clone, Copy, Default)]
+struct BiquadState {
+    z1: f32,
+    z2: f32,
+}
+
+impl BiquadState {
+    #[inline]
+    fn process(&mut self, c: &BiquadCoefficients, x: f32) -> f32 {
+        let y = c.b0 * x + self.z1;
+        self.z1 = c.b1 * x - c.a1 * y + self.z2;
+        self.z2 = c.b2 * x - c.a2 * y;
+        y
+    }
+}
+
+/// 多声道均衡器
+#[derive(Debug, Clone)]
+pub struct Equalizer {
+    sample_rate: u32,
+    channels: usize,
+    coefficients: [BiquadCoefficients; EQ_BAND_COUNT],
+    /// 每个声道每个频段的状态
+    states: Vec<[BiquadState; EQ_BAND_COUNT]>,
+    /// 防止提升频段后削波的前级衰减
+    pregain: f32,
+    /// 所有频段增益为0时跳过处理
+    bypass: bool,
+}
+
+impl Equalizer {
+    /// 创建均衡器
+    ///
+    /// # 参数
+    /// * `sample_rate` - 采样率
+    /// * `channels` - 声道数
+    /// * `gains` - 各频段增益（dB）
+    pub fn new(sample_rate: u32, channels: usize, gains: [f32; EQ_BAND_COUNT]) -> Self {
+        let mut equalizer = Self {
+            sample_rate,
+            channels: channels.max(1),
+            coefficients: [BiquadCoefficients::IDENTITY; EQ_BAND_COUNT],
+            states: vec![[BiquadState::default(); EQ_BAND_COUNT]; channels.max(1)],
+            pregain: 1.0,
+            bypass: true,
+        };
+        equalizer.set_gains(gains);
+        equalizer
+    }
+
+    /// 更新各频段增益（dB），保留滤波器状态以避免爆音
+    pub fn set_gains(&mut self, gains: [f32; EQ_BAND_COUNT]) {
+        for (band, gain) in gains.iter().enumerate() {
+            let kind = match band {
+                0 => FilterKind::LowShelf,
+                b if b == EQ_BAND_COUNT - 1 => FilterKind::HighShelf,
+                _ => FilterKind::Peaking,
+            };
+            self.coefficients[band] = BiquadCoefficients::new(
+                kind,
+                EQ_FREQUENCIES[band] as f64,
+                gain.clamp(-MAX_EQ_GAIN_DB, MAX_EQ_GAIN_DB) as f64,
+                self.sample_rate,
+            );
+        }
+
+        let max_boost = gains.iter().fold(0.0f32, |max, &g| max.max(g.min(MAX_EQ_GAIN_DB)));
+        self.pregain = 10f32.powf(-max_boost / 20.0);
+        self.bypass = gains.iter().all(|&g| g == 0.0);
+        if self.bypass {
+            self.reset();
+        }
+    }
+
+    /// 清除滤波器状态（跳转后调用）
+    pub fn reset(&mut self) {
+        for state in self.states.iter_mut() {
+            *state = [BiquadState::default(); EQ_BAND_COUNT];
+        }
+    }
+
+    /// 原地处理交错样本
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if self.bypass {
+            return;
+        }
+        for frame in samples.chunks_mut(self.channels) {
+            for (sample, states) in frame.iter_mut().zip(self.states.iter_mut()) {
+                let mut value = *sample * self.pregain;
+                for (state, coefficients) in states.iter_mut().zip(self.coefficients.iter()) {
+                    if *coefficients != BiquadCoefficients::IDENTITY {
+                        value = state.process(coefficients, value);
+                    }
+                }
+                *sample = value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 处理单声道正弦波，返回稳定后的峰值
+    fn sine_peak(equalizer: &mut Equalizer, frequency: f32, sample_rate: u32) -> f32 {
+        let mut samples: Vec<f32> = (0..sample_rate as usize)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate as f32).sin() * 0.25)
+            .collect();
+        equalizer.process(&mut samples);
+        samples[sample_rate as usize / 2..].iter().fold(0.0f32, |max, s| max.max(s.abs())) / 0.25
+    }
+
+    #[test]
+    fn test_flat_is_bypassed() {
+        let mut equalizer = Equalizer::new(44100, 2, [0.0; EQ_BAND_COUNT]);
+        let mut samples = vec![0.5, -0.5, 0.25, -0.25];
+        equalizer.process(&mut samples);
+        assert_eq!(samples, vec![0.5, -0.5, 0.25, -0.25]);
+    }
+
+    #[test]
+    fn test_peaking_band_boost() {
+        let mut gains = [0.0; EQ_BAND_COUNT];
+        gains[5] = 6.0;
+        let mut equalizer = Equalizer::new(44100, 1, gains);
+        // 前级衰减6dB，1kHz提升6dB，整体不变；远离该频段的信号被衰减
+        assert!((sine_peak(&mut equalizer, 1000.0, 44100) - 1.0).abs() < 0.05);
+        equalizer.reset();
+        assert!((sine_peak(&mut equalizer, 100.0, 44100) - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_builtin_presets() {
+        assert_eq!(builtin_preset("Flat"), Some([0.0; EQ_BAND_COUNT]));
+        assert!(builtin_preset("Bass Boost").is_some());
+        assert!(builtin_preset("Unknown").is_none());
+        for (_, gains) in BUILTIN_PRESETS {
+            assert!(gains.iter().all(|g| g.abs() <= MAX_EQ_GAIN_DB));
+        }
+    }
+
+    #[test]
+    fn test_control_version() {
+        let control = EqualizerControl::new([0.0; EQ_BAND_COUNT]);
+        let version = control.version();
+        control.set_gains([20.0; EQ_BAND_COUNT]);
+        assert!(control.version() > version);
+        assert_eq!(control.gains(), [MAX_EQ_GAIN_DB; EQ_BAND_COUNT]);
+    }
+}
@@ -14,6 +14,7 @@ use symphonia::default;
 
 use crate::error::{PlayerError, Result};
 use crate::config::audio::{MAX_ESTIMATION_PACKETS, DEFAULT_SAMPLE_RATE};
+use super::replaygain::ReplayGain;
 
 /// 封面图片信息
 #[derive(Debug, Clone)]
@@ -51,6 +52,8 @@ pub struct AudioMetadata {
     pub cover_art: Option<CoverArt>,
     /// 内嵌歌词信息
     pub embedded_lyrics: Vec<EmbeddedLyrics>,
+    /// ReplayGain响度信息
+    pub replay_gain: ReplayGain,
     /// 其他标签
     pub other_tags: HashMap<String, String>,
 }
@@ -157,6 +160,10 @@ impl AudioMetadata {
                     StandardTagKey::AlbumArtist => audio_metadata.album_artist = Some(value),
                     StandardTagKey::Composer => audio_metadata.composer = Some(value),
                     StandardTagKey::Comment => audio_metadata.comment = Some(value),
+                    StandardTagKey::ReplayGainTrackGain => { audio_metadata.replay_gain.apply_tag("REPLAYGAIN_TRACK_GAIN", &value); }
+                    StandardTagKey::ReplayGainTrackPeak => { audio_metadata.replay_gain.apply_tag("REPLAYGAIN_TRACK_PEAK", &value); }
+                    StandardTagKey::ReplayGainAlbumGain => { audio_metadata.replay_gain.apply_tag("REPLAYGAIN_ALBUM_GAIN", &value); }
+                    StandardTagKey::ReplayGainAlbumPeak => { audio_metadata.replay_gain.apply_tag("REPLAYGAIN_ALBUM_PEAK", &value); }
                     _ => {
                         // 对于其他标准键，使用其调试字符串表示作为键名
                         audio_metadata.other_tags.insert(format!("{:?}", std_key), value);
@@ -165,6 +172,9 @@ impl AudioMetadata {
             } else {
                 // 如果没有std_key，回退到使用原始key
                 let key = &tag.key;
+                if audio_metadata.replay_gain.apply_tag(key, &value) {
+                    continue;
+                }
                 audio_metadata.other_tags.insert(key.clone(), value);
             }
         }
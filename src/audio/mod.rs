@@ -7,13 +7,26 @@ pub mod device;
 pub mod playback;
 pub mod decoder;
 pub mod stream;
+pub mod volume;
+pub mod crossfade;
+pub mod replaygain;
+pub mod clock;
+pub mod resampler;
+pub mod equalizer;
+pub mod time_stretch;
 
 // 重新导出主要类型
 pub use file::{AudioFile, AudioInfo};
-pub use device::{list_audio_devices, setup_audio_device};
+pub use device::{list_audio_devices, output_device_names, setup_audio_device};
 pub use playback::{
-    PlaybackState, PlaybackCommand, AudioBuffer, AudioSource,
+    PlaybackState, PlaybackCommand, PlaybackEvent, AudioBuffer, AudioSource,
     start_audio_playback,
 };
 pub use decoder::create_decoder;
-pub use stream::{create_audio_stream, create_stream}; 
\ No newline at end of file
+pub use stream::{create_audio_stream, create_stream, StreamErrorSender};
+pub use volume::VolumeControl;
+pub use replaygain::{ReplayGain, ReplayGainSettings};
+pub use clock::PlaybackClock;
+pub use resampler::Resampler;
+pub use equalizer::{Equalizer, EqualizerControl};
+pub use time_stretch::{SpeedControl, TimeStretcher}; 
\ No newline at end of file
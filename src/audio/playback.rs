@@ -4,9 +4,9 @@
 
 use std::{
     collections::VecDeque,
-    sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU64, Ordering}},
+    sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering}},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::sync::mpsc;
 use cpal::traits::StreamTrait;
@@ -14,8 +14,13 @@ use symphonia::core::audio::Signal;
 use symphonia::core::formats::{SeekMode, SeekTo};
 
 use crate::error::{PlayerError, Result};
-use crate::config::{BUFFER_CAPACITY_THRESHOLD, BUFFER_WRITE_DELAY};
-use super::{AudioFile, create_decoder, setup_audio_device, create_audio_stream};
+use crate::config::{BUFFER_CAPACITY_THRESHOLD, BUFFER_WRITE_DELAY, ResampleQuality};
+use super::{AudioFile, AudioInfo, PlaybackClock, StreamErrorSender, VolumeControl, create_decoder, setup_audio_device, create_audio_stream};
+use super::crossfade::Crossfade;
+use super::replaygain::ReplayGainSettings;
+use super::resampler::Resampler;
+use super::equalizer::{Equalizer, EqualizerControl, EQ_BAND_COUNT};
+use super::time_stretch::{SpeedControl, TimeStretcher};
 
 /// 播放控制命令
 #[derive(Debug, Clone)]
@@ -28,6 +33,22 @@ pub enum PlaybackCommand {
     Stop,
     /// 跳转到指定时间（秒）
     Seek(f64),
+    /// 设置音量（0.0 - 1.0）
+    SetVolume(f32),
+    /// 设置静音状态
+    SetMuted(bool),
+    /// 预排下一首曲目及交叉淡化时长（秒），时长为0时在当前曲目结束后无缝衔接
+    QueueNext(Box<AudioFile>, f64),
+    /// 取消预排的下一首
+    ClearNext,
+    /// 切换输出设备（设备名称，None表示默认设备），保持当前播放位置
+    SetDevice(Option<String>),
+    /// 设置均衡器各频段增益（dB），全部为0时不做处理
+    SetEqualizer([f32; EQ_BAND_COUNT]),
+    /// 设置播放速度（0.5 - 2.0），保持音高不变
+    SetSpeed(f32),
+    /// 设置A-B循环区间（A点秒数, B点秒数），播放到B点时跳回A点；None表示取消
+    SetLoop(Option<(f64, f64)>),
 }
 
 /// 播放状态
@@ -37,7 +58,7 @@ pub struct PlaybackState {
     pub is_playing: bool,
     /// 是否已暂停
     pub is_paused: bool,
-    /// 当前播放时间（秒）
+    /// 当前播放时间（秒，曲目内的时间，不受播放速度影响）
     pub current_time: f64,
     /// 总时长（秒）
     pub total_duration: f64,
@@ -45,6 +66,22 @@ pub struct PlaybackState {
     pub current_samples: u64,
     /// 样本率
     pub sample_rate: u32,
+    /// 播放速度
+    pub speed: f32,
+}
+
+/// 播放引擎发出的事件
+#[derive(Debug, Clone)]
+pub enum PlaybackEvent {
+    /// 播放状态更新（位置基于音频回调实际播放的样本数）
+    State(PlaybackState),
+    /// 已播放到预排的下一首（无缝播放或交叉淡化），携带其文件路径
+    TrackChanged(String),
+    /// 所有样本已播放完毕，且没有可衔接的下一首
+    EndOfStream,
+    /// 输出设备不可用（如设备被拔出），引擎会切换到默认设备继续播放；
+    /// 默认设备也无法打开时引擎暂停播放，恢复播放时重试
+    DeviceError(String),
 }
 
 impl Default for PlaybackState {
@@ -56,6 +93,7 @@ impl Default for PlaybackState {
             total_duration: 0.0,
             current_samples: 0,
             sample_rate: 0,
+            speed: 1.0,
         }
     }
 }
@@ -63,6 +101,83 @@ impl Default for PlaybackState {
 /// 音频缓冲区类型
 pub type AudioBuffer = Arc<Mutex<VecDeque<f32>>>;
 
+/// A-B循环区间（由控制任务写入，播放线程读取）
+type LoopRange = Arc<Mutex<Option<(f64, f64)>>>;
+
+/// 预排的下一首曲目及交叉淡化时长（由控制任务写入，播放线程取出）
+type NextTrackSlot = Arc<Mutex<Option<(AudioFile, f64)>>>;
+
+/// 已完成探测并创建好解码器的下一首曲目
+struct PreparedTrack {
+    audio_file: AudioFile,
+    format: Box<dyn symphonia::core::formats::FormatReader>,
+    decoder: Box<dyn symphonia::core::codecs::Decoder>,
+    track_id: u32,
+    /// 下一首的总帧数（用于它自己结束时的交叉淡化）
+    total_frames: Option<u64>,
+    /// ReplayGain线性增益
+    gain: f32,
+    /// 交叉淡化时长（秒）
+    crossfade_duration: f64,
+    /// 与当前曲目的交叉淡化区间，None表示直接衔接
+    crossfade: Option<Crossfade>,
+    /// 已解码但尚未输出的交错样本
+    incoming: VecDeque<f32>,
+    /// 已解码的帧数
+    decoded_frames: u64,
+    /// 下一首是否已在淡化期间解码完毕
+    exhausted: bool,
+}
+
+impl PreparedTrack {
+    /// 在淡化区间内将下一首的开头混入当前曲目的样本
+    /// 
+    /// # 参数
+    /// * `samples` - 当前曲目的交错样本（原地修改）
+    /// * `position` - `samples` 第一帧在当前曲目中的帧位置
+    /// * `channels` - 声道数
+    fn mix_into(&mut self, samples: &mut [f32], position: u64, channels: usize) {
+        let Some(crossfade) = self.crossfade else {
+            return;
+        };
+        let frames = (samples.len() / channels) as u64;
+        if position + frames <= crossfade.start_frame() {
+            return;
+        }
+        
+        // 解码足够的下一首样本
+        while self.incoming.len() < samples.len() && !self.exhausted {
+            match self.format.next_packet() {
+                Ok(packet) => {
+                    if packet.track_id() != self.track_id {
+                        continue;
+                    }
+                    match self.decoder.decode(&packet) {
+                        Ok(decoded) => {
+                            self.decoded_frames += decoded.frames() as u64;
+                            let mut samples = interleave_samples(&decoded, channels);
+                            apply_gain(&mut samples, self.gain);
+                            self.incoming.extend(samples);
+                        }
+                        Err(e) => {
+                            eprintln!("Crossfade decode error: {}", e);
+                            self.exhausted = true;
+                        }
+                    }
+                }
+                Err(_) => self.exhausted = true,
+            }
+        }
+        
+        crossfade.mix(samples, position, channels, &mut self.incoming);
+    }
+    
+    /// 淡化是否已经开始（已从下一首读取过数据）
+    fn has_started(&self) -> bool {
+        self.decoded_frames > 0
+    }
+}
+
 /// 音频源枚举 - 支持文件路径或已加载的AudioFile
 #[derive(Debug)]
 pub enum AudioSource {
@@ -76,13 +191,25 @@ pub enum AudioSource {
 /// 
 /// # 参数
 /// * `audio_source` - 音频源（文件路径或AudioFile实例）
-/// * `state_sender` - 可选的播放状态发送器
+/// * `event_sender` - 可选的播放事件发送器
+/// * `device_name` - 输出设备名称，None表示使用默认设备
+/// * `volume` - 音量控制（初始音量和静音状态）
+/// * `equalizer` - 均衡器控制（初始增益）
+/// * `speed` - 播放速度控制（初始速度）
+/// * `replay_gain` - ReplayGain响度标准化设置
+/// * `resample_quality` - 设备不支持文件采样率时的采样率转换质量
 /// 
 /// # 返回
 /// 返回命令发送器和播放任务句柄
 pub async fn start_audio_playback(
     audio_source: AudioSource,
-    state_sender: Option<mpsc::UnboundedSender<PlaybackState>>,
+    event_sender: Option<mpsc::UnboundedSender<PlaybackEvent>>,
+    device_name: Option<String>,
+    volume: VolumeControl,
+    equalizer: EqualizerControl,
+    speed: SpeedControl,
+    replay_gain: ReplayGainSettings,
+    resample_quality: ResampleQuality,
 ) -> (mpsc::UnboundedSender<PlaybackCommand>, tokio::task::JoinHandle<()>) {
     let (command_sender, command_receiver) = mpsc::unbounded_channel();
     
@@ -90,17 +217,17 @@ pub async fn start_audio_playback(
         tokio::runtime::Handle::current().block_on(async move {
             let result = match audio_source {
                 AudioSource::FilePath(file_path) => {
-                    if let Some(sender) = state_sender {
-                        run_audio_playback_with_control_and_state(&file_path, None, command_receiver, sender).await
+                    if let Some(sender) = event_sender {
+                        run_audio_playback_with_control_and_state(&file_path, device_name, command_receiver, sender, volume, equalizer, speed, replay_gain, resample_quality).await
                     } else {
-                        run_audio_playback_with_control(&file_path, None, command_receiver).await
+                        run_audio_playback_with_control(&file_path, device_name, command_receiver, volume, equalizer, speed, replay_gain, resample_quality).await
                     }
                 }
                 AudioSource::AudioFile(audio_file) => {
-                    if let Some(sender) = state_sender {
-                        run_audio_playback_with_file_and_state(audio_file, None, command_receiver, sender).await
+                    if let Some(sender) = event_sender {
+                        run_audio_playback_with_file_and_state(audio_file, device_name, command_receiver, sender, volume, equalizer, speed, replay_gain, resample_quality).await
                     } else {
-                        run_audio_playback_with_file_control(audio_file, None, command_receiver).await
+                        run_audio_playback_with_file_control(audio_file, device_name, command_receiver, volume, equalizer, speed, replay_gain, resample_quality).await
                     }
                 }
             };
@@ -118,277 +245,114 @@ pub async fn start_audio_playback(
 /// 
 /// # 参数
 /// * `file_path` - 音频文件路径  
-/// * `device_index` - 音频设备索引
+/// * `device_name` - 输出设备名称
 /// * `command_receiver` - 命令接收器
+/// * `volume` - 音量控制
+/// * `equalizer` - 均衡器控制
+/// * `speed` - 播放速度控制
+/// * `replay_gain` - ReplayGain设置
+/// * `resample_quality` - 采样率转换质量
 async fn run_audio_playback_with_control(
     file_path: &str,
-    device_index: Option<usize>,
+    device_name: Option<String>,
     command_receiver: mpsc::UnboundedReceiver<PlaybackCommand>,
+    volume: VolumeControl,
+    equalizer: EqualizerControl,
+    speed: SpeedControl,
+    replay_gain: ReplayGainSettings,
+    resample_quality: ResampleQuality,
 ) -> Result<()> {
     let audio_file = AudioFile::open(file_path)?;
-    run_audio_playback_with_file_control(audio_file, device_index, command_receiver).await
+    run_audio_playback_with_file_control(audio_file, device_name, command_receiver, volume, equalizer, speed, replay_gain, resample_quality).await
 }
 
 /// 音频播放控制函数（使用已打开的AudioFile）
 /// 
 /// # 参数
 /// * `audio_file` - 已打开的AudioFile实例
-/// * `device_index` - 音频设备索引
+/// * `device_name` - 输出设备名称
 /// * `command_receiver` - 命令接收器
+/// * `volume` - 音量控制
+/// * `equalizer` - 均衡器控制
+/// * `speed` - 播放速度控制
+/// * `replay_gain` - ReplayGain设置
+/// * `resample_quality` - 采样率转换质量
 async fn run_audio_playback_with_file_control(
     audio_file: AudioFile,
-    device_index: Option<usize>,
-    mut command_receiver: mpsc::UnboundedReceiver<PlaybackCommand>,
+    device_name: Option<String>,
+    command_receiver: mpsc::UnboundedReceiver<PlaybackCommand>,
+    volume: VolumeControl,
+    equalizer: EqualizerControl,
+    speed: SpeedControl,
+    replay_gain: ReplayGainSettings,
+    resample_quality: ResampleQuality,
 ) -> Result<()> {
-    // 创建播放上下文
-    let (probed, track) = audio_file.create_playback_context()?;
-    let decoder = create_decoder(&track)?;
-    let (device, config, sample_format) = setup_audio_device(
-        device_index, 
-        audio_file.info.sample_rate, 
-        audio_file.info.channels
-    )?;
-    
-    let buffer_size = calculate_buffer_size(audio_file.info.sample_rate, audio_file.info.channels);
-    let audio_buffer: AudioBuffer = Arc::new(Mutex::new(VecDeque::with_capacity(buffer_size)));
-    
-    let stream = create_audio_stream(&device, &config, sample_format, audio_buffer.clone(), audio_file.info.channels)?;
-    
-    let _is_playing = Arc::new(AtomicBool::new(true));
-    let is_paused = Arc::new(AtomicBool::new(false));
-    let should_stop = Arc::new(AtomicBool::new(false));
-    
-    // 添加跳转目标时间的原子变量（以秒为单位，乘以1000存储为毫秒以保持精度）
-    let seek_target_ms = Arc::new(AtomicU64::new(u64::MAX)); // u64::MAX 表示没有跳转请求
-    
-    // 创建音频流的暂停/恢复控制
-    let _stream_is_paused = is_paused.clone();
-    
-    stream.play().map_err(|e| PlayerError::PlaybackError(e.to_string()))?;
-    
-    let playback_thread = {
-        let audio_buffer = audio_buffer.clone();
-        let should_stop = should_stop.clone();
-        let is_paused = is_paused.clone();
-        let seek_target_ms = seek_target_ms.clone();
-        
-        let target_channels = audio_file.info.channels;
-        let sample_rate = audio_file.info.sample_rate;
-        thread::spawn(move || {
-            let _ = run_playback_loop(probed, track, decoder, audio_buffer, should_stop, is_paused, seek_target_ms, target_channels, sample_rate);
-        })
-    };
-    
-    // 处理播放控制命令
-    while let Some(command) = command_receiver.recv().await {
-        match command {
-            PlaybackCommand::Pause => {
-                is_paused.store(true, Ordering::Relaxed);
-                // 暂停音频流
-                if let Err(e) = stream.pause() {
-                    eprintln!("Failed to pause stream: {}", e);
-                }
-            }
-            PlaybackCommand::Resume => {
-                is_paused.store(false, Ordering::Relaxed);
-                // 恢复音频流
-                if let Err(e) = stream.play() {
-                    eprintln!("Failed to resume stream: {}", e);
-                }
-            }
-            PlaybackCommand::Stop => {
-                should_stop.store(true, Ordering::Relaxed);
-                break;
-            }
-            PlaybackCommand::Seek(target_time) => {
-                // 将跳转目标时间转换为毫秒并存储
-                let target_ms = (target_time * 1000.0) as u64;
-                seek_target_ms.store(target_ms, Ordering::Relaxed);
-                println!("Seek request: {:.2}s", target_time);
-            }
-        }
-    }
-    
-    should_stop.store(true, Ordering::Relaxed);
-    playback_thread.join().unwrap();
-    
-    Ok(())
+    // 与带状态更新的播放共用同一流程，由它跟踪播放位置（A-B循环依赖播放位置），播放事件直接丢弃
+    let (event_sender, _) = mpsc::unbounded_channel();
+    run_audio_playback_with_file_and_state(audio_file, device_name, command_receiver, event_sender, volume, equalizer, speed, replay_gain, resample_quality).await
 }
 
 /// 音频播放控制函数（带状态更新）
 /// 
 /// # 参数
 /// * `file_path` - 音频文件路径  
-/// * `device_index` - 音频设备索引
+/// * `device_name` - 输出设备名称
 /// * `command_receiver` - 命令接收器
-/// * `state_sender` - 播放状态发送器
+/// * `event_sender` - 播放事件发送器
+/// * `volume` - 音量控制
+/// * `equalizer` - 均衡器控制
+/// * `speed` - 播放速度控制
+/// * `replay_gain` - ReplayGain设置
+/// * `resample_quality` - 采样率转换质量
 async fn run_audio_playback_with_control_and_state(
     file_path: &str,
-    device_index: Option<usize>,
-    mut command_receiver: mpsc::UnboundedReceiver<PlaybackCommand>,
-    state_sender: mpsc::UnboundedSender<PlaybackState>,
+    device_name: Option<String>,
+    command_receiver: mpsc::UnboundedReceiver<PlaybackCommand>,
+    event_sender: mpsc::UnboundedSender<PlaybackEvent>,
+    volume: VolumeControl,
+    equalizer: EqualizerControl,
+    speed: SpeedControl,
+    replay_gain: ReplayGainSettings,
+    resample_quality: ResampleQuality,
 ) -> Result<()> {
     let audio_file = AudioFile::open(file_path)?;
-    let (probed, track) = audio_file.create_playback_context()?;
-    let decoder = create_decoder(&track)?;
-    let (device, config, sample_format) = setup_audio_device(
-        device_index, 
-        audio_file.info.sample_rate, 
-        audio_file.info.channels
-    )?;
-    
-    let buffer_size = calculate_buffer_size(audio_file.info.sample_rate, audio_file.info.channels);
-    let audio_buffer: AudioBuffer = Arc::new(Mutex::new(VecDeque::with_capacity(buffer_size)));
-    
-    let stream = create_audio_stream(&device, &config, sample_format, audio_buffer.clone(), audio_file.info.channels)?;
-    
-    let _is_playing = Arc::new(AtomicBool::new(true));
-    let is_paused = Arc::new(AtomicBool::new(false));
-    let should_stop = Arc::new(AtomicBool::new(false));
-    
-    // 添加跳转目标时间的原子变量（以秒为单位，乘以1000存储为毫秒以保持精度）
-    let seek_target_ms = Arc::new(AtomicU64::new(u64::MAX)); // u64::MAX 表示没有跳转请求
-    
-    // 用于跟踪当前播放位置的原子变量
-    let current_samples = Arc::new(AtomicU64::new(0));
-    
-    // 创建音频流的暂停/恢复控制
-    let _stream_is_paused = is_paused.clone();
-    
-    stream.play().map_err(|e| PlayerError::PlaybackError(e.to_string()))?;
-    
-    // 提取播放会话所需的信息
-    let audio_sample_rate = audio_file.info.sample_rate;
-    let total_duration = audio_file.info.duration.unwrap_or(0.0);
-    
-    // 创建初始播放状态
-    let initial_state = PlaybackState {
-        is_playing: true,
-        is_paused: false,
-        current_time: 0.0,
-        total_duration,
-        current_samples: 0,
-        sample_rate: audio_sample_rate,
-    };
-    
-    // 发送初始状态
-    let _ = state_sender.send(initial_state);
-    
-    let playback_thread = {
-        let audio_buffer = audio_buffer.clone();
-        let should_stop = should_stop.clone();
-        let is_paused = is_paused.clone();
-        let seek_target_ms = seek_target_ms.clone();
-        let current_samples = current_samples.clone();
-        let state_sender = state_sender.clone();
-        
-        let target_channels = audio_file.info.channels;
-        thread::spawn(move || {
-            let _ = run_playback_loop_with_state(
-                probed,
-                track,
-                decoder, 
-                audio_buffer, 
-                should_stop, 
-                is_paused, 
-                seek_target_ms,
-                current_samples,
-                state_sender,
-                target_channels,
-                audio_sample_rate,
-                total_duration,
-            );
-        })
-    };
-    
-    // 处理播放控制命令
-    while let Some(command) = command_receiver.recv().await {
-        match command {
-            PlaybackCommand::Pause => {
-                is_paused.store(true, Ordering::Relaxed);
-                // 暂停音频流
-                if let Err(e) = stream.pause() {
-                    eprintln!("Failed to pause stream: {}", e);
-                }
-                
-                // 发送暂停状态更新
-                let current_pos = current_samples.load(Ordering::Relaxed);
-                let current_time = current_pos as f64 / audio_sample_rate as f64;
-                let state = PlaybackState {
-                    is_playing: false,
-                    is_paused: true,
-                    current_time,
-                    total_duration,
-                    current_samples: current_pos,
-                    sample_rate: audio_sample_rate,
-                };
-                let _ = state_sender.send(state);
-            }
-            PlaybackCommand::Resume => {
-                is_paused.store(false, Ordering::Relaxed);
-                // 恢复音频流
-                if let Err(e) = stream.play() {
-                    eprintln!("Failed to resume stream: {}", e);
-                }
-                
-                // 发送恢复状态更新
-                let current_pos = current_samples.load(Ordering::Relaxed);
-                let current_time = current_pos as f64 / audio_sample_rate as f64;
-                let state = PlaybackState {
-                    is_playing: true,
-                    is_paused: false,
-                    current_time,
-                    total_duration,
-                    current_samples: current_pos,
-                    sample_rate: audio_sample_rate,
-                };
-                let _ = state_sender.send(state);
-            }
-            PlaybackCommand::Stop => {
-                should_stop.store(true, Ordering::Relaxed);
-                break;
-            }
-            PlaybackCommand::Seek(target_time) => {
-                // 将跳转目标时间转换为毫秒并存储
-                let target_ms = (target_time * 1000.0) as u64;
-                seek_target_ms.store(target_ms, Ordering::Relaxed);
-                println!("Seek request: {:.2}s", target_time);
-            }
-        }
-    }
-    
-    should_stop.store(true, Ordering::Relaxed);
-    playback_thread.join().unwrap();
-    
-    Ok(())
+    run_audio_playback_with_file_and_state(audio_file, device_name, command_receiver, event_sender, volume, equalizer, speed, replay_gain, resample_quality).await
 }
 
 /// 音频播放控制函数（使用已打开的AudioFile，带状态更新）
 /// 
 /// # 参数
 /// * `audio_file` - 已打开的AudioFile实例
-/// * `device_index` - 音频设备索引
+/// * `device_name` - 输出设备名称
 /// * `command_receiver` - 命令接收器
-/// * `state_sender` - 播放状态发送器
+/// * `event_sender` - 播放事件发送器
+/// * `volume` - 音量控制
+/// * `equalizer` - 均衡器控制
+/// * `speed` - 播放速度控制
+/// * `replay_gain` - ReplayGain设置
+/// * `resample_quality` - 采样率转换质量
 async fn run_audio_playback_with_file_and_state(
     audio_file: AudioFile,
-    device_index: Option<usize>,
-    mut command_receiver: mpsc::UnboundedReceiver<PlaybackCommand>,
-    state_sender: mpsc::UnboundedSender<PlaybackState>,
+    device_name: Option<String>,
+    command_receiver: mpsc::UnboundedReceiver<PlaybackCommand>,
+    event_sender: mpsc::UnboundedSender<PlaybackEvent>,
+    volume: VolumeControl,
+    equalizer: EqualizerControl,
+    speed: SpeedControl,
+    replay_gain: ReplayGainSettings,
+    resample_quality: ResampleQuality,
 ) -> Result<()> {
     // 创建播放上下文
     let (probed, track) = audio_file.create_playback_context()?;
     let decoder = create_decoder(&track)?;
-    let (device, config, sample_format) = setup_audio_device(
-        device_index, 
-        audio_file.info.sample_rate, 
-        audio_file.info.channels
-    )?;
-    
     let buffer_size = calculate_buffer_size(audio_file.info.sample_rate, audio_file.info.channels);
     let audio_buffer: AudioBuffer = Arc::new(Mutex::new(VecDeque::with_capacity(buffer_size)));
     
-    let stream = create_audio_stream(&device, &config, sample_format, audio_buffer.clone(), audio_file.info.channels)?;
+    let clock = PlaybackClock::new();
+    let (stream_error_sender, stream_error_receiver) = mpsc::unbounded_channel();
+    let (stream, stream_rate) = open_output_stream(device_name.as_deref(), &audio_file.info, &audio_buffer, &volume, &clock, &stream_error_sender)?;
+    // 输出设备的采样率，切换设备后可能变化
+    let output_rate = Arc::new(AtomicU32::new(stream_rate));
     
     let _is_playing = Arc::new(AtomicBool::new(true));
     let is_paused = Arc::new(AtomicBool::new(false));
@@ -397,8 +361,11 @@ async fn run_audio_playback_with_file_and_state(
     // 添加跳转目标时间的原子变量（以秒为单位，乘以1000存储为毫秒以保持精度）
     let seek_target_ms = Arc::new(AtomicU64::new(u64::MAX)); // u64::MAX 表示没有跳转请求
     
-    // 用于跟踪当前播放位置的原子变量
-    let current_samples = Arc::new(AtomicU64::new(0));
+    // 无缝播放的下一首曲目
+    let next_track: NextTrackSlot = Arc::new(Mutex::new(None));
+    
+    // A-B循环区间
+    let loop_range: LoopRange = Arc::new(Mutex::new(None));
     
     // 创建音频流的暂停/恢复控制
     let _stream_is_paused = is_paused.clone();
@@ -417,18 +384,21 @@ async fn run_audio_playback_with_file_and_state(
         total_duration,
         current_samples: 0,
         sample_rate: audio_sample_rate,
+        speed: speed.speed(),
     };
     
     // 发送初始状态
-    let _ = state_sender.send(initial_state);
+    let _ = event_sender.send(PlaybackEvent::State(initial_state));
     
     let playback_thread = {
-        let audio_buffer = audio_buffer.clone();
+        let writer = BufferWriter::new(audio_buffer.clone(), audio_file.info.sample_rate, audio_file.info.channels, equalizer.clone(), speed.clone(), resample_quality, output_rate.clone());
         let should_stop = should_stop.clone();
         let is_paused = is_paused.clone();
         let seek_target_ms = seek_target_ms.clone();
-        let current_samples = current_samples.clone();
-        let state_sender = state_sender.clone();
+        let next_track = next_track.clone();
+        let track_gain = replay_gain.gain_factor(&audio_file.info.metadata.replay_gain);
+        let event_sender = event_sender.clone();
+        let tracker = PositionTracker::new(clock.clone(), audio_file.info.channels, audio_sample_rate, writer.output_rate(), writer.speed(), total_duration, loop_range.clone());
         
         let target_channels = audio_file.info.channels;
         thread::spawn(move || {
@@ -436,62 +406,73 @@ async fn run_audio_playback_with_file_and_state(
                 probed,
                 track,
                 decoder, 
-                audio_buffer, 
+                writer,
                 should_stop, 
                 is_paused, 
                 seek_target_ms,
-                current_samples,
-                state_sender,
+                next_track,
+                replay_gain,
+                track_gain,
+                tracker,
+                event_sender,
                 target_channels,
                 audio_sample_rate,
-                total_duration,
             );
         })
     };
     
     // 处理播放控制命令
-    while let Some(command) = command_receiver.recv().await {
-        match command {
-            PlaybackCommand::Pause => {
-                is_paused.store(true, Ordering::Relaxed);
-            }
-            PlaybackCommand::Resume => {
-                is_paused.store(false, Ordering::Relaxed);
-            }
-            PlaybackCommand::Stop => {
-                should_stop.store(true, Ordering::Relaxed);
-                break;
-            }
-            PlaybackCommand::Seek(time_seconds) => {
-                let target_ms = (time_seconds * 1000.0) as u64;
-                seek_target_ms.store(target_ms, Ordering::Relaxed);
-                println!("Seek request: {:.2}s", time_seconds);
-            }
-        }
-    }
-    
-    should_stop.store(true, Ordering::Relaxed);
+    let mut control = PlaybackControl {
+        stream,
+        info: audio_file.info.clone(),
+        audio_buffer,
+        volume,
+        equalizer,
+        speed,
+        clock,
+        stream_error_sender,
+        is_paused,
+        should_stop,
+        seek_target_ms,
+        next_track,
+        loop_range,
+        output_rate,
+        event_sender,
+        device_lost: false,
+    };
+    control.run(command_receiver, stream_error_receiver).await;
     playback_thread.join().unwrap();
     
     Ok(())
 }
 
-/// 播放循环
-fn run_playback_loop(
+/// 播放循环（带状态更新）
+/// 
+/// 播放位置由 `PositionTracker` 根据音频回调实际消耗的样本数计算，
+/// 所有样本播放完毕后发送 `PlaybackEvent::EndOfStream`。
+fn run_playback_loop_with_state(
     probed: symphonia::core::probe::ProbeResult,
     track: symphonia::core::formats::Track,
     mut decoder: Box<dyn symphonia::core::codecs::Decoder>,
-    audio_buffer: AudioBuffer,
+    mut writer: BufferWriter,
     should_stop: Arc<AtomicBool>,
     is_paused: Arc<AtomicBool>,
     seek_target_ms: Arc<AtomicU64>,
+    next_track: NextTrackSlot,
+    replay_gain: ReplayGainSettings,
+    mut track_gain: f32,
+    mut tracker: PositionTracker,
+    event_sender: mpsc::UnboundedSender<PlaybackEvent>,
     target_channels: usize,
-    sample_rate: u32,
+    audio_sample_rate: u32,
 ) -> Result<()> {
     let mut format = probed.format;
-    let track_id = track.id;
+    let mut track_id = track.id;
+    let mut total_frames = track_total_frames(&track, Some(tracker.total_duration), audio_sample_rate);
+    let mut prepared_next: Option<PreparedTrack> = None;
+    let mut reached_end = false;
     
-    // 跟踪当前播放位置（以样本数计算）
+    // 跟踪解码位置（以帧计算），领先于实际播放位置
     let mut _current_samples: u64 = 0;
     
     loop {
@@ -499,21 +480,53 @@ fn run_playback_loop(
             break;
         }
         
+        // 输出设备采样率或播放速度变化后，缓冲区中的样本作废，从当前位置重新解码
+        if writer.output_rate_changed() || writer.speed_changed() {
+            let position_ms = (tracker.position_seconds() * 1000.0) as u64;
+            writer.apply_output_rate();
+            writer.apply_speed();
+            tracker.set_output_rate(writer.output_rate());
+            tracker.set_speed(writer.speed());
+            if seek_target_ms.load(Ordering::Relaxed) == u64::MAX {
+                seek_target_ms.store(position_ms, Ordering::Relaxed);
+            }
+        }
+        
         // 检查是否有跳转请求
         let seek_target = seek_target_ms.load(Ordering::Relaxed);
         if seek_target != u64::MAX {
+            reset_started_crossfade(&mut prepared_next, replay_gain, total_frames, audio_sample_rate, target_channels);
+            tracker.clear_buffer(writer.audio_buffer(), &event_sender);
             // 执行跳转操作
-            if let Err(e) = perform_seek(&mut format, &mut decoder, seek_target, sample_rate, track_id, &audio_buffer) {
-                eprintln!("Seek failed: {}", e);
-            } else {
-                // 跳转成功，更新当前样本位置
-                _current_samples = (seek_target * sample_rate as u64) / 1000;
-                println!("Seek completed to {:.2}s", seek_target as f64 / 1000.0);
+            match perform_seek(&mut format, &mut decoder, seek_target, audio_sample_rate, track_id, &mut writer, track_gain) {
+                Ok((preloaded_frames, written_frames)) => {
+                    // 跳转成功，更新解码位置（包括预加载的部分）
+                    _current_samples = (seek_target * audio_sample_rate as u64) / 1000 + preloaded_frames;
+                    tracker.record_written(written_frames);
+                    println!("Seek completed to {:.2}s", seek_target as f64 / 1000.0);
+                }
+                Err(e) => eprintln!("Seek failed: {}", e),
             }
+            tracker.sync_origin(writer.to_output_frames(_current_samples));
             // 清除跳转请求
             seek_target_ms.store(u64::MAX, Ordering::Relaxed);
         }
         
+        // 提前准备预排的下一首，避免在曲目结束时才打开文件
+        if let Some((audio_file, crossfade)) = next_track.lock().unwrap().take() {
+            prepared_next = prepare_next_track(audio_file, crossfade, replay_gain, total_frames, audio_sample_rate, target_channels);
+        }
+        
+        tracker.report(&event_sender, is_paused.load(Ordering::Relaxed));
+        
+        // A-B循环：播放到B点时丢弃缓冲区中B点之后的样本，从A点重新解码
+        if let Some(start) = tracker.loop_restart() {
+            if seek_target_ms.load(Ordering::Relaxed) == u64::MAX {
+                seek_target_ms.store((start * 1000.0) as u64, Ordering::Relaxed);
+                continue;
+            }
+        }
+        
         // 如果暂停，等待一小段时间后重新检查
         if is_paused.load(Ordering::Relaxed) {
             thread::sleep(Duration::from_millis(10));
@@ -524,26 +537,69 @@ fn run_playback_loop(
             Ok(packet) => packet,
             Err(symphonia::core::errors::Error::IoError(ref e)) 
                 if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                // 无缝播放：切换到已准备好的下一首，继续写入同一缓冲区
+                match prepared_next.take() {
+                    Some(next) => {
+                        // 交叉淡化期间已解码但未混音的部分直接输出
+                        let mut incoming: Vec<f32> = next.incoming.into();
+                        let leftover_frames = writer.write(&mut incoming);
+                        tracker.begin_next_track(
+                            leftover_frames,
+                            writer.to_output_frames(next.decoded_frames),
+                            next.audio_file.info.duration.unwrap_or(0.0),
+                            next.audio_file.file_path,
+                        );
+                        format = next.format;
+                        decoder = next.decoder;
+                        track_id = next.track_id;
+                        track_gain = next.gain;
+                        total_frames = next.total_frames;
+                        _current_samples = next.decoded_frames;
+                        continue;
+                    }
+                    None => {
+                        reached_end = true;
+                        break;
+                    }
+                }
+            }
+            Err(e) => {
+                // 无法继续读取时按流结束处理，让界面可以切换到下一首
+                eprintln!("Failed to read packet: {}", e);
+                reached_end = true;
                 break;
             }
-            Err(e) => return Err(PlayerError::DecodingError(e.to_string())),
         };
         
         if packet.track_id() != track_id {
             continue;
         }
         
-        let decoded = decoder.decode(&packet)
-            .map_err(|e| PlayerError::DecodingError(e.to_string()))?;
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(symphonia::core::errors::Error::DecodeError(e)) => {
+                // 单个损坏的数据包可以跳过
+                eprintln!("Decode error: {}", e);
+                continue;
+            }
+            Err(e) => return Err(PlayerError::DecodingError(e.to_string())),
+        };
+        
+        let mut samples = interleave_samples(&decoded, target_channels);
+        apply_gain(&mut samples, track_gain);
+        // 交叉淡化：进入淡化区间后混入下一首的开头
+        if let Some(next) = prepared_next.as_mut() {
+            next.mix_into(&mut samples, _current_samples, target_channels);
+        }
         
-        // 更新当前样本位置
+        // 更新解码位置
         _current_samples += decoded.frames() as u64;
         
-        write_audio_buffer(&audio_buffer, &decoded, target_channels)?;
+        tracker.record_written(writer.write(&mut samples));
         
         // 控制缓冲区大小
         {
-            let buffer = audio_buffer.lock().unwrap();
+            let buffer = writer.audio_buffer().lock().unwrap();
             if buffer.len() > BUFFER_CAPACITY_THRESHOLD {
                 drop(buffer);
                 thread::sleep(Duration::from_millis(BUFFER_WRITE_DELAY));
@@ -551,120 +607,473 @@ fn run_playback_loop(
         }
     }
     
+    if reached_end {
+        tracker.record_written(writer.finish());
+        // 等待缓冲区中剩余的样本播放完毕，再通知界面播放结束
+        while !should_stop.load(Ordering::Relaxed) {
+            tracker.report(&event_sender, is_paused.load(Ordering::Relaxed));
+            if tracker.is_drained() {
+                let _ = event_sender.send(PlaybackEvent::EndOfStream);
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+    
     Ok(())
 }
 
-/// 播放循环（带状态更新）
-fn run_playback_loop_with_state(
-    probed: symphonia::core::probe::ProbeResult,
-    track: symphonia::core::formats::Track,
-    mut decoder: Box<dyn symphonia::core::codecs::Decoder>,
+/// 解码后样本的输出阶段
+/// 
+/// 样本依次经过均衡器和变速处理；输出设备的采样率与文件不同时再转换采样率，最后写入音频缓冲区。
+struct BufferWriter {
     audio_buffer: AudioBuffer,
-    should_stop: Arc<AtomicBool>,
-    is_paused: Arc<AtomicBool>,
-    seek_target_ms: Arc<AtomicU64>,
-    current_samples_atomic: Arc<AtomicU64>,
-    state_sender: mpsc::UnboundedSender<PlaybackState>,
-    target_channels: usize,
-    audio_sample_rate: u32,
-    total_duration: f64,
-) -> Result<()> {
-    let mut format = probed.format;
-    let track_id = track.id;
+    equalizer: Equalizer,
+    equalizer_control: EqualizerControl,
+    /// 均衡器当前使用的增益版本
+    equalizer_version: u64,
+    /// 变速器，速度为1.0时不处理
+    stretcher: Option<TimeStretcher>,
+    speed_control: SpeedControl,
+    /// 当前变速使用的速度
+    speed: f32,
+    resampler: Option<Resampler>,
+    source_rate: u32,
+    channels: usize,
+    quality: ResampleQuality,
+    /// 输出设备的采样率（与控制线程共享）
+    shared_output_rate: Arc<AtomicU32>,
+    /// 当前转换使用的输出采样率
+    output_rate: u32,
+}
+
+impl BufferWriter {
+    fn new(
+        audio_buffer: AudioBuffer,
+        source_rate: u32,
+        channels: usize,
+        equalizer_control: EqualizerControl,
+        speed_control: SpeedControl,
+        quality: ResampleQuality,
+        shared_output_rate: Arc<AtomicU32>,
+    ) -> Self {
+        let mut writer = Self {
+            audio_buffer,
+            equalizer: Equalizer::new(source_rate, channels, equalizer_control.gains()),
+            equalizer_version: equalizer_control.version(),
+            equalizer_control,
+            stretcher: None,
+            speed_control,
+            speed: 1.0,
+            resampler: None,
+            source_rate,
+            channels,
+            quality,
+            shared_output_rate,
+            output_rate: source_rate,
+        };
+        writer.apply_output_rate();
+        writer.apply_speed();
+        writer
+    }
     
-    // 跟踪当前播放位置（以样本数计算）
-    let mut _current_samples: u64 = 0;
+    fn audio_buffer(&self) -> &AudioBuffer {
+        &self.audio_buffer
+    }
     
-    loop {
-        if should_stop.load(Ordering::Relaxed) {
-            break;
+    fn output_rate(&self) -> u32 {
+        self.output_rate
+    }
+    
+    /// 输出设备的采样率是否已变化
+    fn output_rate_changed(&self) -> bool {
+        self.shared_output_rate.load(Ordering::Relaxed) != self.output_rate
+    }
+    
+    /// 按输出设备当前的采样率重新创建采样率转换器
+    fn apply_output_rate(&mut self) {
+        self.output_rate = self.shared_output_rate.load(Ordering::Relaxed);
+        self.resampler = if self.output_rate != self.source_rate {
+            eprintln!("Resampling {} Hz -> {} Hz ({:?})", self.source_rate, self.output_rate, self.quality);
+            Some(Resampler::new(self.source_rate, self.output_rate, self.channels, self.quality))
+        } else {
+            None
+        };
+    }
+    
+    fn speed(&self) -> f32 {
+        self.speed
+    }
+    
+    /// 播放速度是否已变化
+    fn speed_changed(&self) -> bool {
+        self.speed_control.speed() != self.speed
+    }
+    
+    /// 按当前播放速度重新创建变速器
+    fn apply_speed(&mut self) {
+        self.speed = self.speed_control.speed();
+        self.stretcher = if self.speed != 1.0 {
+            Some(TimeStretcher::new(self.source_rate, self.channels, self.speed))
+        } else {
+            None
+        };
+    }
+    
+    /// 将源采样率下的帧数换算为输出采样率下（变速后）的帧数
+    fn to_output_frames(&self, source_frames: u64) -> u64 {
+        let frames = (source_frames as u128 * self.output_rate as u128 / self.source_rate.max(1) as u128) as f64;
+        (frames / self.speed as f64) as u64
+    }
+    
+    /// 清空缓冲区并丢弃均衡器和转换器中的历史样本（跳转时调用）
+    fn clear(&mut self) {
+        self.audio_buffer.lock().unwrap().clear();
+        self.equalizer.reset();
+        if let Some(stretcher) = self.stretcher.as_mut() {
+            stretcher.reset();
+        }
+        if let Some(resampler) = self.resampler.as_mut() {
+            resampler.reset();
         }
+    }
+    
+    /// 写入源采样率下的交错样本
+    /// 
+    /// # 返回
+    /// 写入缓冲区的帧数（输出采样率下）
+    fn write(&mut self, samples: &mut [f32]) -> u64 {
+        // 界面调整均衡器后立即使用新的增益
+        let version = self.equalizer_control.version();
+        if version != self.equalizer_version {
+            self.equalizer.set_gains(self.equalizer_control.gains());
+            self.equalizer_version = version;
+        }
+        self.equalizer.process(samples);
         
-        // 检查是否有跳转请求
-        let seek_target = seek_target_ms.load(Ordering::Relaxed);
-        if seek_target != u64::MAX {
-            // 执行跳转操作
-            if let Err(e) = perform_seek(&mut format, &mut decoder, seek_target, audio_sample_rate, track_id, &audio_buffer) {
-                eprintln!("Seek failed: {}", e);
-            } else {
-                // 跳转成功，更新当前样本位置
-                _current_samples = (seek_target * audio_sample_rate as u64) / 1000;
-                current_samples_atomic.store(_current_samples, Ordering::Relaxed);
-                println!("Seek completed to {:.2}s", seek_target as f64 / 1000.0);
+        match self.stretcher.as_mut() {
+            Some(stretcher) => {
+                let stretched = stretcher.process(samples);
+                self.write_output(&stretched)
             }
-            // 清除跳转请求
-            seek_target_ms.store(u64::MAX, Ordering::Relaxed);
+            None => self.write_output(samples),
         }
-        
-        // 如果暂停，等待一小段时间后重新检查
-        if is_paused.load(Ordering::Relaxed) {
-            thread::sleep(Duration::from_millis(10));
-            continue;
+    }
+    
+    /// 输出变速器和转换器中剩余的样本（曲目全部解码完后调用）
+    /// 
+    /// # 返回
+    /// 写入缓冲区的帧数（输出采样率下）
+    fn finish(&mut self) -> u64 {
+        let remaining = self.stretcher.as_mut().map(TimeStretcher::flush).unwrap_or_default();
+        let mut frames = self.write_output(&remaining);
+        if let Some(resampler) = self.resampler.as_mut() {
+            let output = resampler.flush();
+            frames += (output.len() / self.channels.max(1)) as u64;
+            self.audio_buffer.lock().unwrap().extend(output);
         }
-        
-        let packet = match format.next_packet() {
-            Ok(packet) => packet,
-            Err(symphonia::core::errors::Error::IoError(ref e)) 
-                if e.kind() == std::io::ErrorKind::UnexpectedEof => {
-                break;
+        frames
+    }
+    
+    /// 转换采样率后写入缓冲区
+    fn write_output(&mut self, samples: &[f32]) -> u64 {
+        let channels = self.channels.max(1);
+        match self.resampler.as_mut() {
+            Some(resampler) => {
+                let output = resampler.process(samples);
+                let frames = (output.len() / channels) as u64;
+                self.audio_buffer.lock().unwrap().extend(output);
+                frames
+            }
+            None => {
+                self.audio_buffer.lock().unwrap().extend(samples.iter().copied());
+                (samples.len() / channels) as u64
             }
-            Err(e) => return Err(PlayerError::DecodingError(e.to_string())),
-        };
-        
-        if packet.track_id() != track_id {
-            continue;
         }
+    }
+}
+
+/// 解码线程已切换、但尚未播放到的曲目
+struct PendingTrack {
+    /// 播放到该帧（写入帧序列中的位置）时切换到这首
+    switch_frame: u64,
+    /// 该曲目第0帧在写入帧序列中的位置
+    origin: i64,
+    total_duration: f64,
+    file_path: String,
+}
+
+/// 实际播放位置跟踪
+/// 
+/// 解码线程领先于音频回调写入样本，因此需要记录每首曲目在写入帧序列中的起点，
+/// 再结合音频回调实际消耗的帧数计算当前听到的位置。
+struct PositionTracker {
+    clock: PlaybackClock,
+    channels: usize,
+    /// 文件采样率
+    sample_rate: u32,
+    /// 缓冲区中样本的采样率（输出设备采样率），以下帧数均以此计
+    output_rate: u32,
+    /// 播放速度，每播放一帧曲目前进 `speed` 帧
+    speed: f32,
+    /// 已写入缓冲区的帧数
+    written_frames: u64,
+    /// 当前曲目第0帧在写入帧序列中的位置（跳转后可能为负）
+    origin: i64,
+    /// 当前曲目总时长（秒）
+    total_duration: f64,
+    pending: VecDeque<PendingTrack>,
+    last_report: Option<Instant>,
+    last_paused: bool,
+    /// A-B循环区间
+    loop_range: LoopRange,
+    /// 上次检查A-B循环时的播放位置（秒），用于判断是否刚越过B点
+    loop_check_position: f64,
+}
+
+impl PositionTracker {
+    fn new(
+        clock: PlaybackClock,
+        channels: usize,
+        sample_rate: u32,
+        output_rate: u32,
+        speed: f32,
+        total_duration: f64,
+        loop_range: LoopRange,
+    ) -> Self {
+        Self {
+            clock,
+            channels: channels.max(1),
+            sample_rate,
+            output_rate,
+            speed,
+            written_frames: 0,
+            origin: 0,
+            total_duration,
+            pending: VecDeque::new(),
+            last_report: None,
+            last_paused: false,
+            loop_range,
+            loop_check_position: 0.0,
+        }
+    }
+    
+    /// 音频回调已播放的帧数
+    fn played_frames(&self) -> u64 {
+        self.clock.consumed_samples() / self.channels as u64
+    }
+    
+    /// 记录写入缓冲区的帧数
+    fn record_written(&mut self, frames: u64) {
+        self.written_frames += frames;
+    }
+    
+    /// 所有写入的样本是否都已播放
+    fn is_drained(&self) -> bool {
+        self.played_frames() >= self.written_frames
+    }
+    
+    /// 当前曲目的播放位置（帧）
+    fn position_frames(&self) -> u64 {
+        (self.played_frames() as i64 - self.origin).max(0) as u64
+    }
+    
+    /// 当前曲目的播放位置（秒）
+    fn position_seconds(&self) -> f64 {
+        self.position_frames() as f64 * self.speed as f64 / self.output_rate.max(1) as f64
+    }
+    
+    /// 输出采样率变化（之后需要清空缓冲区并重新同步起点）
+    fn set_output_rate(&mut self, output_rate: u32) {
+        self.output_rate = output_rate;
+    }
+    
+    /// 播放速度变化（之后需要清空缓冲区并重新同步起点）
+    fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+    
+    /// 跳转前清空缓冲区
+    /// 
+    /// 持有缓冲区锁时音频回调无法取出样本，因此清空后已写入帧数与已播放帧数一致。
+    /// 如果解码线程已经切换到下一首，跳转作用于下一首，立即通知界面。
+    fn clear_buffer(&mut self, audio_buffer: &AudioBuffer, event_sender: &mpsc::UnboundedSender<PlaybackEvent>) {
+        {
+            let mut buffer = audio_buffer.lock().unwrap();
+            buffer.clear();
+            self.written_frames = self.played_frames();
+        }
+        // 跳转本身越过B点时不触发循环
+        self.loop_check_position = f64::INFINITY;
+        while let Some(pending) = self.pending.pop_front() {
+            self.total_duration = pending.total_duration;
+            let _ = event_sender.send(PlaybackEvent::TrackChanged(pending.file_path));
+        }
+    }
+    
+    /// 根据解码位置重新确定当前曲目的起点
+    fn sync_origin(&mut self, decode_position: u64) {
+        self.origin = self.written_frames as i64 - decode_position as i64;
+    }
+    
+    /// 解码线程切换到下一首
+    /// 
+    /// # 参数
+    /// * `leftover_frames` - 切换时直接写入的下一首帧数（交叉淡化后剩余部分）
+    /// * `decode_position` - 下一首已解码的帧数
+    /// * `total_duration` - 下一首总时长（秒）
+    /// * `file_path` - 下一首文件路径
+    fn begin_next_track(&mut self, leftover_frames: u64, decode_position: u64, total_duration: f64, file_path: String) {
+        let switch_frame = self.written_frames;
+        self.written_frames += leftover_frames;
+        self.pending.push_back(PendingTrack {
+            switch_frame,
+            origin: self.written_frames as i64 - decode_position as i64,
+            total_duration,
+            file_path,
+        });
+    }
+    
+    /// 播放位置刚越过B点时返回需要跳回的A点（秒）
+    /// 
+    /// 解码线程已切换到下一首时，跳转会作用于下一首，因此不触发循环。
+    fn loop_restart(&mut self) -> Option<f64> {
+        let position = self.position_seconds();
+        let previous = std::mem::replace(&mut self.loop_check_position, position);
+        let (start, end) = (*self.loop_range.lock().unwrap())?;
+        (self.pending.is_empty() && previous < end && position >= end).then_some(start)
+    }
+    
+    /// 发送曲目切换事件和定期的播放状态
+    fn report(&mut self, event_sender: &mpsc::UnboundedSender<PlaybackEvent>, is_paused: bool) {
+        use crate::config::ui::PROGRESS_UPDATE_INTERVAL;
         
-        let decoded = decoder.decode(&packet)
-            .map_err(|e| PlayerError::DecodingError(e.to_string()))?;
-        
-        // 更新当前样本位置
-        _current_samples += decoded.frames() as u64;
-        current_samples_atomic.store(_current_samples, Ordering::Relaxed);
-        
-        write_audio_buffer(&audio_buffer, &decoded, target_channels)?;
+        let played = self.played_frames();
+        let mut force = is_paused != self.last_paused;
+        while self.pending.front().is_some_and(|pending| played >= pending.switch_frame) {
+            let pending = self.pending.pop_front().unwrap();
+            self.origin = pending.origin;
+            self.total_duration = pending.total_duration;
+            let _ = event_sender.send(PlaybackEvent::TrackChanged(pending.file_path));
+            force = true;
+        }
         
-        // 控制缓冲区大小
-        {
-            let buffer = audio_buffer.lock().unwrap();
-            if buffer.len() > BUFFER_CAPACITY_THRESHOLD {
-                drop(buffer);
-                thread::sleep(Duration::from_millis(BUFFER_WRITE_DELAY));
-            }
+        let due = self.last_report
+            .is_none_or(|last| last.elapsed() >= Duration::from_millis(PROGRESS_UPDATE_INTERVAL));
+        if !force && !due {
+            return;
         }
+        
+        let position = self.position_seconds();
+        let state = PlaybackState {
+            is_playing: !is_paused,
+            is_paused,
+            current_time: position,
+            total_duration: self.total_duration,
+            current_samples: (position * self.sample_rate as f64) as u64,
+            sample_rate: self.sample_rate,
+            speed: self.speed,
+        };
+        let _ = event_sender.send(PlaybackEvent::State(state));
+        self.last_report = Some(Instant::now());
+        self.last_paused = is_paused;
+    }
+}
 
-        // 每隔一定数量的帧发送状态更新（避免过于频繁的更新）
-        if _current_samples % (audio_sample_rate as u64 / 10) == 0 { // 每100ms更新一次
-            let current_time = _current_samples as f64 / audio_sample_rate as f64;
-            let state = PlaybackState {
-                is_playing: !is_paused.load(Ordering::Relaxed),
-                is_paused: is_paused.load(Ordering::Relaxed),
-                current_time,
-                total_duration,
-                current_samples: _current_samples,
-                sample_rate: audio_sample_rate,
-            };
-            let _ = state_sender.send(state);
-        }
+/// 为无缝播放或交叉淡化准备下一首曲目
+/// 
+/// 下一首与当前音频流的采样率和声道数一致时才能直接衔接，
+/// 否则返回None，由当前曲目正常结束后重新建立播放会话。
+/// 
+/// # 参数
+/// * `audio_file` - 下一首曲目
+/// * `crossfade_duration` - 交叉淡化时长（秒），0表示直接衔接
+/// * `replay_gain` - ReplayGain设置
+/// * `current_total_frames` - 当前曲目的总帧数，未知时无法交叉淡化
+/// * `sample_rate` - 当前音频流的采样率
+/// * `channels` - 当前音频流的声道数
+fn prepare_next_track(
+    audio_file: AudioFile,
+    crossfade_duration: f64,
+    replay_gain: ReplayGainSettings,
+    current_total_frames: Option<u64>,
+    sample_rate: u32,
+    channels: usize,
+) -> Option<PreparedTrack> {
+    if audio_file.info.sample_rate != sample_rate || audio_file.info.channels != channels {
+        eprintln!(
+            "Gapless playback unavailable for {}: {}Hz/{}ch differs from {}Hz/{}ch",
+            audio_file.file_path, audio_file.info.sample_rate, audio_file.info.channels, sample_rate, channels
+        );
+        return None;
     }
     
-    Ok(())
+    let prepared = audio_file.create_playback_context().and_then(|(probed, track)| {
+        let decoder = create_decoder(&track)?;
+        Ok((probed.format, decoder, track))
+    });
+    
+    match prepared {
+        Ok((format, decoder, track)) => {
+            let crossfade = current_total_frames
+                .and_then(|frames| Crossfade::new(frames, crossfade_duration, sample_rate));
+            let gain = replay_gain.gain_factor(&audio_file.info.metadata.replay_gain);
+            Some(PreparedTrack {
+                total_frames: track_total_frames(&track, audio_file.info.duration, sample_rate),
+                audio_file,
+                format,
+                decoder,
+                track_id: track.id,
+                gain,
+                crossfade_duration,
+                crossfade,
+                incoming: VecDeque::new(),
+                decoded_frames: 0,
+                exhausted: false,
+            })
+        }
+        Err(e) => {
+            eprintln!("Failed to prepare next track {}: {}", audio_file.file_path, e);
+            None
+        }
+    }
+}
+
+/// 跳转时如果交叉淡化已经开始，重新准备下一首使其从头开始
+fn reset_started_crossfade(
+    prepared_next: &mut Option<PreparedTrack>,
+    replay_gain: ReplayGainSettings,
+    current_total_frames: Option<u64>,
+    sample_rate: u32,
+    channels: usize,
+) {
+    if prepared_next.as_ref().is_some_and(|next| next.has_started()) {
+        let next = prepared_next.take().unwrap();
+        *prepared_next = prepare_next_track(next.audio_file, next.crossfade_duration, replay_gain, current_total_frames, sample_rate, channels);
+    }
+}
+
+/// 获取曲目的总帧数，编码参数中没有时按时长估算
+fn track_total_frames(track: &symphonia::core::formats::Track, duration: Option<f64>, sample_rate: u32) -> Option<u64> {
+    track.codec_params.n_frames
+        .or_else(|| duration.filter(|d| *d > 0.0).map(|d| (d * sample_rate as f64) as u64))
 }
 
 /// 执行音频跳转操作
+/// 
+/// # 返回
+/// 成功时返回（跳转后预加载解码的帧数, 写入缓冲区的帧数）
 fn perform_seek(
     format: &mut Box<dyn symphonia::core::formats::FormatReader>,
     decoder: &mut Box<dyn symphonia::core::codecs::Decoder>,
     target_time_ms: u64,
     sample_rate: u32,
     track_id: u32,
-    audio_buffer: &AudioBuffer,
-) -> Result<()> {
+    writer: &mut BufferWriter,
+    gain: f32,
+) -> Result<(u64, u64)> {
     // 清空音频缓冲区
-    {
-        let mut buffer = audio_buffer.lock().unwrap();
-        buffer.clear();
-    }
+    writer.clear();
     
     // 计算目标时间戳（以timebase为单位）
     // 对于大多数格式，timebase通常是样本率
@@ -682,17 +1091,22 @@ fn perform_seek(
             decoder.reset();
             
             // 预加载一些数据到缓冲区
+            let mut preloaded_frames = 0;
+            let mut written_frames = 0;
             for _ in 0..5 { // 预加载几个包
                 if let Ok(packet) = format.next_packet() {
                     if packet.track_id() == track_id {
                         if let Ok(decoded) = decoder.decode(&packet) {
-                            let _ = write_audio_buffer(audio_buffer, &decoded, decoded.spec().channels.count());
+                            let mut samples = interleave_samples(&decoded, decoded.spec().channels.count());
+                            apply_gain(&mut samples, gain);
+                            written_frames += writer.write(&mut samples);
+                            preloaded_frames += decoded.frames() as u64;
                         }
                     }
                 }
             }
             
-            Ok(())
+            Ok((preloaded_frames, written_frames))
         }
         Err(e) => {
             Err(PlayerError::PlaybackError(format!("Seek failed: {}", e)))
@@ -700,18 +1114,255 @@ fn perform_seek(
     }
 }
 
+/// 播放控制循环的输入
+enum ControlInput {
+    /// 来自界面的播放命令
+    Command(PlaybackCommand),
+    /// 音频流报告的错误
+    StreamError(cpal::StreamError),
+}
+
+/// 等待下一个播放命令或音频流错误
+/// 
+/// # 返回
+/// 命令发送器全部关闭时返回None
+async fn recv_control_input(
+    command_receiver: &mut mpsc::UnboundedReceiver<PlaybackCommand>,
+    stream_error_receiver: &mut mpsc::UnboundedReceiver<cpal::StreamError>,
+) -> Option<ControlInput> {
+    tokio::select! {
+        command = command_receiver.recv() => command.map(ControlInput::Command),
+        Some(error) = stream_error_receiver.recv() => Some(ControlInput::StreamError(error)),
+    }
+}
+
+/// 播放控制任务持有的会话状态
+///
+/// 各播放入口共用，统一处理界面发来的命令和音频流报告的错误。
+struct PlaybackControl {
+    stream: cpal::Stream,
+    info: AudioInfo,
+    audio_buffer: AudioBuffer,
+    volume: VolumeControl,
+    equalizer: EqualizerControl,
+    speed: SpeedControl,
+    clock: PlaybackClock,
+    stream_error_sender: StreamErrorSender,
+    is_paused: Arc<AtomicBool>,
+    should_stop: Arc<AtomicBool>,
+    seek_target_ms: Arc<AtomicU64>,
+    next_track: NextTrackSlot,
+    /// A-B循环区间
+    loop_range: LoopRange,
+    /// 输出设备的采样率，切换设备后可能变化
+    output_rate: Arc<AtomicU32>,
+    /// 播放事件发送器
+    event_sender: mpsc::UnboundedSender<PlaybackEvent>,
+    /// 输出设备已不可用且未能切换到默认设备
+    device_lost: bool,
+}
+
+impl PlaybackControl {
+    /// 处理播放控制命令，直到收到停止命令或命令发送器全部关闭
+    async fn run(
+        &mut self,
+        mut command_receiver: mpsc::UnboundedReceiver<PlaybackCommand>,
+        mut stream_error_receiver: mpsc::UnboundedReceiver<cpal::StreamError>,
+    ) {
+        while let Some(input) = recv_control_input(&mut command_receiver, &mut stream_error_receiver).await {
+            if !self.handle_control_input(input, &mut stream_error_receiver) {
+                break;
+            }
+        }
+        self.should_stop.store(true, Ordering::Relaxed);
+    }
+
+    /// 处理一个播放命令或音频流错误
+    ///
+    /// # 参数
+    /// * `input` - 播放命令或音频流错误
+    /// * `stream_error_receiver` - 音频流错误接收器，切换到默认设备后丢弃旧音频流残留的错误
+    ///
+    /// # 返回
+    /// 收到停止命令时返回false
+    fn handle_control_input(
+        &mut self,
+        input: ControlInput,
+        stream_error_receiver: &mut mpsc::UnboundedReceiver<cpal::StreamError>,
+    ) -> bool {
+        let command = match input {
+            ControlInput::Command(command) => command,
+            ControlInput::StreamError(cpal::StreamError::DeviceNotAvailable) => {
+                self.report_device_error(cpal::StreamError::DeviceNotAvailable.to_string());
+                // 回退到默认设备，缓冲区和播放时钟不变，从原位置继续播放
+                self.fall_back_to_default_device();
+                // 丢弃旧音频流残留的错误
+                while stream_error_receiver.try_recv().is_ok() {}
+                return true;
+            }
+            // 其他错误（如后端报告的欠载）不影响设备，继续使用当前音频流
+            ControlInput::StreamError(_) => return true,
+        };
+
+        match command {
+            PlaybackCommand::Pause => {
+                self.is_paused.store(true, Ordering::Relaxed);
+                // 暂停音频流，使播放位置停止前进
+                if let Err(e) = self.stream.pause() {
+                    eprintln!("Failed to pause stream: {}", e);
+                }
+            }
+            PlaybackCommand::Resume => {
+                // 设备不可用时先重试打开默认设备，仍然失败则保持暂停
+                if self.device_lost {
+                    self.fall_back_to_default_device();
+                    if self.device_lost {
+                        return true;
+                    }
+                }
+                self.is_paused.store(false, Ordering::Relaxed);
+                // 恢复音频流
+                if let Err(e) = self.stream.play() {
+                    eprintln!("Failed to resume stream: {}", e);
+                }
+            }
+            PlaybackCommand::Stop => {
+                self.should_stop.store(true, Ordering::Relaxed);
+                return false;
+            }
+            PlaybackCommand::Seek(target_time) => {
+                // 将跳转目标时间转换为毫秒并存储
+                let target_ms = (target_time * 1000.0) as u64;
+                self.seek_target_ms.store(target_ms, Ordering::Relaxed);
+                println!("Seek request: {:.2}s", target_time);
+            }
+            PlaybackCommand::SetVolume(level) => {
+                self.volume.set_volume(level);
+            }
+            PlaybackCommand::SetMuted(muted) => {
+                self.volume.set_muted(muted);
+            }
+            PlaybackCommand::SetEqualizer(gains) => {
+                self.equalizer.set_gains(gains);
+            }
+            PlaybackCommand::SetSpeed(level) => {
+                self.speed.set_speed(level);
+            }
+            PlaybackCommand::SetLoop(range) => {
+                *self.loop_range.lock().unwrap() = range.filter(|(start, end)| end > start);
+            }
+            PlaybackCommand::QueueNext(audio_file, crossfade) => {
+                *self.next_track.lock().unwrap() = Some((*audio_file, crossfade));
+            }
+            PlaybackCommand::ClearNext => {
+                *self.next_track.lock().unwrap() = None;
+            }
+            PlaybackCommand::SetDevice(device_name) => {
+                if let Err(e) = self.switch_device(device_name.as_deref()) {
+                    eprintln!("Failed to switch output device: {}", e);
+                }
+            }
+        }
+        true
+    }
+
+    /// 切换到指定设备上的新音频流
+    ///
+    /// 新音频流共享同一缓冲区和播放时钟，播放位置保持不变。
+    ///
+    /// # 参数
+    /// * `device_name` - 输出设备名称，None表示使用默认设备
+    fn switch_device(&mut self, device_name: Option<&str>) -> Result<()> {
+        let (new_stream, rate) = open_output_stream(device_name, &self.info, &self.audio_buffer, &self.volume, &self.clock, &self.stream_error_sender)?;
+        switch_output_stream(&mut self.stream, new_stream, self.is_paused.load(Ordering::Relaxed));
+        self.output_rate.store(rate, Ordering::Relaxed);
+        self.device_lost = false;
+        Ok(())
+    }
+
+    /// 切换到默认设备
+    ///
+    /// 默认设备也无法打开时暂停播放并通知界面，使播放位置停在原处，恢复播放时重试。
+    fn fall_back_to_default_device(&mut self) {
+        if let Err(e) = self.switch_device(None) {
+            eprintln!("Failed to fall back to default device: {}", e);
+            self.device_lost = true;
+            self.is_paused.store(true, Ordering::Relaxed);
+            if let Err(e) = self.stream.pause() {
+                eprintln!("Failed to pause stream: {}", e);
+            }
+            self.report_device_error(format!("Failed to fall back to default device: {}", e));
+        }
+    }
+
+    /// 向界面报告输出设备错误
+    fn report_device_error(&self, error: String) {
+        let _ = self.event_sender.send(PlaybackEvent::DeviceError(error));
+    }
+}
+
+/// 在指定设备上创建音频流
+/// 
+/// # 参数
+/// * `device_name` - 输出设备名称，None表示使用默认设备
+/// * `info` - 音频信息（采样率和声道数）
+/// * `audio_buffer` - 音频缓冲区
+/// * `volume` - 音量控制
+/// * `clock` - 播放时钟
+/// * `error_sender` - 音频流错误发送器
+/// 
+/// # 返回
+/// 成功时返回音频流及其采样率，失败时返回错误
+fn open_output_stream(
+    device_name: Option<&str>,
+    info: &AudioInfo,
+    audio_buffer: &AudioBuffer,
+    volume: &VolumeControl,
+    clock: &PlaybackClock,
+    error_sender: &StreamErrorSender,
+) -> Result<(cpal::Stream, u32)> {
+    let (device, config, sample_format) = setup_audio_device(device_name, info.sample_rate, info.channels)?;
+    let stream = create_audio_stream(&device, &config, sample_format, audio_buffer.clone(), info.channels, volume.clone(), clock.clone(), error_sender.clone())?;
+    Ok((stream, config.sample_rate.0))
+}
+
+/// 用新设备上的音频流替换当前音频流
+/// 
+/// 先暂停旧音频流，避免两个音频回调同时从缓冲区取样本。
+fn switch_output_stream(stream: &mut cpal::Stream, new_stream: cpal::Stream, is_paused: bool) {
+    if let Err(e) = stream.pause() {
+        eprintln!("Failed to pause stream: {}", e);
+    }
+    if is_paused {
+        if let Err(e) = new_stream.pause() {
+            eprintln!("Failed to pause stream: {}", e);
+        }
+    } else if let Err(e) = new_stream.play() {
+        eprintln!("Failed to start stream on new device: {}", e);
+    }
+    *stream = new_stream;
+}
+
 /// 计算缓冲区大小
 fn calculate_buffer_size(sample_rate: u32, channels: usize) -> usize {
     use crate::config::DEFAULT_BUFFER_MULTIPLIER;
     sample_rate as usize * channels * DEFAULT_BUFFER_MULTIPLIER
 }
 
-/// 写入音频缓冲区
-fn write_audio_buffer(
-    buffer: &AudioBuffer, 
+/// 对样本应用线性增益（ReplayGain）
+fn apply_gain(samples: &mut [f32], gain: f32) {
+    if gain != 1.0 {
+        for sample in samples.iter_mut() {
+            *sample *= gain;
+        }
+    }
+}
+
+/// 将解码后的音频转换为交错的f32样本
+fn interleave_samples(
     decoded: &symphonia::core::audio::AudioBufferRef, 
     target_channels: usize
-) -> Result<()> {
+) -> Vec<f32> {
     let mut interleaved = Vec::new();
     let mut channel_data = vec![Vec::new(); target_channels];
     
@@ -730,10 +1381,7 @@ fn write_audio_buffer(
         }
     }
     
-    let mut audio_buffer = buffer.lock().unwrap();
-    audio_buffer.extend(interleaved);
-    
-    Ok(())
+    interleaved
 }
 
 /// 转换音频样本
@@ -0,0 +1,164 @@
+//! ReplayGain响度标准化模块
+//!
+//! 解析ReplayGain标签并根据配置计算应用到样本上的线性增益。
+
+use crate::config::ReplayGainMode;
+
+/// 曲目中的ReplayGain标签值
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReplayGain {
+    /// 曲目增益（dB）
+    pub track_gain: Option<f32>,
+    /// 曲目峰值（线性，1.0为满幅）
+    pub track_peak: Option<f32>,
+    /// 专辑增益（dB）
+    pub album_gain: Option<f32>,
+    /// 专辑峰值（线性，1.0为满幅）
+    pub album_peak: Option<f32>,
+}
+
+impl ReplayGain {
+    /// 尝试从标签中读取ReplayGain值
+    ///
+    /// # 参数
+    /// * `key` - 标签名（如 `REPLAYGAIN_TRACK_GAIN`，不区分大小写）
+    /// * `value` - 标签值
+    ///
+    /// # 返回
+    /// 标签属于ReplayGain并解析成功时返回true
+    pub fn apply_tag(&mut self, key: &str, value: &str) -> bool {
+        let key = key.to_uppercase();
+        // ID3v2的TXXX帧可能带有前缀，只匹配结尾部分
+        if key.ends_with("REPLAYGAIN_TRACK_GAIN") {
+            self.track_gain = parse_gain(value);
+            self.track_gain.is_some()
+        } else if key.ends_with("REPLAYGAIN_TRACK_PEAK") {
+            self.track_peak = parse_peak(value);
+            self.track_peak.is_some()
+        } else if key.ends_with("REPLAYGAIN_ALBUM_GAIN") {
+            self.album_gain = parse_gain(value);
+            self.album_gain.is_some()
+        } else if key.ends_with("REPLAYGAIN_ALBUM_PEAK") {
+            self.album_peak = parse_peak(value);
+            self.album_peak.is_some()
+        } else {
+            false
+        }
+    }
+
+    /// 是否包含任何增益信息
+    pub fn is_empty(&self) -> bool {
+        self.track_gain.is_none() && self.album_gain.is_none()
+    }
+
+    /// 计算线性增益
+    ///
+    /// 专辑模式缺少专辑增益时回退到曲目增益，反之亦然；没有任何增益标签时不做调整。
+    /// 如果增益后峰值会超过满幅，则降低增益以避免削波。
+    ///
+    /// # 参数
+    /// * `mode` - ReplayGain模式
+    /// * `preamp_db` - 前置放大（dB）
+    ///
+    /// # 返回
+    /// 应用到样本上的线性增益
+    pub fn gain_factor(&self, mode: ReplayGainMode, preamp_db: f32) -> f32 {
+        let (gain, peak) = match mode {
+            ReplayGainMode::Off => return 1.0,
+            ReplayGainMode::Track => (
+                self.track_gain.or(self.album_gain),
+                self.track_peak.or(self.album_peak),
+            ),
+            ReplayGainMode::Album => (
+                self.album_gain.or(self.track_gain),
+                self.album_peak.or(self.track_peak),
+            ),
+        };
+
+        let Some(gain) = gain else {
+            return 1.0;
+        };
+
+        let factor = 10f32.powf((gain + preamp_db) / 20.0);
+        match peak {
+            Some(peak) if peak > 0.0 && factor * peak > 1.0 => 1.0 / peak,
+            _ => factor,
+        }
+    }
+}
+
+/// ReplayGain播放设置
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReplayGainSettings {
+    /// 模式
+    pub mode: ReplayGainMode,
+    /// 前置放大（dB）
+    pub preamp_db: f32,
+}
+
+impl ReplayGainSettings {
+    /// 计算指定曲目的线性增益
+    pub fn gain_factor(&self, replay_gain: &ReplayGain) -> f32 {
+        replay_gain.gain_factor(self.mode, self.preamp_db)
+    }
+}
+
+/// 解析增益值，如 "-6.54 dB"
+fn parse_gain(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let number = value
+        .strip_suffix("dB")
+        .or_else(|| value.strip_suffix("db"))
+        .or_else(|| value.strip_suffix("DB"))
+        .unwrap_or(value);
+    number.trim().parse::<f32>().ok().filter(|g| g.is_finite())
+}
+
+/// 解析峰值，如 "0.988553"
+fn parse_peak(value: &str) -> Option<f32> {
+    value.trim().parse::<f32>().ok().filter(|p| p.is_finite() && *p >= 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_tag() {
+        let mut rg = ReplayGain::default();
+        assert!(rg.apply_tag("REPLAYGAIN_TRACK_GAIN", "-6.54 dB"));
+        assert!(rg.apply_tag("replaygain_track_peak", "0.988553"));
+        assert!(rg.apply_tag("TXXX:REPLAYGAIN_ALBUM_GAIN", "+1.20 dB"));
+        assert!(!rg.apply_tag("REPLAYGAIN_ALBUM_PEAK", "n/a"));
+        assert!(!rg.apply_tag("ARTIST", "someone"));
+        assert_eq!(rg.track_gain, Some(-6.54));
+        assert_eq!(rg.track_peak, Some(0.988553));
+        assert_eq!(rg.album_gain, Some(1.2));
+        assert_eq!(rg.album_peak, None);
+    }
+
+    #[test]
+    fn test_gain_factor() {
+        let rg = ReplayGain {
+            track_gain: Some(-6.0),
+            track_peak: Some(0.5),
+            album_gain: Some(-20.0),
+            album_peak: None,
+        };
+        assert_eq!(rg.gain_factor(ReplayGainMode::Off, 0.0), 1.0);
+        assert!((rg.gain_factor(ReplayGainMode::Track, 0.0) - 0.501).abs() < 0.001);
+        assert!((rg.gain_factor(ReplayGainMode::Album, 0.0) - 0.1).abs() < 0.001);
+        // 没有增益标签时不调整
+        assert_eq!(ReplayGain::default().gain_factor(ReplayGainMode::Track, 6.0), 1.0);
+    }
+
+    #[test]
+    fn test_peak_prevents_clipping() {
+        let rg = ReplayGain {
+            track_gain: Some(12.0),
+            track_peak: Some(0.8),
+            ..Default::default()
+        };
+        assert!((rg.gain_factor(ReplayGainMode::Track, 0.0) - 1.25).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,291 @@
+//! 采样率转换模块
+//!
+//! 在解码器和音频缓冲区之间把样本转换为输出设备的采样率，
+//! 支持快速的线性插值和带限的加窗sinc插值两种模式。
+
+use std::f64::consts::PI;
+
+use crate::config::ResampleQuality;
+
+/// sinc模式在不降低截止频率时单侧的抽头数
+const SINC_HALF_TAPS: usize = 16;
+
+/// sinc核的相位表精度
+const SINC_PHASES: usize = 256;
+
+/// 截止频率相对于奈奎斯特频率的比例，留出过渡带
+const SINC_CUTOFF: f64 = 0.95;
+
+/// 流式采样率转换器
+///
+/// 输入输出均为交错样本。转换器在多次调用之间保留历史样本，
+/// 因此可以逐包处理解码结果而不会在包边界产生断点。
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    /// 输入采样率
+    from_rate: u32,
+    /// 输出采样率
+    to_rate: u32,
+    /// 声道数
+    channels: usize,
+    /// 每个输出帧在输入中前进的帧数
+    step: f64,
+    /// 插值核单侧的抽头数
+    half_taps: usize,
+    /// sinc核相位表，线性模式为空
+    table: Vec<f32>,
+    /// 尚未丢弃的输入样本（交错）
+    history: Vec<f32>,
+    /// 下一个输出帧在 `history` 中的位置（帧）
+    position: f64,
+}
+
+impl Resampler {
+    /// 创建采样率转换器
+    ///
+    /// # 参数
+    /// * `from_rate` - 输入采样率
+    /// * `to_rate` - 输出采样率
+    /// * `channels` - 声道数
+    /// * `quality` - 插值模式
+    pub fn new(from_rate: u32, to_rate: u32, channels: usize, quality: ResampleQuality) -> Self {
+        let step = from_rate as f64 / to_rate as f64;
+        let (half_taps, table) = match quality {
+            ResampleQuality::Linear => (1, Vec::new()),
+            ResampleQuality::Sinc => {
+                // 降采样时降低截止频率并相应加宽核，避免混叠
+                let cutoff = SINC_CUTOFF * (1.0 / step).min(1.0);
+                let half_taps = (SINC_HALF_TAPS as f64 / (cutoff / SINC_CUTOFF)).ceil() as usize;
+                (half_taps, build_sinc_table(half_taps, cutoff))
+            }
+        };
+
+        let mut resampler = Self {
+            from_rate,
+            to_rate,
+            channels: channels.max(1),
+            step,
+            half_taps,
+            table,
+            history: Vec::new(),
+            position: 0.0,
+        };
+        resampler.reset();
+        resampler
+    }
+
+    /// 输入采样率
+    pub fn from_rate(&self) -> u32 {
+        self.from_rate
+    }
+
+    /// 输出采样率
+    pub fn to_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    /// 清除历史样本（跳转后调用）
+    pub fn reset(&mut self) {
+        // 在开头补零，使第一个输出帧与第一个输入帧对齐
+        self.history.clear();
+        self.history.resize(self.half_taps * self.channels, 0.0);
+        self.position = self.half_taps as f64;
+    }
+
+    /// 转换一段交错样本
+    ///
+    /// 由于插值需要之后的样本，输出会比输入延迟少量帧，剩余部分在 `flush` 时输出。
+    ///
+    /// # 参数
+    /// * `input` - 输入采样率下的交错样本
+    ///
+    /// # 返回
+    /// 输出采样率下的交错样本
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        self.history.extend_from_slice(input);
+        let channels = self.channels;
+        let frames = self.history.len() / channels;
+        let estimated = ((frames as f64 - self.position) / self.step).max(0.0) as usize + 1;
+        let mut output = Vec::with_capacity(estimated * channels);
+        let mut weights = vec![0.0f32; self.half_taps * 2];
+
+        loop {
+            let base = self.position.floor() as usize;
+            if base + self.half_taps >= frames {
+                break;
+            }
+            let frac = self.position - base as f64;
+            self.fill_weights(frac, &mut weights);
+
+            // 参与插值的输入帧为 base - half_taps + 1 ..= base + half_taps
+            let first = base + 1 - self.half_taps;
+            for channel in 0..channels {
+                let mut sum = 0.0;
+                for (tap, weight) in weights.iter().enumerate() {
+                    sum += self.history[(first + tap) * channels + channel] * weight;
+                }
+                output.push(sum);
+            }
+            self.position += self.step;
+        }
+
+        // 丢弃之后不再需要的输入帧
+        let drop_frames = (self.position.floor() as usize).saturating_sub(self.half_taps).min(frames);
+        self.history.drain(..drop_frames * channels);
+        self.position -= drop_frames as f64;
+
+        output
+    }
+
+    /// 输出剩余的样本（输入结束时调用）
+    pub fn flush(&mut self) -> Vec<f32> {
+        let padding = vec![0.0; self.half_taps * self.channels];
+        let output = self.process(&padding);
+        self.reset();
+        output
+    }
+
+    /// 计算小数位置 `frac` 处各抽头的权重
+    fn fill_weights(&self, frac: f64, weights: &mut [f32]) {
+        if self.table.is_empty() {
+            // 线性插值
+            weights[0] = (1.0 - frac) as f32;
+            weights[1] = frac as f32;
+            return;
+        }
+
+        // 在相邻两个相位之间线性插值
+        let taps = self.half_taps * 2;
+        let phase = frac * SINC_PHASES as f64;
+        let index = (phase.floor() as usize).min(SINC_PHASES - 1);
+        let t = (phase - index as f64) as f32;
+        let a = &self.table[index * taps..(index + 1) * taps];
+        let b = &self.table[(index + 1) * taps..(index + 2) * taps];
+        for (tap, weight) in weights.iter_mut().enumerate() {
+            *weight = a[tap] + (b[tap] - a[tap]) * t;
+        }
+    }
+}
+
+/// 构建加窗sinc核的相位表
+///
+/// 第 `p` 行对应小数位置 `p / SINC_PHASES`，第 `j` 列对应输入帧 `base - half_taps + 1 + j`。
+fn build_sinc_table(half_taps: usize, cutoff: f64) -> Vec<f32> {
+    let taps = half_taps * 2;
+    let mut table = Vec::with_capacity((SINC_PHASES + 1) * taps);
+    for phase in 0..=SINC_PHASES {
+        let frac = phase as f64 / SINC_PHASES as f64;
+        for tap in 0..taps {
+            // 输出位置到该输入帧的距离
+            let distance = frac + (half_taps as f64 - 1.0) - tap as f64;
+            table.push((cutoff * sinc(cutoff * distance) * blackman(distance / half_taps as f64)) as f32);
+        }
+    }
+    table
+}
+
+/// 归一化sinc函数
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Blackman窗，`x` 的取值范围为 -1.0 - 1.0
+fn blackman(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    let t = (x + 1.0) / 2.0;
+    0.42 - 0.5 * (2.0 * PI * t).cos() + 0.08 * (4.0 * PI * t).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 生成单声道正弦波
+    fn sine(frequency: f64, sample_rate: u32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (2.0 * PI * frequency * i as f64 / sample_rate as f64).sin() as f32)
+            .collect()
+    }
+
+    /// 用Goertzel算法计算指定频率的幅度（满幅正弦约为1.0）
+    fn magnitude(samples: &[f32], frequency: f64, sample_rate: u32) -> f64 {
+        let coeff = 2.0 * (2.0 * PI * frequency / sample_rate as f64).cos();
+        let (mut s1, mut s2) = (0.0, 0.0);
+        for &x in samples {
+            let s0 = x as f64 + coeff * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        let power = s1 * s1 + s2 * s2 - coeff * s1 * s2;
+        2.0 * power.max(0.0).sqrt() / samples.len() as f64
+    }
+
+    /// 分块处理，模拟逐包解码
+    fn resample_in_chunks(resampler: &mut Resampler, input: &[f32], chunk: usize) -> Vec<f32> {
+        let mut output = Vec::new();
+        for part in input.chunks(chunk) {
+            output.extend(resampler.process(part));
+        }
+        output.extend(resampler.flush());
+        output
+    }
+
+    #[test]
+    fn test_output_length() {
+        for quality in [ResampleQuality::Linear, ResampleQuality::Sinc] {
+            let input = sine(1000.0, 44100, 44100);
+            let mut resampler = Resampler::new(44100, 48000, 1, quality);
+            let output = resample_in_chunks(&mut resampler, &input, 1152);
+            assert!((output.len() as i64 - 48000).abs() <= 2, "{:?}: {}", quality, output.len());
+
+            let input = sine(1000.0, 96000, 96000);
+            let mut resampler = Resampler::new(96000, 48000, 1, quality);
+            let output = resample_in_chunks(&mut resampler, &input, 4096);
+            assert!((output.len() as i64 - 48000).abs() <= 2, "{:?}: {}", quality, output.len());
+        }
+    }
+
+    #[test]
+    fn test_preserves_frequency() {
+        for quality in [ResampleQuality::Linear, ResampleQuality::Sinc] {
+            let input = sine(1000.0, 96000, 96000);
+            let mut resampler = Resampler::new(96000, 48000, 1, quality);
+            let output = resample_in_chunks(&mut resampler, &input, 1000);
+            // 跳过开头的过渡部分
+            let steady = &output[1000..45000];
+            let target = magnitude(steady, 1000.0, 48000);
+            assert!(target > 0.95, "{:?}: {}", quality, target);
+            // 音高正确时，相邻频率几乎没有能量
+            assert!(magnitude(steady, 1100.0, 48000) < 0.01);
+            assert!(magnitude(steady, 2000.0, 48000) < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_sinc_rejects_aliasing() {
+        // 30 kHz 超过 48 kHz 的奈奎斯特频率，降采样后应被滤除，否则会混叠到 18 kHz
+        let input = sine(30000.0, 96000, 96000);
+        let mut resampler = Resampler::new(96000, 48000, 1, ResampleQuality::Sinc);
+        let output = resample_in_chunks(&mut resampler, &input, 1000);
+        let steady = &output[1000..45000];
+        assert!(magnitude(steady, 18000.0, 48000) < 0.01);
+    }
+
+    #[test]
+    fn test_interleaved_channels() {
+        // 左声道为正弦，右声道为静音
+        let left = sine(440.0, 44100, 4410);
+        let input: Vec<f32> = left.iter().flat_map(|&l| [l, 0.0]).collect();
+        let mut resampler = Resampler::new(44100, 48000, 2, ResampleQuality::Sinc);
+        let output = resample_in_chunks(&mut resampler, &input, 512);
+        assert_eq!(output.len() % 2, 0);
+        assert!(output.iter().skip(1).step_by(2).all(|&r| r.abs() < 1e-6));
+        assert!(output.iter().step_by(2).any(|&l| l.abs() > 0.9));
+    }
+}
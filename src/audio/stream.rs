@@ -8,8 +8,16 @@ use cpal::{
     FromSample,
 };
 
+use tokio::sync::mpsc;
+
 use crate::error::{PlayerError, Result};
 use super::playback::AudioBuffer;
+use super::{PlaybackClock, VolumeControl};
+
+/// 音频流错误发送器
+/// 
+/// 音频流出错（如耳机被拔出）时通过它把错误交给播放引擎，由引擎根据错误类型决定是否切换设备。
+pub type StreamErrorSender = mpsc::UnboundedSender<cpal::StreamError>;
 
 /// 创建音频流
 /// 
@@ -19,6 +27,9 @@ use super::playback::AudioBuffer;
 /// * `sample_format` - 样本格式
 /// * `buffer` - 音频缓冲区
 /// * `channels` - 声道数
+/// * `volume` - 音量控制
+/// * `clock` - 播放时钟，记录实际播放的样本数
+/// * `error_sender` - 音频流错误发送器
 /// 
 /// # 返回
 /// 成功时返回音频流，失败时返回错误
@@ -28,16 +39,19 @@ pub fn create_audio_stream(
     sample_format: SampleFormat,
     buffer: AudioBuffer,
     channels: usize,
+    volume: VolumeControl,
+    clock: PlaybackClock,
+    error_sender: StreamErrorSender,
 ) -> Result<Stream> {
     match sample_format {
-        SampleFormat::F32 => create_stream::<f32>(device, config, buffer, channels),
-        SampleFormat::I16 => create_stream::<i16>(device, config, buffer, channels),
-        SampleFormat::U16 => create_stream::<u16>(device, config, buffer, channels),
-        SampleFormat::I8 => create_stream::<i8>(device, config, buffer, channels),
-        SampleFormat::U8 => create_stream::<u8>(device, config, buffer, channels),
-        SampleFormat::I32 => create_stream::<i32>(device, config, buffer, channels),
-        SampleFormat::U32 => create_stream::<u32>(device, config, buffer, channels),
-        SampleFormat::F64 => create_stream::<f64>(device, config, buffer, channels),
+        SampleFormat::F32 => create_stream::<f32>(device, config, buffer, channels, volume, clock, error_sender),
+        SampleFormat::I16 => create_stream::<i16>(device, config, buffer, channels, volume, clock, error_sender),
+        SampleFormat::U16 => create_stream::<u16>(device, config, buffer, channels, volume, clock, error_sender),
+        SampleFormat::I8 => create_stream::<i8>(device, config, buffer, channels, volume, clock, error_sender),
+        SampleFormat::U8 => create_stream::<u8>(device, config, buffer, channels, volume, clock, error_sender),
+        SampleFormat::I32 => create_stream::<i32>(device, config, buffer, channels, volume, clock, error_sender),
+        SampleFormat::U32 => create_stream::<u32>(device, config, buffer, channels, volume, clock, error_sender),
+        SampleFormat::F64 => create_stream::<f64>(device, config, buffer, channels, volume, clock, error_sender),
         _ => Err(PlayerError::AudioDeviceError(format!("Unsupported sample format: {:?}", sample_format))),
     }
 }
@@ -49,6 +63,9 @@ pub fn create_audio_stream(
 /// * `config` - 流配置
 /// * `buffer` - 音频缓冲区
 /// * `channels` - 源音频声道数
+/// * `volume` - 音量控制，每次回调时读取当前增益
+/// * `clock` - 播放时钟
+/// * `error_sender` - 音频流错误发送器
 /// 
 /// # 返回
 /// 成功时返回音频流，失败时返回错误
@@ -57,6 +74,9 @@ pub fn create_stream<T>(
     config: &StreamConfig,
     buffer: AudioBuffer,
     channels: usize,
+    volume: VolumeControl,
+    clock: PlaybackClock,
+    error_sender: StreamErrorSender,
 ) -> Result<Stream>
 where
     T: Sample + SizedSample + FromSample<f32> + Send + 'static,
@@ -65,10 +85,12 @@ where
     let stream = device.build_output_stream(
         config,
         move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
-            fill_audio_buffer(data, &buffer, output_channels, channels);
+            fill_audio_buffer(data, &buffer, output_channels, channels, volume.gain(), &clock);
         },
-        |err| {
+        move |err| {
             eprintln!("Audio stream error: {}", err);
+            // 交给播放引擎处理（设备不可用时切换到默认设备）
+            let _ = error_sender.send(err);
         },
         None,
     ).map_err(|e| PlayerError::AudioDeviceError(e.to_string()))?;
@@ -83,15 +105,20 @@ where
 /// * `buffer` - 音频缓冲区
 /// * `output_channels` - 输出声道数
 /// * `source_channels` - 源音频声道数
+/// * `gain` - 线性增益（音量）
+/// * `clock` - 播放时钟，在持有缓冲区锁时累加取出的样本数
 fn fill_audio_buffer<T>(
     data: &mut [T],
     buffer: &AudioBuffer,
     output_channels: usize,
     source_channels: usize,
+    gain: f32,
+    clock: &PlaybackClock,
 ) where
     T: Sample + FromSample<f32>,
 {
     let mut audio_buffer = buffer.lock().unwrap();
+    let available_samples = audio_buffer.len();
     
     for frame in data.chunks_mut(output_channels) {
         if output_channels >= source_channels {
@@ -130,7 +157,7 @@ fn fill_audio_buffer<T>(
                     frame_samples[i % source_channels]
                 };
                 
-                *sample = T::from_sample(audio_value);
+                *sample = T::from_sample(audio_value * gain);
             }
         } else {
             // 输出通道数 < 源通道数 (downmix)
@@ -177,8 +204,10 @@ fn fill_audio_buffer<T>(
                     source_samples[source_idx.min(source_samples.len() - 1)]
                 };
                 
-                *sample = T::from_sample(mixed_sample);
+                *sample = T::from_sample(mixed_sample * gain);
             }
         }
     }
+    
+    clock.advance((available_samples - audio_buffer.len()) as u64);
 } 
\ No newline at end of file
@@ -0,0 +1,342 @@
+//! 变速播放模块
+//!
+//! 使用WSOLA（波形相似重叠相加）改变播放速度而不改变音高：
+//! 按播放速度在输入中跳跃选取分析帧，并在容差范围内寻找与上一帧自然延续最相似的位置，
+//! 再以50%重叠的汉宁窗叠加输出。
+
+use std::f64::consts::PI;
+use std::sync::{Arc, atomic::{AtomicU32, Ordering}};
+
+/// 最低播放速度
+pub const MIN_SPEED: f32 = 0.5;
+
+/// 最高播放速度
+pub const MAX_SPEED: f32 = 2.0;
+
+/// 分析帧长度（毫秒）
+const FRAME_MS: f64 = 40.0;
+
+/// 寻找相似波形的容差（毫秒）
+const SEEK_MS: f64 = 12.0;
+
+/// 粗搜索的步长（帧），之后在最佳位置附近逐帧细化
+const COARSE_STEP: usize = 4;
+
+/// 播放速度控制
+///
+/// 速度以f32的位模式存储在原子变量中，解码线程在写入样本前检查是否变化。
+#[derive(Debug, Clone)]
+pub struct SpeedControl {
+    /// 播放速度（以f32位模式存储）
+    speed_bits: Arc<AtomicU32>,
+}
+
+impl SpeedControl {
+    /// 创建速度控制
+    ///
+    /// # 参数
+    /// * `speed` - 初始播放速度（0.5 - 2.0）
+    pub fn new(speed: f32) -> Self {
+        let control = Self {
+            speed_bits: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+        };
+        control.set_speed(speed);
+        control
+    }
+
+    /// 设置播放速度，超出范围的值会被限制到 0.5 - 2.0
+    pub fn set_speed(&self, speed: f32) {
+        let speed = if speed.is_finite() { speed.clamp(MIN_SPEED, MAX_SPEED) } else { 1.0 };
+        self.speed_bits.store(speed.to_bits(), Ordering::Relaxed);
+    }
+
+    /// 获取当前播放速度
+    pub fn speed(&self) -> f32 {
+        f32::from_bits(self.speed_bits.load(Ordering::Relaxed))
+    }
+}
+
+impl Default for SpeedControl {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+/// 流式变速器
+///
+/// 输入输出均为交错样本，在多次调用之间保留未处理的输入，可以逐包处理解码结果。
+#[derive(Debug, Clone)]
+pub struct TimeStretcher {
+    channels: usize,
+    speed: f64,
+    /// 分析帧长度（帧）
+    frame_len: usize,
+    /// 相邻输出帧的间隔，等于帧长度的一半（帧）
+    overlap: usize,
+    /// 搜索容差（帧）
+    seek: usize,
+    /// 汉宁窗
+    window: Vec<f32>,
+    /// 尚未丢弃的输入样本（交错）
+    input: Vec<f32>,
+    /// `input` 混合为单声道后的样本，用于寻找相似波形
+    mono: Vec<f32>,
+    /// 下一个分析帧在 `input` 中的名义起点（帧）
+    next_position: f64,
+    /// 上一个分析帧的自然延续（紧接其前半部分之后）在 `input` 中的起点（帧）
+    continuation: Option<usize>,
+    /// 上一个分析帧加窗后的后半部分，与下一帧的前半部分叠加
+    tail: Vec<f32>,
+    /// 累计输入帧数
+    consumed: u64,
+    /// 累计输出帧数
+    produced: u64,
+}
+
+impl TimeStretcher {
+    /// 创建变速器
+    ///
+    /// # 参数
+    /// * `sample_rate` - 采样率
+    /// * `channels` - 声道数
+    /// * `speed` - 播放速度
+    pub fn new(sample_rate: u32, channels: usize, speed: f32) -> Self {
+        let channels = channels.max(1);
+        let overlap = ((sample_rate as f64 * FRAME_MS / 1000.0 / 2.0) as usize).max(1);
+        let frame_len = overlap * 2;
+        let seek = (sample_rate as f64 * SEEK_MS / 1000.0) as usize;
+        // 周期汉宁窗在50%重叠时各帧权重之和恒为1
+        let window = (0..frame_len)
+            .map(|i| (0.5 - 0.5 * (2.0 * PI * i as f64 / frame_len as f64).cos()) as f32)
+            .collect();
+
+        Self {
+            channels,
+            speed: speed.clamp(MIN_SPEED, MAX_SPEED) as f64,
+            frame_len,
+            overlap,
+            seek,
+            window,
+            input: Vec::new(),
+            mono: Vec::new(),
+            next_position: 0.0,
+            continuation: None,
+            tail: vec![0.0; overlap * channels],
+            consumed: 0,
+            produced: 0,
+        }
+    }
+
+    /// 当前播放速度
+    pub fn speed(&self) -> f32 {
+        self.speed as f32
+    }
+
+    /// 清除未处理的输入（跳转后调用）
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.mono.clear();
+        self.next_position = 0.0;
+        self.continuation = None;
+        self.tail.fill(0.0);
+        self.consumed = 0;
+        self.produced = 0;
+    }
+
+    /// 变速处理一段交错样本
+    ///
+    /// 选取分析帧需要之后的样本，输出会比输入延迟少量帧，剩余部分在 `flush` 时输出。
+    ///
+    /// # 参数
+    /// * `input` - 交错样本
+    ///
+    /// # 返回
+    /// 变速后的交错样本
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        self.input.extend_from_slice(input);
+        self.mono.extend(input.chunks_exact(self.channels).map(|frame| frame.iter().sum::<f32>()));
+        self.consumed += (input.len() / self.channels) as u64;
+
+        let channels = self.channels;
+        let overlap = self.overlap;
+        let frames = self.input.len() / channels;
+        let mut output = Vec::new();
+
+        loop {
+            let nominal = self.next_position.round() as usize;
+            let start = match self.continuation {
+                None => {
+                    if nominal + self.frame_len > frames {
+                        break;
+                    }
+                    nominal
+                }
+                Some(continuation) => {
+                    let last = nominal + self.seek;
+                    if last + self.frame_len > frames {
+                        break;
+                    }
+                    // 与上一帧的自然延续最相似的位置，使叠加处波形连续
+                    self.best_match(continuation, nominal.saturating_sub(self.seek), last)
+                }
+            };
+
+            let first = self.continuation.is_none();
+            for i in 0..overlap {
+                for channel in 0..channels {
+                    let head = self.input[(start + i) * channels + channel];
+                    // 第一帧前面没有可叠加的部分，前半段直接输出避免淡入
+                    let value = if first { head } else { self.tail[i * channels + channel] + head * self.window[i] };
+                    output.push(value);
+                    self.tail[i * channels + channel] = self.input[(start + overlap + i) * channels + channel] * self.window[overlap + i];
+                }
+            }
+            self.continuation = Some(start + overlap);
+            self.next_position += overlap as f64 * self.speed;
+        }
+        self.produced += (output.len() / channels) as u64;
+
+        // 丢弃之后不再需要的输入帧
+        let drop_frames = self.continuation.unwrap_or(usize::MAX)
+            .min((self.next_position.floor() as usize).saturating_sub(self.seek))
+            .min(frames);
+        self.input.drain(..drop_frames * channels);
+        self.mono.drain(..drop_frames);
+        self.next_position -= drop_frames as f64;
+        if let Some(continuation) = self.continuation.as_mut() {
+            *continuation -= drop_frames;
+        }
+
+        output
+    }
+
+    /// 输出剩余的样本（输入结束时调用）
+    pub fn flush(&mut self) -> Vec<f32> {
+        let target = (self.consumed as f64 / self.speed).round() as u64;
+        let produced = self.produced;
+        let padding = vec![0.0; (self.frame_len + self.seek) * self.channels];
+        let mut output = self.process(&padding);
+        output.extend_from_slice(&self.tail);
+        // 补零产生的多余部分不输出，使总时长为输入时长除以速度
+        let remaining = target.saturating_sub(produced);
+        output.truncate(remaining as usize * self.channels);
+        self.reset();
+        output
+    }
+
+    /// 在 `lowest..=highest` 中寻找与 `reference` 处波形最相似的起点
+    fn best_match(&self, reference: usize, lowest: usize, highest: usize) -> usize {
+        let mut best = lowest;
+        let mut best_score = f32::MIN;
+        for candidate in (lowest..=highest).step_by(COARSE_STEP) {
+            let score = self.similarity(reference, candidate, COARSE_STEP);
+            if score > best_score {
+                best = candidate;
+                best_score = score;
+            }
+        }
+
+        let coarse = best;
+        let fine_lowest = coarse.saturating_sub(COARSE_STEP - 1).max(lowest);
+        let fine_highest = (coarse + COARSE_STEP - 1).min(highest);
+        for candidate in fine_lowest..=fine_highest {
+            let score = self.similarity(reference, candidate, 1);
+            if score > best_score {
+                best = candidate;
+                best_score = score;
+            }
+        }
+        best
+    }
+
+    /// 两段重叠区域（混合为单声道后）的归一化互相关
+    /// 
+    /// 粗搜索时每隔 `stride` 帧取一个样本以减少计算量。
+    fn similarity(&self, reference: usize, candidate: usize, stride: usize) -> f32 {
+        let a = &self.mono[reference..reference + self.overlap];
+        let b = &self.mono[candidate..candidate + self.overlap];
+        let (correlation, energy) = a.iter().zip(b).step_by(stride)
+            .fold((0.0, 0.0), |(correlation, energy), (a, b)| (correlation + a * b, energy + b * b));
+        correlation / (energy + 1e-9f32).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 生成单声道正弦波
+    fn sine(frequency: f64, sample_rate: u32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (2.0 * PI * frequency * i as f64 / sample_rate as f64).sin() as f32 * 0.5)
+            .collect()
+    }
+
+    /// 用Goertzel算法计算指定频率的幅度
+    fn magnitude(samples: &[f32], frequency: f64, sample_rate: u32) -> f64 {
+        let coeff = 2.0 * (2.0 * PI * frequency / sample_rate as f64).cos();
+        let (mut s1, mut s2) = (0.0, 0.0);
+        for &x in samples {
+            let s0 = x as f64 + coeff * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        let power = s1 * s1 + s2 * s2 - coeff * s1 * s2;
+        2.0 * power.max(0.0).sqrt() / samples.len() as f64
+    }
+
+    /// 分块处理，模拟逐包解码
+    fn stretch_in_chunks(stretcher: &mut TimeStretcher, input: &[f32], chunk: usize) -> Vec<f32> {
+        let mut output = Vec::new();
+        for part in input.chunks(chunk) {
+            output.extend(stretcher.process(part));
+        }
+        output.extend(stretcher.flush());
+        output
+    }
+
+    #[test]
+    fn test_output_length() {
+        for speed in [0.5, 0.75, 1.5, 2.0] {
+            let input = sine(440.0, 44100, 44100);
+            let mut stretcher = TimeStretcher::new(44100, 1, speed);
+            let output = stretch_in_chunks(&mut stretcher, &input, 1152);
+            let expected = 44100.0 / speed as f64;
+            assert!((output.len() as f64 - expected).abs() < 2.0, "{}: {}", speed, output.len());
+        }
+    }
+
+    #[test]
+    fn test_preserves_pitch() {
+        for speed in [0.75, 1.5] {
+            let input = sine(440.0, 44100, 44100);
+            let mut stretcher = TimeStretcher::new(44100, 1, speed);
+            let output = stretch_in_chunks(&mut stretcher, &input, 1000);
+            let steady = &output[2000..output.len() - 4000];
+            // 音高不变：440 Hz 处幅度接近原始值，按速度缩放后的频率几乎没有能量
+            assert!(magnitude(steady, 440.0, 44100) > 0.45, "{}", speed);
+            assert!(magnitude(steady, 440.0 * speed as f64, 44100) < 0.02, "{}", speed);
+        }
+    }
+
+    #[test]
+    fn test_interleaved_channels() {
+        let left = sine(300.0, 44100, 22050);
+        let input: Vec<f32> = left.iter().flat_map(|&l| [l, 0.0]).collect();
+        let mut stretcher = TimeStretcher::new(44100, 2, 1.25);
+        let output = stretch_in_chunks(&mut stretcher, &input, 512);
+        assert_eq!(output.len() % 2, 0);
+        assert!(output.iter().skip(1).step_by(2).all(|&r| r == 0.0));
+        assert!(output.iter().step_by(2).any(|&l| l.abs() > 0.45));
+    }
+
+    #[test]
+    fn test_speed_control_clamp() {
+        let control = SpeedControl::new(3.0);
+        assert_eq!(control.speed(), MAX_SPEED);
+        control.set_speed(0.1);
+        assert_eq!(control.speed(), MIN_SPEED);
+        control.set_speed(f32::NAN);
+        assert_eq!(control.speed(), 1.0);
+    }
+}
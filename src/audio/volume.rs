@@ -0,0 +1,98 @@
+//! 音量控制模块
+//!
+//! 在控制任务和音频流回调之间共享音量与静音状态。
+
+use std::sync::{Arc, atomic::{AtomicBool, AtomicU32, Ordering}};
+
+/// 音量控制
+///
+/// 音量以f32的位模式存储在原子变量中，音频流回调在每次填充输出时读取当前增益，
+/// 因此调整音量立即生效，且无需重建音频流。
+#[derive(Debug, Clone)]
+pub struct VolumeControl {
+    /// 音量（0.0 - 1.0，以f32位模式存储）
+    volume_bits: Arc<AtomicU32>,
+    /// 是否静音
+    muted: Arc<AtomicBool>,
+}
+
+impl VolumeControl {
+    /// 创建音量控制
+    ///
+    /// # 参数
+    /// * `volume` - 初始音量（0.0 - 1.0）
+    /// * `muted` - 初始静音状态
+    pub fn new(volume: f32, muted: bool) -> Self {
+        let control = Self {
+            volume_bits: Arc::new(AtomicU32::new(0)),
+            muted: Arc::new(AtomicBool::new(muted)),
+        };
+        control.set_volume(volume);
+        control
+    }
+
+    /// 设置音量，超出范围的值会被限制到 0.0 - 1.0
+    pub fn set_volume(&self, volume: f32) {
+        let volume = if volume.is_finite() { volume.clamp(0.0, 1.0) } else { 0.0 };
+        self.volume_bits.store(volume.to_bits(), Ordering::Relaxed);
+    }
+
+    /// 获取当前音量
+    pub fn volume(&self) -> f32 {
+        f32::from_bits(self.volume_bits.load(Ordering::Relaxed))
+    }
+
+    /// 设置静音状态
+    pub fn set_muted(&self, muted: bool) {
+        self.muted.store(muted, Ordering::Relaxed);
+    }
+
+    /// 是否静音
+    pub fn is_muted(&self) -> bool {
+        self.muted.load(Ordering::Relaxed)
+    }
+
+    /// 获取应用到样本上的线性增益
+    ///
+    /// 音量滑块按平方曲线映射，使低音量区间的调节更贴近人耳感知；静音时增益为0。
+    pub fn gain(&self) -> f32 {
+        if self.is_muted() {
+            0.0
+        } else {
+            let volume = self.volume();
+            volume * volume
+        }
+    }
+}
+
+impl Default for VolumeControl {
+    fn default() -> Self {
+        Self::new(1.0, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_clamp() {
+        let control = VolumeControl::new(1.5, false);
+        assert_eq!(control.volume(), 1.0);
+        control.set_volume(-0.2);
+        assert_eq!(control.volume(), 0.0);
+        control.set_volume(f32::NAN);
+        assert_eq!(control.volume(), 0.0);
+    }
+
+    #[test]
+    fn test_gain_and_mute() {
+        let control = VolumeControl::new(0.5, false);
+        let shared = control.clone();
+        assert_eq!(control.gain(), 0.25);
+        shared.set_muted(true);
+        assert_eq!(control.gain(), 0.0);
+        shared.set_muted(false);
+        assert_eq!(control.gain(), 0.25);
+    }
+}
@@ -0,0 +1,143 @@
+//! 书签模块
+//!
+//! 按文件保存命名的播放位置，持久化到配置目录下的 `bookmarks.toml`。
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{config_sibling_path, load_or_default};
+use crate::error::{PlayerError, Result};
+
+/// 书签文件名
+const BOOKMARKS_FILE_NAME: &str = "bookmarks.toml";
+
+/// 曲目内的书签
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// 书签名称
+    pub name: String,
+    /// 播放位置（秒）
+    pub position: f64,
+}
+
+/// 所有文件的书签
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookmarkStore {
+    /// 文件路径 -> 按位置排序的书签
+    #[serde(default)]
+    files: BTreeMap<String, Vec<Bookmark>>,
+}
+
+impl BookmarkStore {
+    /// 获取书签文件路径
+    pub fn file_path() -> Result<PathBuf> {
+        config_sibling_path(BOOKMARKS_FILE_NAME)
+    }
+
+    /// 从书签文件加载，文件不存在或损坏时返回空的书签集合
+    pub fn load() -> Self {
+        load_or_default(BOOKMARKS_FILE_NAME, "书签", Self::from_toml)
+    }
+
+    /// 保存到书签文件
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(Self::file_path()?, self.to_toml()?)?;
+        Ok(())
+    }
+
+    /// 安全保存书签（忽略错误）
+    pub fn save_safe(&self) {
+        if let Err(e) = self.save() {
+            eprintln!("保存书签失败: {}", e);
+        }
+    }
+
+    /// 从TOML文本解析
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|e| PlayerError::Other(format!("Invalid bookmarks file: {}", e)))
+    }
+
+    /// 序列化为TOML文本
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).map_err(|e| PlayerError::Other(e.to_string()))
+    }
+
+    /// 获取文件的书签（按位置排序）
+    pub fn bookmarks(&self, file_path: &str) -> &[Bookmark] {
+        self.files.get(file_path).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// 添加书签，同名书签会被替换
+    ///
+    /// # 参数
+    /// * `file_path` - 文件路径
+    /// * `name` - 书签名称
+    /// * `position` - 播放位置（秒）
+    pub fn add(&mut self, file_path: &str, name: String, position: f64) {
+        let bookmarks = self.files.entry(file_path.to_string()).or_default();
+        bookmarks.retain(|bookmark| bookmark.name != name);
+        let index = bookmarks.partition_point(|bookmark| bookmark.position <= position);
+        bookmarks.insert(index, Bookmark { name, position: position.max(0.0) });
+    }
+
+    /// 删除书签
+    ///
+    /// # 参数
+    /// * `file_path` - 文件路径
+    /// * `index` - 书签在 `bookmarks` 返回列表中的索引
+    ///
+    /// # 返回
+    /// 被删除的书签
+    pub fn remove(&mut self, file_path: &str, index: usize) -> Option<Bookmark> {
+        let bookmarks = self.files.get_mut(file_path)?;
+        if index >= bookmarks.len() {
+            return None;
+        }
+        let removed = bookmarks.remove(index);
+        if bookmarks.is_empty() {
+            self.files.remove(file_path);
+        }
+        Some(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_sorted_and_replace() {
+        let mut store = BookmarkStore::default();
+        store.add("/music/a.flac", "Chorus".to_string(), 60.0);
+        store.add("/music/a.flac", "Intro".to_string(), 5.0);
+        store.add("/music/a.flac", "Solo".to_string(), 120.0);
+        store.add("/music/a.flac", "Chorus".to_string(), 75.0);
+
+        let names: Vec<&str> = store.bookmarks("/music/a.flac").iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["Intro", "Chorus", "Solo"]);
+        assert_eq!(store.bookmarks("/music/a.flac")[1].position, 75.0);
+        assert!(store.bookmarks("/music/b.flac").is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut store = BookmarkStore::default();
+        store.add("a.mp3", "One".to_string(), 1.0);
+        assert!(store.remove("a.mp3", 1).is_none());
+        assert_eq!(store.remove("a.mp3", 0).map(|b| b.name), Some("One".to_string()));
+        assert_eq!(store, BookmarkStore::default());
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let mut store = BookmarkStore::default();
+        store.add("C:\\音乐\\练习 曲.flac", "Bar 16".to_string(), 42.5);
+        store.add("/home/user/song.mp3", "Bridge".to_string(), 90.0);
+
+        let content = store.to_toml().unwrap();
+        assert_eq!(BookmarkStore::from_toml(&content).unwrap(), store);
+        assert_eq!(BookmarkStore::from_toml("").unwrap(), BookmarkStore::default());
+    }
+}
@@ -14,6 +14,9 @@ pub const BUFFER_CAPACITY_THRESHOLD: usize = 1000;
 /// 缓冲区写入延迟（毫秒）
 pub const BUFFER_WRITE_DELAY: u64 = 10;
 
+/// 最大交叉淡化时长（秒）
+pub const MAX_CROSSFADE_SECONDS: f64 = 12.0;
+
 /// 字体配置
 pub mod fonts {
     /// 获取适合当前平台的中文字体
@@ -160,6 +163,28 @@ impl Default for PlayModeConfig {
     }
 }
 
+/// ReplayGain模式
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ReplayGainMode {
+    /// 不做响度标准化
+    #[default]
+    Off,
+    /// 使用曲目增益
+    Track,
+    /// 使用专辑增益
+    Album,
+}
+
+/// 采样率转换质量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ResampleQuality {
+    /// 线性插值，速度快
+    Linear,
+    /// 带限的加窗sinc插值，音质好
+    #[default]
+    Sinc,
+}
+
 /// 窗口配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowConfig {
@@ -214,8 +239,9 @@ impl Default for UIConfig {
 /// 播放器配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerConfig {
-    /// 音频设备索引
-    pub device_index: Option<usize>,
+    /// 输出设备名称（None表示使用系统默认设备）
+    #[serde(default)]
+    pub device_name: Option<String>,
     /// 自动播放下一首
     pub auto_next: bool,
     /// 启用播放列表循环
@@ -224,6 +250,24 @@ pub struct PlayerConfig {
     pub play_mode: PlayModeConfig,
     /// 音量（0.0 - 1.0）
     pub volume: f64,
+    /// 是否静音
+    #[serde(default)]
+    pub muted: bool,
+    /// 曲目间交叉淡化时长（秒，0表示不淡化）
+    #[serde(default)]
+    pub crossfade_seconds: f64,
+    /// ReplayGain模式
+    #[serde(default)]
+    pub replay_gain_mode: ReplayGainMode,
+    /// ReplayGain前置放大（dB）
+    #[serde(default)]
+    pub replay_gain_preamp: f64,
+    /// 设备不支持文件采样率时的采样率转换质量
+    #[serde(default)]
+    pub resample_quality: ResampleQuality,
+    /// 播放速度（0.5 - 2.0）
+    #[serde(default = "default_playback_speed")]
+    pub playback_speed: f32,
     /// 最后播放的文件路径
     pub last_file_path: Option<String>,
     /// 最后播放的播放列表路径
@@ -234,14 +278,25 @@ pub struct PlayerConfig {
     pub last_position: f64,
 }
 
+/// 旧配置文件中没有播放速度时使用正常速度
+fn default_playback_speed() -> f32 {
+    1.0
+}
+
 impl Default for PlayerConfig {
     fn default() -> Self {
         Self {
-            device_index: None,
+            device_name: None,
             auto_next: true,
             loop_playlist: false,
             play_mode: PlayModeConfig::ListLoop,
             volume: 0.8,
+            muted: false,
+            crossfade_seconds: 0.0,
+            replay_gain_mode: ReplayGainMode::Off,
+            replay_gain_preamp: 0.0,
+            resample_quality: ResampleQuality::Sinc,
+            playback_speed: 1.0,
             last_file_path: None,
             last_playlist_path: None,
             remember_position: true,
@@ -250,6 +305,53 @@ impl Default for PlayerConfig {
     }
 }
 
+/// 用户保存的均衡器预设
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EqualizerPreset {
+    /// 预设名称
+    pub name: String,
+    /// 各频段增益（dB）
+    pub gains: Vec<f32>,
+}
+
+/// 均衡器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EqualizerConfig {
+    /// 是否启用均衡器
+    pub enabled: bool,
+    /// 当前各频段增益（dB）
+    pub gains: Vec<f32>,
+    /// 当前选中的预设名称（手动调整后为None）
+    pub preset: Option<String>,
+    /// 用户预设
+    #[serde(default)]
+    pub user_presets: Vec<EqualizerPreset>,
+}
+
+impl Default for EqualizerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            gains: vec![0.0; crate::audio::equalizer::EQ_BAND_COUNT],
+            preset: Some("Flat".to_string()),
+            user_presets: Vec::new(),
+        }
+    }
+}
+
+impl EqualizerConfig {
+    /// 实际生效的各频段增益（未启用时全部为0）
+    pub fn effective_gains(&self) -> [f32; crate::audio::equalizer::EQ_BAND_COUNT] {
+        let mut gains = [0.0; crate::audio::equalizer::EQ_BAND_COUNT];
+        if self.enabled {
+            for (gain, value) in gains.iter_mut().zip(self.gains.iter()) {
+                *gain = *value;
+            }
+        }
+        gains
+    }
+}
+
 /// 歌词配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LyricsConfig {
@@ -277,6 +379,14 @@ impl Default for LyricsConfig {
     }
 }
 
+/// 音乐库配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibraryConfig {
+    /// 扫描的音乐文件夹
+    #[serde(default)]
+    pub folders: Vec<String>,
+}
+
 /// 应用程序配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -290,6 +400,12 @@ pub struct AppConfig {
     pub player: PlayerConfig,
     /// 歌词配置
     pub lyrics: LyricsConfig,
+    /// 均衡器配置
+    #[serde(default)]
+    pub equalizer: EqualizerConfig,
+    /// 音乐库配置
+    #[serde(default)]
+    pub library: LibraryConfig,
 }
 
 impl Default for AppConfig {
@@ -300,6 +416,8 @@ impl Default for AppConfig {
             ui: UIConfig::default(),
             player: PlayerConfig::default(),
             lyrics: LyricsConfig::default(),
+            equalizer: EqualizerConfig::default(),
+            library: LibraryConfig::default(),
         }
     }
 }
@@ -410,6 +528,47 @@ impl AppConfig {
     }
 }
 
+/// 获取与配置文件位于同一目录的数据文件路径
+///
+/// # 参数
+/// * `file_name` - 数据文件名
+pub fn config_sibling_path(file_name: &str) -> crate::error::Result<PathBuf> {
+    let config_path = AppConfig::config_file_path()
+        .map_err(|e| crate::error::PlayerError::Other(e.to_string()))?;
+    Ok(config_path.with_file_name(file_name))
+}
+
+/// 从配置目录下的数据文件加载，文件不存在或无法解析时返回默认值
+///
+/// # 参数
+/// * `file_name` - 数据文件名
+/// * `description` - 数据名称，用于错误日志
+/// * `parse` - 解析文件内容
+pub fn load_or_default<T: Default>(
+    file_name: &str,
+    description: &str,
+    parse: impl FnOnce(&str) -> crate::error::Result<T>,
+) -> T {
+    let path = match config_sibling_path(file_name) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("加载{}失败: {}", description, e);
+            return T::default();
+        }
+    };
+    if !path.exists() {
+        return T::default();
+    }
+
+    match std::fs::read_to_string(&path).map_err(crate::error::PlayerError::from).and_then(|content| parse(&content)) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("加载{}失败: {}", description, e);
+            T::default()
+        }
+    }
+}
+
 // ============================================================================
 // 兼容性和转换
 // ============================================================================
@@ -461,6 +620,8 @@ impl From<crate::ui::components::PageType> for String {
     fn from(page: crate::ui::components::PageType) -> Self {
         match page {
             crate::ui::components::PageType::Home => "Home".to_string(),
+            crate::ui::components::PageType::Library => "Library".to_string(),
+            crate::ui::components::PageType::History => "History".to_string(),
             crate::ui::components::PageType::Settings => "Settings".to_string(),
         }
     }
@@ -470,6 +631,8 @@ impl From<crate::ui::components::PageType> for String {
 impl From<String> for crate::ui::components::PageType {
     fn from(s: String) -> Self {
         match s.as_str() {
+            "Library" => crate::ui::components::PageType::Library,
+            "History" => crate::ui::components::PageType::History,
             "Settings" => crate::ui::components::PageType::Settings,
             _ => crate::ui::components::PageType::Home,
         }
@@ -0,0 +1,319 @@
+//! 无界面播放模块
+//!
+//! 在终端中播放音频文件或播放列表，通过键盘控制播放并显示播放进度，
+//! 适合在服务器或SSH会话中使用。
+
+use std::{
+    io::{self, Write},
+    sync::{Arc, atomic::{AtomicBool, Ordering}},
+    thread,
+    time::Duration,
+};
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    terminal::{self, ClearType},
+};
+use tokio::sync::mpsc;
+
+use crate::audio::{
+    start_audio_playback, AudioSource, EqualizerControl, PlaybackCommand, PlaybackEvent, PlaybackState,
+    ReplayGainSettings, SpeedControl, VolumeControl,
+};
+use crate::config::AppConfig;
+use crate::error::{PlayerError, Result};
+use crate::playlist::Playlist;
+use crate::utils::{extract_filename, format_duration, is_playlist_file, is_supported_audio_file};
+
+/// 每次快进/快退的秒数
+const SEEK_STEP_SECONDS: f64 = 5.0;
+
+/// 每次调整音量的幅度
+const VOLUME_STEP: f32 = 0.05;
+
+/// 播放超过该秒数时“上一首”改为从头播放当前曲目
+const RESTART_THRESHOLD_SECONDS: f64 = 3.0;
+
+/// 按键对应的播放控制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyCommand {
+    TogglePause,
+    SeekBackward,
+    SeekForward,
+    Next,
+    Previous,
+    VolumeUp,
+    VolumeDown,
+    ToggleMute,
+    Quit,
+}
+
+/// 当前曲目结束后的去向
+enum TrackEnd {
+    Next,
+    Previous,
+    Quit,
+}
+
+/// 终端原始模式，离开作用域时恢复
+struct RawModeGuard;
+
+impl RawModeGuard {
+    /// 开启原始模式，标准输入不是终端时返回None
+    fn enable() -> Option<Self> {
+        terminal::enable_raw_mode().ok().map(|_| Self)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// 在终端中播放
+///
+/// # 参数
+/// * `inputs` - 音频文件或播放列表路径
+/// * `config` - 应用配置（输出设备、音量、均衡器等）
+///
+/// # 返回
+/// 没有可播放的文件时返回错误
+pub fn run(inputs: &[String], config: &AppConfig) -> Result<()> {
+    let tracks = collect_tracks(inputs);
+    if tracks.is_empty() {
+        return Err(PlayerError::Other("No playable audio files".to_string()));
+    }
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(play_tracks(tracks, config))
+}
+
+/// 展开播放列表，得到按顺序播放的文件
+///
+/// # 参数
+/// * `inputs` - 音频文件或播放列表路径
+///
+/// # 返回
+/// 音频文件路径列表，无法读取的播放列表和不支持的文件会被跳过
+pub fn collect_tracks(inputs: &[String]) -> Vec<String> {
+    let mut tracks = Vec::new();
+    for input in inputs {
+        if is_playlist_file(input) {
+            match Playlist::create_from_playlist_file(input.clone()) {
+                Ok(playlist) => tracks.extend(playlist.file_paths().iter().cloned()),
+                Err(e) => eprintln!("Failed to load playlist {}: {}", input, e),
+            }
+        } else if is_supported_audio_file(input) {
+            tracks.push(input.clone());
+        } else {
+            eprintln!("Skipping unsupported file: {}", input);
+        }
+    }
+    tracks
+}
+
+/// 依次播放所有曲目，直到播放完毕或用户退出
+async fn play_tracks(tracks: Vec<String>, config: &AppConfig) -> Result<()> {
+    let raw_mode = RawModeGuard::enable();
+    let stop_keys = Arc::new(AtomicBool::new(false));
+    let mut keys = if raw_mode.is_some() {
+        print_line("Controls: [space] pause  [←/→] seek  [n/b] next/previous  [↑/↓] volume  [m] mute  [q] quit");
+        spawn_key_reader(stop_keys.clone())
+    } else {
+        eprintln!("Keyboard controls unavailable: standard input is not a terminal");
+        mpsc::unbounded_channel().1
+    };
+
+    // 所有曲目共享同一个音量控制，切换曲目后音量保持不变
+    let volume = VolumeControl::new(config.player.volume as f32, config.player.muted);
+    let replay_gain = ReplayGainSettings {
+        mode: config.player.replay_gain_mode,
+        preamp_db: config.player.replay_gain_preamp as f32,
+    };
+
+    let mut index = 0;
+    loop {
+        let path = &tracks[index];
+        print_line(&format!("♪ [{}/{}] {}", index + 1, tracks.len(), extract_filename(path)));
+
+        let (event_sender, mut events) = mpsc::unbounded_channel();
+        let (commands, _handle) = start_audio_playback(
+            AudioSource::FilePath(path.clone()),
+            Some(event_sender),
+            config.player.device_name.clone(),
+            volume.clone(),
+            EqualizerControl::new(config.equalizer.effective_gains()),
+            SpeedControl::new(config.player.playback_speed),
+            replay_gain,
+            config.player.resample_quality,
+        ).await;
+
+        let mut state = PlaybackState::default();
+        let end = loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Some(PlaybackEvent::State(new_state)) => {
+                        state = new_state;
+                        print_progress(&state, &volume);
+                    }
+                    Some(PlaybackEvent::DeviceError(error)) => {
+                        print_line(&format!("Output device error: {}", error));
+                    }
+                    Some(PlaybackEvent::TrackChanged(_)) => {}
+                    // 播放完毕，或文件无法播放导致会话结束
+                    Some(PlaybackEvent::EndOfStream) | None => break TrackEnd::Next,
+                },
+                Some(key) = keys.recv() => match key {
+                    KeyCommand::TogglePause => {
+                        let command = if state.is_paused { PlaybackCommand::Resume } else { PlaybackCommand::Pause };
+                        let _ = commands.send(command);
+                    }
+                    KeyCommand::SeekBackward => {
+                        let _ = commands.send(PlaybackCommand::Seek((state.current_time - SEEK_STEP_SECONDS).max(0.0)));
+                    }
+                    KeyCommand::SeekForward => {
+                        let target = state.current_time + SEEK_STEP_SECONDS;
+                        if state.total_duration <= 0.0 || target < state.total_duration {
+                            let _ = commands.send(PlaybackCommand::Seek(target));
+                        }
+                    }
+                    KeyCommand::Next => break TrackEnd::Next,
+                    KeyCommand::Previous => {
+                        if state.current_time > RESTART_THRESHOLD_SECONDS || index == 0 {
+                            let _ = commands.send(PlaybackCommand::Seek(0.0));
+                        } else {
+                            break TrackEnd::Previous;
+                        }
+                    }
+                    KeyCommand::VolumeUp => {
+                        volume.set_volume(volume.volume() + VOLUME_STEP);
+                        print_progress(&state, &volume);
+                    }
+                    KeyCommand::VolumeDown => {
+                        volume.set_volume(volume.volume() - VOLUME_STEP);
+                        print_progress(&state, &volume);
+                    }
+                    KeyCommand::ToggleMute => {
+                        volume.set_muted(!volume.is_muted());
+                        print_progress(&state, &volume);
+                    }
+                    KeyCommand::Quit => break TrackEnd::Quit,
+                },
+            }
+        };
+
+        let _ = commands.send(PlaybackCommand::Stop);
+        match end {
+            TrackEnd::Next if index + 1 < tracks.len() => index += 1,
+            TrackEnd::Previous => index -= 1,
+            TrackEnd::Next | TrackEnd::Quit => break,
+        }
+    }
+
+    stop_keys.store(true, Ordering::Relaxed);
+    drop(raw_mode);
+    println!();
+    Ok(())
+}
+
+/// 在后台线程读取按键
+fn spawn_key_reader(stop: Arc<AtomicBool>) -> mpsc::UnboundedReceiver<KeyCommand> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            match event::poll(Duration::from_millis(100)) {
+                Ok(true) => {
+                    if let Ok(Event::Key(key)) = event::read() {
+                        if let Some(command) = map_key(key) {
+                            if sender.send(command).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    });
+    receiver
+}
+
+/// 将按键映射为播放控制
+fn map_key(key: KeyEvent) -> Option<KeyCommand> {
+    // Windows下松开按键也会产生事件
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+
+    let command = match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => KeyCommand::Quit,
+        KeyCode::Char(' ') | KeyCode::Char('p') => KeyCommand::TogglePause,
+        KeyCode::Left => KeyCommand::SeekBackward,
+        KeyCode::Right => KeyCommand::SeekForward,
+        KeyCode::Char('n') | KeyCode::Char('>') => KeyCommand::Next,
+        KeyCode::Char('b') | KeyCode::Char('<') => KeyCommand::Previous,
+        KeyCode::Up | KeyCode::Char('+') | KeyCode::Char('=') => KeyCommand::VolumeUp,
+        KeyCode::Down | KeyCode::Char('-') => KeyCommand::VolumeDown,
+        KeyCode::Char('m') => KeyCommand::ToggleMute,
+        KeyCode::Char('q') | KeyCode::Esc => KeyCommand::Quit,
+        _ => return None,
+    };
+    Some(command)
+}
+
+/// 输出一行文本（原始模式下需要手动回到行首）
+fn print_line(line: &str) {
+    let mut stdout = io::stdout();
+    let _ = queue!(stdout, terminal::Clear(ClearType::CurrentLine));
+    let _ = write!(stdout, "\r{}\r\n", line);
+    let _ = stdout.flush();
+}
+
+/// 在当前行刷新播放进度
+fn print_progress(state: &PlaybackState, volume: &VolumeControl) {
+    let status = if state.is_paused { "⏸" } else { "▶" };
+    let volume_text = if volume.is_muted() {
+        "muted".to_string()
+    } else {
+        format!("{:.0}%", volume.volume() * 100.0)
+    };
+
+    let mut stdout = io::stdout();
+    let _ = queue!(stdout, terminal::Clear(ClearType::CurrentLine));
+    let _ = write!(
+        stdout,
+        "\r{} {} / {}  vol {}  {}x",
+        status,
+        format_duration(state.current_time),
+        format_duration(state.total_duration),
+        volume_text,
+        state.speed,
+    );
+    let _ = stdout.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_map_key() {
+        assert_eq!(map_key(key(KeyCode::Char(' '))), Some(KeyCommand::TogglePause));
+        assert_eq!(map_key(key(KeyCode::Left)), Some(KeyCommand::SeekBackward));
+        assert_eq!(map_key(key(KeyCode::Char('n'))), Some(KeyCommand::Next));
+        assert_eq!(map_key(key(KeyCode::Down)), Some(KeyCommand::VolumeDown));
+        assert_eq!(map_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(KeyCommand::Quit));
+        assert_eq!(map_key(key(KeyCode::Char('x'))), None);
+
+        let mut release = key(KeyCode::Char('q'));
+        release.kind = KeyEventKind::Release;
+        assert_eq!(map_key(release), None);
+    }
+}
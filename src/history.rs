@@ -0,0 +1,333 @@
+//! 播放历史模块
+//!
+//! 记录每一次播放（开始时间、实际收听时长、播完还是中途跳过），逐条追加到配置目录下的 `history.jsonl`
+//! （每行一条JSON记录，不需要每次重写整个文件），并按文件统计播放次数、跳过次数和最近播放时间，
+//! 可以导出为CSV或JSON进行分析。
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{config_sibling_path, load_or_default};
+use crate::error::{PlayerError, Result};
+use crate::playlist::smart::TrackStats;
+use crate::utils::format_timestamp;
+
+/// 播放历史文件名
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+/// 两次位置更新之间超过该秒数时视为跳转，不计入收听时长
+const MAX_POSITION_STEP: f64 = 5.0;
+
+/// CSV导出的表头
+const CSV_HEADER: &str = "started_at,started,path,title,artist,listened,duration,completed";
+
+/// 一次播放记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayRecord {
+    /// 文件路径
+    pub path: String,
+    /// 标题
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// 艺术家
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    /// 开始播放的时间（Unix时间戳，秒）
+    pub started: u64,
+    /// 实际收听的时长（秒），不包括暂停和跳转的部分
+    pub listened: f64,
+    /// 曲目时长（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    /// 是否播放完毕，否则为中途跳过
+    pub completed: bool,
+}
+
+impl PlayRecord {
+    /// 序列化为一行JSON（包括换行符）
+    pub fn to_json_line(&self) -> Result<String> {
+        let mut line = serde_json::to_string(self).map_err(|e| PlayerError::Other(e.to_string()))?;
+        line.push('\n');
+        Ok(line)
+    }
+}
+
+/// 正在进行的一次播放
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaySession {
+    /// 文件路径
+    path: String,
+    /// 开始播放的时间（Unix时间戳，秒）
+    started: u64,
+    /// 已收听的时长（秒）
+    listened: f64,
+    /// 最近一次的播放位置（秒）
+    position: f64,
+}
+
+impl PlaySession {
+    /// 开始一次播放
+    ///
+    /// # 参数
+    /// * `path` - 文件路径
+    /// * `started` - 开始播放的时间（Unix时间戳，秒）
+    pub fn new(path: String, started: u64) -> Self {
+        Self { path, started, listened: 0.0, position: 0.0 }
+    }
+
+    /// 根据播放位置累计收听时长
+    ///
+    /// 位置后退或一次前进过多（跳转）时只记录新位置，暂停时位置不变也不会累计。
+    pub fn update_position(&mut self, position: f64) {
+        let step = position - self.position;
+        if step > 0.0 && step <= MAX_POSITION_STEP {
+            self.listened += step;
+        }
+        self.position = position;
+    }
+
+    /// 结束播放，生成播放记录（标题、艺术家和时长由调用者补充）
+    ///
+    /// # 参数
+    /// * `completed` - 是否播放完毕
+    pub fn finish(self, completed: bool) -> PlayRecord {
+        PlayRecord {
+            path: self.path,
+            title: None,
+            artist: None,
+            started: self.started,
+            listened: self.listened,
+            duration: None,
+            completed,
+        }
+    }
+}
+
+/// 播放历史
+#[derive(Debug, Clone, Default)]
+pub struct PlayHistory {
+    /// 按开始时间排列的播放记录
+    records: Vec<PlayRecord>,
+    /// 文件路径 -> 播放统计（由记录生成）
+    stats: HashMap<String, TrackStats>,
+}
+
+impl PlayHistory {
+    /// 获取播放历史文件路径
+    pub fn file_path() -> Result<PathBuf> {
+        config_sibling_path(HISTORY_FILE_NAME)
+    }
+
+    /// 从播放历史文件加载，文件不存在时返回空的播放历史
+    pub fn load() -> Self {
+        load_or_default(HISTORY_FILE_NAME, "播放历史", |content| Ok(Self::from_json_lines(content)))
+    }
+
+    /// 把播放记录追加到播放历史文件末尾
+    ///
+    /// # 参数
+    /// * `records` - 新的播放记录
+    pub fn append(records: &[PlayRecord]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let mut content = String::new();
+        for record in records {
+            content.push_str(&record.to_json_line()?);
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(Self::file_path()?)?;
+        file.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    /// 安全追加播放记录（忽略错误）
+    pub fn append_safe(records: &[PlayRecord]) {
+        if let Err(e) = Self::append(records) {
+            eprintln!("保存播放历史失败: {}", e);
+        }
+    }
+
+    /// 从JSON Lines文本解析，每行一条播放记录
+    ///
+    /// 无法解析的行（例如写入中断留下的半行）会被跳过，不影响其余记录。
+    pub fn from_json_lines(content: &str) -> Self {
+        let mut records: Vec<PlayRecord> = Vec::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(record) => records.push(record),
+                Err(e) => eprintln!("跳过无效的播放记录: {}", e),
+            }
+        }
+        // 后台追加的顺序不一定与开始时间一致
+        records.sort_by_key(|record| record.started);
+
+        let mut history = Self::default();
+        for record in records {
+            history.record(record);
+        }
+        history
+    }
+
+    /// 播放记录数量
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// 播放历史是否为空
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// 添加一条播放记录并更新统计
+    pub fn record(&mut self, record: PlayRecord) {
+        let stats = self.stats.entry(record.path.clone()).or_default();
+        if record.completed {
+            stats.play_count += 1;
+        } else {
+            stats.skip_count += 1;
+        }
+        stats.last_played = stats.last_played.max(Some(record.started));
+        self.records.push(record);
+    }
+
+    /// 最近的播放记录，最新的在前
+    pub fn recent(&self) -> impl Iterator<Item = &PlayRecord> {
+        self.records.iter().rev()
+    }
+
+    /// 文件的播放统计：播完计为播放，中途切换计为跳过
+    pub fn stats(&self, path: &str) -> TrackStats {
+        self.stats.get(path).copied().unwrap_or_default()
+    }
+
+    /// 导出为CSV文本，每条记录一行，开始时间同时给出本地时间和Unix时间戳
+    pub fn to_csv(&self) -> String {
+        let mut content = String::from(CSV_HEADER);
+        content.push('\n');
+        for record in &self.records {
+            let fields = [
+                format_timestamp(record.started),
+                record.started.to_string(),
+                csv_field(&record.path),
+                csv_field(record.title.as_deref().unwrap_or_default()),
+                csv_field(record.artist.as_deref().unwrap_or_default()),
+                format!("{:.1}", record.listened),
+                record.duration.map(|duration| format!("{:.1}", duration)).unwrap_or_default(),
+                record.completed.to_string(),
+            ];
+            content.push_str(&fields.join(","));
+            content.push('\n');
+        }
+        content
+    }
+
+    /// 导出为JSON文本（播放记录数组）
+    pub fn to_export_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.records).map_err(|e| PlayerError::Other(e.to_string()))
+    }
+
+    /// 导出到指定文件，扩展名为 `.json` 时导出JSON，否则导出CSV
+    pub fn export(&self, target_path: &str) -> Result<()> {
+        let is_json = Path::new(target_path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let content = if is_json { self.to_export_json()? } else { self.to_csv() };
+        std::fs::write(target_path, content)?;
+        Ok(())
+    }
+}
+
+/// 按CSV规则转义字段：含有逗号、引号或换行时加引号
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn record(path: &str, started: u64, completed: bool) -> PlayRecord {
+        PlaySession::new(path.to_string(), started).finish(completed)
+    }
+
+    #[test]
+    fn test_session_listened_time() {
+        let mut session = PlaySession::new("/m/a.mp3".to_string(), 100);
+        for position in [0.5, 1.0, 1.5, 1.5, 2.0] {
+            session.update_position(position);
+        }
+        // 向前跳转和向后跳转都不计入收听时长
+        session.update_position(60.0);
+        session.update_position(61.0);
+        session.update_position(10.0);
+        session.update_position(10.5);
+
+        let record = session.finish(false);
+        assert_eq!(record.path, "/m/a.mp3");
+        assert_eq!(record.started, 100);
+        assert_eq!(record.listened, 3.5);
+        assert!(!record.completed);
+    }
+
+    #[test]
+    fn test_stats_and_round_trip() {
+        let mut history = PlayHistory::default();
+        history.record(record("/m/a.mp3", 100, true));
+        history.record(record("/m/b.mp3", 200, false));
+        history.record(record("/m/a.mp3", 300, true));
+        history.record(record("/m/a.mp3", 400, false));
+
+        assert_eq!(history.stats("/m/a.mp3"), TrackStats { play_count: 2, skip_count: 1, last_played: Some(400) });
+        assert_eq!(history.stats("/m/b.mp3"), TrackStats { play_count: 0, skip_count: 1, last_played: Some(200) });
+        assert_eq!(history.stats("/m/c.mp3"), TrackStats::default());
+        let recent: Vec<u64> = history.recent().map(|record| record.started).collect();
+        assert_eq!(recent, vec![400, 300, 200, 100]);
+
+        // 统计不保存在文件中，加载时重新生成；顺序错乱的记录按开始时间排列，半行被跳过
+        let mut lines: Vec<String> = history.records.iter().map(|record| record.to_json_line().unwrap()).collect();
+        lines.swap(0, 3);
+        lines.push("{\"path\":\"/m/c.mp3\",\"sta".to_string());
+        let loaded = PlayHistory::from_json_lines(&lines.concat());
+        assert_eq!(loaded.len(), 4);
+        assert_eq!(loaded.records, history.records);
+        assert_eq!(loaded.stats("/m/a.mp3"), history.stats("/m/a.mp3"));
+        assert!(PlayHistory::from_json_lines("").is_empty());
+    }
+
+    #[test]
+    fn test_export() {
+        let mut history = PlayHistory::default();
+        history.record(PlayRecord {
+            title: Some("Hello, \"World\"".to_string()),
+            artist: Some("Band".to_string()),
+            listened: 183.3,
+            duration: Some(185.0),
+            ..record("/m/a.mp3", 1_700_000_000, true)
+        });
+        history.record(record("/m/b.mp3", 1_700_000_200, false));
+
+        let csv = history.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], format!("{},1700000000,/m/a.mp3,\"Hello, \"\"World\"\"\",Band,183.3,185.0,true", format_timestamp(1_700_000_000)));
+        assert_eq!(lines[2], format!("{},1700000200,/m/b.mp3,,,0.0,,false", format_timestamp(1_700_000_200)));
+
+        let dir = TempDir::new("history");
+        let json_path = dir.join("history.JSON").to_string_lossy().to_string();
+        history.export(&json_path).unwrap();
+        let exported: Vec<PlayRecord> = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(exported, history.records);
+        let csv_path = dir.join("history.csv").to_string_lossy().to_string();
+        history.export(&csv_path).unwrap();
+        assert_eq!(std::fs::read_to_string(&csv_path).unwrap(), csv);
+    }
+}
@@ -0,0 +1,100 @@
+//! 单实例模块
+//!
+//! 图形界面启动时在配置目录下锁定 `summer-player.lock`。锁已被其他进程持有时，
+//! 说明播放器已在运行，新启动的进程通过远程控制套接字把要打开的文件交给它后退出。
+//! 远程控制套接字只在Unix上可用，其他平台不启用单实例。
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use crate::config::config_sibling_path;
+use crate::error::{PlayerError, Result};
+use crate::ipc::{self, IpcRequest};
+
+/// 锁文件名
+const LOCK_FILE_NAME: &str = "summer-player.lock";
+
+/// 等待已运行实例开始监听套接字的最长时间
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 重试连接的间隔
+const FORWARD_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 实例锁，持有期间其他进程无法获取，进程退出时由系统释放
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+}
+
+impl InstanceLock {
+    /// 获取锁文件路径
+    pub fn file_path() -> Result<PathBuf> {
+        config_sibling_path(LOCK_FILE_NAME)
+    }
+
+    /// 尝试获取实例锁
+    ///
+    /// # 返回
+    /// 获取成功返回锁，已有其他实例在运行时返回None
+    pub fn acquire() -> Result<Option<Self>> {
+        Self::acquire_at(Self::file_path()?)
+    }
+
+    fn acquire_at(path: PathBuf) -> Result<Option<Self>> {
+        let mut file = OpenOptions::new().create(true).truncate(false).write(true).open(path)?;
+        match file.try_lock() {
+            Ok(()) => {
+                // 记录进程号，便于排查
+                file.set_len(0)?;
+                writeln!(file, "{}", std::process::id())?;
+                Ok(Some(Self { _file: file }))
+            }
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e.into()),
+        }
+    }
+}
+
+/// 把文件交给已运行的实例播放
+///
+/// 已运行的实例可能刚启动、尚未开始监听，连接失败时会在超时前重试。
+///
+/// # 参数
+/// * `files` - 音频文件或播放列表路径，为空时只确认实例可以连接
+pub fn forward_to_running_instance(files: Vec<String>) -> Result<()> {
+    let request = if files.is_empty() { IpcRequest::Status } else { IpcRequest::open(files) };
+
+    let mut waited = Duration::ZERO;
+    loop {
+        match ipc::send_request(&request) {
+            Ok(response) if response.ok => return Ok(()),
+            Ok(response) => return Err(PlayerError::Other(response.error.unwrap_or_default())),
+            Err(_) if waited < FORWARD_TIMEOUT => {
+                thread::sleep(FORWARD_RETRY_INTERVAL);
+                waited += FORWARD_RETRY_INTERVAL;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let dir = TempDir::new("instance");
+        let path = dir.join("summer-player.lock");
+        let lock = InstanceLock::acquire_at(path.clone()).unwrap();
+        assert!(lock.is_some());
+        assert!(InstanceLock::acquire_at(path.clone()).unwrap().is_none());
+
+        drop(lock);
+        assert!(InstanceLock::acquire_at(path).unwrap().is_some());
+    }
+}
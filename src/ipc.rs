@@ -0,0 +1,312 @@
+//! 远程控制模块
+//!
+//! 图形界面运行时在Unix域套接字上监听控制命令，`summer_player ctl` 作为客户端发送命令。
+//! 协议为按行分隔的JSON：每行一个请求，播放器对每个请求回复一行。
+//!
+//! ```text
+//! {"command":"seek","position":42.0}
+//! {"ok":true,"status":{"file":"/music/a.flac","playing":true,...}}
+//! ```
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::config::config_sibling_path;
+use crate::error::{PlayerError, Result};
+
+/// 套接字文件名
+const SOCKET_FILE_NAME: &str = "summer-player.sock";
+
+/// 客户端等待回复的最长时间
+#[cfg(unix)]
+const CLIENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// 控制命令
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum IpcRequest {
+    /// 播放/暂停切换
+    PlayPause,
+    /// 开始或继续播放
+    Play,
+    /// 暂停
+    Pause,
+    /// 下一首
+    Next,
+    /// 上一首
+    Previous,
+    /// 停止播放
+    Stop,
+    /// 跳转到指定位置（秒）
+    Seek { position: f64 },
+    /// 设置音量（0.0 - 1.0）
+    SetVolume { volume: f32 },
+    /// 设置播放速度
+    SetSpeed { speed: f32 },
+    /// 打开音频文件或播放列表并开始播放
+    Open { files: Vec<String> },
+    /// 查询播放状态
+    Status,
+}
+
+impl IpcRequest {
+    /// 创建打开文件的命令
+    ///
+    /// 播放器的工作目录与发送方不同，相对路径会先转换为绝对路径。
+    pub fn open(files: Vec<String>) -> Self {
+        let files = files
+            .into_iter()
+            .map(|file| std::fs::canonicalize(&file).map(|path| path.to_string_lossy().to_string()).unwrap_or(file))
+            .collect();
+        IpcRequest::Open { files }
+    }
+}
+
+/// 播放状态快照
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStatus {
+    /// 当前文件路径
+    pub file: Option<String>,
+    /// 曲目标题
+    pub title: Option<String>,
+    /// 艺术家
+    pub artist: Option<String>,
+    /// 是否正在播放
+    pub playing: bool,
+    /// 是否已停止（没有播放会话，再次播放时从头开始）
+    #[serde(default)]
+    pub stopped: bool,
+    /// 当前播放位置（秒）
+    pub position: f64,
+    /// 总时长（秒）
+    pub duration: f64,
+    /// 音量（0.0 - 1.0）
+    pub volume: f32,
+    /// 是否静音
+    pub muted: bool,
+    /// 播放速度
+    pub speed: f32,
+}
+
+/// 对控制命令的回复
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpcResponse {
+    /// 命令是否执行成功
+    pub ok: bool,
+    /// 失败原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 执行命令后的播放状态
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<PlayerStatus>,
+}
+
+impl IpcResponse {
+    /// 成功回复
+    pub fn ok(status: PlayerStatus) -> Self {
+        Self { ok: true, error: None, status: Some(status) }
+    }
+
+    /// 失败回复
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { ok: false, error: Some(message.into()), status: None }
+    }
+}
+
+/// 从套接字收到、等待界面处理的命令
+///
+/// 界面处理完命令后调用 `reply` 回复客户端。
+#[derive(Debug, Clone)]
+pub struct IpcCall {
+    /// 收到的命令
+    pub request: IpcRequest,
+    reply: Arc<Mutex<Option<oneshot::Sender<IpcResponse>>>>,
+}
+
+impl IpcCall {
+    pub(crate) fn new(request: IpcRequest, reply: oneshot::Sender<IpcResponse>) -> Self {
+        Self { request, reply: Arc::new(Mutex::new(Some(reply))) }
+    }
+
+    /// 回复客户端，只有第一次调用有效
+    pub fn reply(&self, response: IpcResponse) {
+        if let Some(sender) = self.reply.lock().unwrap().take() {
+            let _ = sender.send(response);
+        }
+    }
+}
+
+/// 获取套接字路径
+///
+/// 套接字位于配置目录下，无法获取配置目录时使用临时目录并以用户名区分。
+pub fn socket_path() -> PathBuf {
+    config_sibling_path(SOCKET_FILE_NAME).unwrap_or_else(|_| {
+        let user = std::env::var("USER").unwrap_or_default();
+        std::env::temp_dir().join(format!("summer-player-{}.sock", user))
+    })
+}
+
+/// 监听套接字，产生等待界面处理的命令
+///
+/// 已有其他实例在监听时不抢占套接字，返回的流直接结束。
+#[cfg(unix)]
+pub fn listen() -> impl iced::futures::Stream<Item = IpcCall> {
+    iced::stream::channel(16, |output: iced::futures::channel::mpsc::Sender<IpcCall>| async move {
+        let listener = match bind_listener().await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("启动远程控制失败: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve_connection(stream, output.clone()));
+                }
+                Err(e) => {
+                    eprintln!("接受远程控制连接失败: {}", e);
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                }
+            }
+        }
+    })
+}
+
+/// 绑定套接字，清理上次异常退出留下的套接字文件
+#[cfg(unix)]
+async fn bind_listener() -> std::io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = socket_path();
+    if path.exists() {
+        if tokio::net::UnixStream::connect(&path).await.is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("{} is used by another instance", path.display()),
+            ));
+        }
+        std::fs::remove_file(&path)?;
+    }
+
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    // 只允许当前用户控制播放器
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// 处理一个连接上的所有请求
+#[cfg(unix)]
+async fn serve_connection(
+    stream: tokio::net::UnixStream,
+    mut output: iced::futures::channel::mpsc::Sender<IpcCall>,
+) {
+    use iced::futures::SinkExt;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match parse_request(&line) {
+            Ok(request) => {
+                let (sender, receiver) = oneshot::channel();
+                if output.send(IpcCall::new(request, sender)).await.is_err() {
+                    break;
+                }
+                receiver.await.unwrap_or_else(|_| IpcResponse::error("Player did not respond"))
+            }
+            Err(e) => IpcResponse::error(e.to_string()),
+        };
+
+        let Ok(mut reply) = serde_json::to_string(&response) else { break };
+        reply.push('\n');
+        if writer.write_all(reply.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// 解析一行请求
+pub fn parse_request(line: &str) -> Result<IpcRequest> {
+    serde_json::from_str(line).map_err(|e| PlayerError::Other(format!("Invalid request: {}", e)))
+}
+
+/// 向正在运行的播放器发送命令并等待回复
+///
+/// # 参数
+/// * `request` - 控制命令
+///
+/// # 返回
+/// 播放器的回复，播放器未运行时返回错误
+#[cfg(unix)]
+pub fn send_request(request: &IpcRequest) -> Result<IpcResponse> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let path = socket_path();
+    let mut stream = UnixStream::connect(&path).map_err(|e| {
+        PlayerError::Other(format!("Cannot connect to {} ({}), is Summer Player running?", path.display(), e))
+    })?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+
+    let mut line = serde_json::to_string(request).map_err(|e| PlayerError::Other(e.to_string()))?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    serde_json::from_str(&reply).map_err(|e| PlayerError::Other(format!("Invalid response: {}", e)))
+}
+
+/// 向正在运行的播放器发送命令并等待回复
+#[cfg(not(unix))]
+pub fn send_request(_request: &IpcRequest) -> Result<IpcResponse> {
+    Err(PlayerError::Other("Remote control is only supported on Unix".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_format() {
+        assert_eq!(parse_request(r#"{"command":"play_pause"}"#).unwrap(), IpcRequest::PlayPause);
+        assert_eq!(
+            parse_request(r#"{"command":"seek","position":12.5}"#).unwrap(),
+            IpcRequest::Seek { position: 12.5 },
+        );
+        assert_eq!(
+            serde_json::to_string(&IpcRequest::Open { files: vec!["/music/a.flac".to_string()] }).unwrap(),
+            r#"{"command":"open","files":["/music/a.flac"]}"#,
+        );
+        assert!(parse_request(r#"{"command":"rewind"}"#).is_err());
+        assert!(parse_request("play").is_err());
+    }
+
+    #[test]
+    fn test_response_format() {
+        let error = serde_json::to_string(&IpcResponse::error("No file loaded")).unwrap();
+        assert_eq!(error, r#"{"ok":false,"error":"No file loaded"}"#);
+
+        let response = IpcResponse::ok(PlayerStatus { playing: true, position: 3.0, ..PlayerStatus::default() });
+        let parsed: IpcResponse = serde_json::from_str(&serde_json::to_string(&response).unwrap()).unwrap();
+        assert_eq!(parsed, response);
+    }
+
+    #[test]
+    fn test_call_replies_once() {
+        let (sender, mut receiver) = oneshot::channel();
+        let call = IpcCall::new(IpcRequest::Status, sender);
+        call.clone().reply(IpcResponse::error("first"));
+        call.reply(IpcResponse::error("second"));
+        assert_eq!(receiver.try_recv().unwrap().error.as_deref(), Some("first"));
+    }
+}
@@ -6,10 +6,24 @@ pub mod error;
 pub mod audio;
 pub mod playlist;
 pub mod lyrics;
+pub mod headless;
+pub mod bookmarks;
+pub mod queue;
+pub mod library;
+pub mod watcher;
+pub mod search;
+pub mod history;
+pub mod ipc;
+#[cfg(unix)]
+pub mod instance;
+pub mod mpris;
 pub mod ui;
 pub mod config;
 pub mod utils;
 
+#[cfg(test)]
+mod test_support;
+
 // 重新导出主要的公共类型
 pub use error::{PlayerError, Result};
 pub use audio::{AudioFile, AudioInfo, PlaybackState, PlaybackCommand};
@@ -0,0 +1,542 @@
+//! 音乐库模块
+//!
+//! 递归扫描配置的音乐文件夹，提取每个音频文件的元数据，
+//! 持久化到配置目录下的 `library.json`。重新扫描时只重新读取修改时间或大小变化的文件。
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioFile;
+use crate::config::{config_sibling_path, load_or_default};
+use crate::error::{PlayerError, Result};
+use crate::utils::{extract_filename_without_extension, is_supported_audio_file, unix_timestamp};
+
+/// 音乐库索引文件名
+const LIBRARY_FILE_NAME: &str = "library.json";
+
+/// 音乐库中的一首曲目
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LibraryTrack {
+    /// 文件路径
+    pub path: String,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 修改时间（Unix时间戳，秒）
+    pub modified: u64,
+    /// 加入音乐库的时间（Unix时间戳，秒），旧版本的索引中没有时为0
+    #[serde(default)]
+    pub added: u64,
+    /// 标题
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// 艺术家
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    /// 专辑
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    /// 专辑艺术家
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_artist: Option<String>,
+    /// 流派
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    /// 年份
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<String>,
+    /// 音轨号（可能为 "3/12" 的形式）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_number: Option<String>,
+    /// 时长（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+}
+
+impl LibraryTrack {
+    /// 显示用的标题，没有标题标签时使用文件名
+    pub fn display_title(&self) -> String {
+        self.title.clone().unwrap_or_else(|| extract_filename_without_extension(&self.path))
+    }
+
+    /// 显示用的艺术家，没有艺术家标签时使用专辑艺术家
+    pub fn display_artist(&self) -> Option<&str> {
+        self.artist.as_deref().or(self.album_artist.as_deref())
+    }
+
+    /// 加入音乐库的时间，没有记录时使用文件修改时间
+    pub fn added_time(&self) -> u64 {
+        if self.added > 0 { self.added } else { self.modified }
+    }
+
+    /// 音轨号的数值部分，用于排序
+    pub(crate) fn track_number_value(&self) -> u32 {
+        self.track_number.as_deref()
+            .and_then(|number| number.split('/').next())
+            .and_then(|number| number.trim().parse().ok())
+            .unwrap_or(u32::MAX)
+    }
+}
+
+/// 音乐库的浏览方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LibraryGrouping {
+    /// 按艺术家
+    #[default]
+    Artist,
+    /// 按专辑
+    Album,
+    /// 按流派
+    Genre,
+}
+
+impl LibraryGrouping {
+    /// 所有浏览方式
+    pub const ALL: [LibraryGrouping; 3] = [LibraryGrouping::Artist, LibraryGrouping::Album, LibraryGrouping::Genre];
+
+    /// 曲目在该浏览方式下所属的分组，空字符串表示未知
+    fn key(self, track: &LibraryTrack) -> &str {
+        let value = match self {
+            LibraryGrouping::Artist => track.display_artist(),
+            LibraryGrouping::Album => track.album.as_deref(),
+            LibraryGrouping::Genre => track.genre.as_deref(),
+        };
+        value.map(str::trim).unwrap_or_default()
+    }
+}
+
+/// 一次扫描的结果统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanSummary {
+    /// 新增的文件数
+    pub added: usize,
+    /// 重新读取的已变化文件数
+    pub updated: usize,
+    /// 移除的文件数
+    pub removed: usize,
+    /// 未变化的文件数
+    pub unchanged: usize,
+}
+
+/// 音乐库
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Library {
+    /// 文件路径 -> 曲目
+    #[serde(default)]
+    tracks: BTreeMap<String, LibraryTrack>,
+}
+
+impl Library {
+    /// 获取音乐库索引文件路径
+    pub fn file_path() -> Result<PathBuf> {
+        config_sibling_path(LIBRARY_FILE_NAME)
+    }
+
+    /// 从索引文件加载，文件不存在或损坏时返回空的音乐库
+    pub fn load() -> Self {
+        load_or_default(LIBRARY_FILE_NAME, "音乐库", Self::from_json)
+    }
+
+    /// 保存到索引文件
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(Self::file_path()?, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// 安全保存音乐库（忽略错误）
+    pub fn save_safe(&self) {
+        if let Err(e) = self.save() {
+            eprintln!("保存音乐库失败: {}", e);
+        }
+    }
+
+    /// 从JSON文本解析
+    pub fn from_json(content: &str) -> Result<Self> {
+        serde_json::from_str(content).map_err(|e| PlayerError::Other(format!("Invalid library file: {}", e)))
+    }
+
+    /// 序列化为JSON文本
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| PlayerError::Other(e.to_string()))
+    }
+
+    /// 曲目数量
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// 音乐库是否为空
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    /// 按路径排序的所有曲目
+    pub fn tracks(&self) -> impl Iterator<Item = &LibraryTrack> {
+        self.tracks.values()
+    }
+
+    /// 获取指定文件的曲目
+    pub fn get(&self, file_path: &str) -> Option<&LibraryTrack> {
+        self.tracks.get(file_path)
+    }
+
+    /// 扫描音乐文件夹，更新音乐库
+    ///
+    /// # 参数
+    /// * `folders` - 音乐文件夹
+    ///
+    /// # 返回
+    /// 扫描结果统计
+    pub fn scan(&mut self, folders: &[String]) -> ScanSummary {
+        self.scan_with(folders, read_track)
+    }
+
+    /// 使用指定的元数据读取函数扫描音乐文件夹
+    ///
+    /// 修改时间和大小都未变化的文件不会重新读取；
+    /// 不再存在或不在任何文件夹中的文件被移除，但无法访问的文件夹（例如未挂载的磁盘）中的曲目保留。
+    ///
+    /// # 参数
+    /// * `folders` - 音乐文件夹
+    /// * `read` - 读取元数据的函数，参数为文件路径、大小和修改时间
+    fn scan_with(&mut self, folders: &[String], mut read: impl FnMut(&str, u64, u64) -> LibraryTrack) -> ScanSummary {
+        let mut summary = ScanSummary::default();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut unavailable: Vec<&Path> = Vec::new();
+
+        for folder in folders {
+            let root = Path::new(folder);
+            if !root.is_dir() {
+                unavailable.push(root);
+                continue;
+            }
+            for (path, size, modified) in collect_audio_files(root) {
+                // 重叠的文件夹中已扫描过的文件跳过
+                if seen.insert(path.clone()) {
+                    self.update_file(path, size, modified, &mut read, &mut summary);
+                }
+            }
+        }
+
+        let before = self.tracks.len();
+        self.tracks.retain(|path, _| {
+            seen.contains(path) || unavailable.iter().any(|root| Path::new(path).starts_with(root))
+        });
+        summary.removed = before - self.tracks.len();
+        summary
+    }
+
+    /// 只更新发生变化的路径（由文件系统监视得到）
+    ///
+    /// # 参数
+    /// * `folders` - 音乐文件夹，不在其中的路径被忽略
+    /// * `paths` - 新增、修改、删除或改名的文件或文件夹
+    ///
+    /// # 返回
+    /// 更新结果统计
+    pub fn refresh_paths(&mut self, folders: &[String], paths: &[String]) -> ScanSummary {
+        self.refresh_paths_with(folders, paths, read_track)
+    }
+
+    /// 使用指定的元数据读取函数更新发生变化的路径
+    ///
+    /// 存在的文件夹重新收集其中的音频文件；不存在的路径移除对应的曲目及该路径下的所有曲目。
+    fn refresh_paths_with(&mut self, folders: &[String], paths: &[String], mut read: impl FnMut(&str, u64, u64) -> LibraryTrack) -> ScanSummary {
+        let mut summary = ScanSummary::default();
+        for changed in paths {
+            let changed_path = Path::new(changed);
+            if !folders.iter().any(|folder| changed_path.starts_with(folder)) {
+                continue;
+            }
+            match std::fs::metadata(changed_path) {
+                Ok(metadata) if metadata.is_dir() => {
+                    for (path, size, modified) in collect_audio_files(changed_path) {
+                        self.update_file(path, size, modified, &mut read, &mut summary);
+                    }
+                }
+                Ok(metadata) => {
+                    if is_supported_audio_file(changed) {
+                        self.update_file(changed.clone(), metadata.len(), modified_secs(&metadata), &mut read, &mut summary);
+                    }
+                }
+                Err(_) => {
+                    let before = self.tracks.len();
+                    self.tracks.retain(|path, _| !Path::new(path).starts_with(changed_path));
+                    summary.removed += before - self.tracks.len();
+                }
+            }
+        }
+        summary
+    }
+
+    /// 修改时间或大小变化时重新读取文件的元数据
+    fn update_file(
+        &mut self,
+        path: String,
+        size: u64,
+        modified: u64,
+        read: &mut impl FnMut(&str, u64, u64) -> LibraryTrack,
+        summary: &mut ScanSummary,
+    ) {
+        match self.tracks.get(&path) {
+            Some(track) if track.size == size && track.modified == modified => summary.unchanged += 1,
+            existing => {
+                // 重新读取的文件保留原来加入音乐库的时间
+                let added = match existing {
+                    Some(track) => {
+                        summary.updated += 1;
+                        track.added
+                    }
+                    None => {
+                        summary.added += 1;
+                        unix_timestamp()
+                    }
+                };
+                let mut track = read(&path, size, modified);
+                track.added = added;
+                self.tracks.insert(path, track);
+            }
+        }
+    }
+
+    /// 按浏览方式分组
+    ///
+    /// # 参数
+    /// * `grouping` - 浏览方式
+    ///
+    /// # 返回
+    /// (分组名称，曲目数)，按名称排序（忽略大小写），未知分组的名称为空字符串并排在最后
+    pub fn groups(&self, grouping: LibraryGrouping) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<(bool, String), (String, usize)> = BTreeMap::new();
+        for track in self.tracks.values() {
+            let name = grouping.key(track);
+            let entry = counts.entry((name.is_empty(), name.to_lowercase())).or_insert_with(|| (name.to_string(), 0));
+            entry.1 += 1;
+        }
+        counts.into_values().collect()
+    }
+
+    /// 获取分组中的曲目
+    ///
+    /// # 参数
+    /// * `grouping` - 浏览方式
+    /// * `name` - `groups` 返回的分组名称
+    ///
+    /// # 返回
+    /// 按专辑、音轨号和标题排序的曲目
+    pub fn group_tracks(&self, grouping: LibraryGrouping, name: &str) -> Vec<&LibraryTrack> {
+        let name = name.to_lowercase();
+        let mut tracks: Vec<&LibraryTrack> = self.tracks.values()
+            .filter(|track| grouping.key(track).to_lowercase() == name)
+            .collect();
+        tracks.sort_by_cached_key(|track| {
+            (track.album.clone().unwrap_or_default().to_lowercase(), track.track_number_value(), track.display_title().to_lowercase())
+        });
+        tracks
+    }
+}
+
+impl FromIterator<LibraryTrack> for Library {
+    fn from_iter<I: IntoIterator<Item = LibraryTrack>>(tracks: I) -> Self {
+        Self {
+            tracks: tracks.into_iter().map(|track| (track.path.clone(), track)).collect(),
+        }
+    }
+}
+
+/// 递归收集文件夹中的音频文件
+///
+/// 不跟随指向文件夹的符号链接，避免循环。
+///
+/// # 返回
+/// (文件路径，大小，修改时间)
+fn collect_audio_files(root: &Path) -> Vec<(String, u64, u64)> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("无法读取文件夹 {}: {}", dir.display(), e);
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else { continue };
+            if file_type.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let path_str = path.to_string_lossy().to_string();
+            if !is_supported_audio_file(&path_str) {
+                continue;
+            }
+            // 符号链接取目标文件的信息
+            let Ok(metadata) = std::fs::metadata(&path) else { continue };
+            if !metadata.is_file() {
+                continue;
+            }
+            files.push((path_str, metadata.len(), modified_secs(&metadata)));
+        }
+    }
+
+    files
+}
+
+/// 文件的修改时间（Unix时间戳，秒），无法获取时为0
+fn modified_secs(metadata: &std::fs::Metadata) -> u64 {
+    metadata.modified().ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// 打开音频文件读取元数据，无法解析的文件仍然加入音乐库（没有标签）
+fn read_track(path: &str, size: u64, modified: u64) -> LibraryTrack {
+    let mut track = LibraryTrack {
+        path: path.to_string(),
+        size,
+        modified,
+        ..LibraryTrack::default()
+    };
+    match AudioFile::open(path) {
+        Ok(audio_file) => {
+            let metadata = audio_file.info.metadata;
+            track.title = metadata.title;
+            track.artist = metadata.artist;
+            track.album = metadata.album;
+            track.album_artist = metadata.album_artist;
+            track.genre = metadata.genre;
+            track.year = metadata.year;
+            track.track_number = metadata.track_number;
+            track.duration = audio_file.info.duration;
+        }
+        Err(e) => eprintln!("读取音乐库文件失败 {}: {}", path, e),
+    }
+    track
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::fs;
+
+    fn track(path: &str, artist: Option<&str>, album: Option<&str>, track_number: Option<&str>) -> LibraryTrack {
+        LibraryTrack {
+            path: path.to_string(),
+            artist: artist.map(str::to_string),
+            album: album.map(str::to_string),
+            track_number: track_number.map(str::to_string),
+            ..LibraryTrack::default()
+        }
+    }
+
+    fn library_of(tracks: Vec<LibraryTrack>) -> Library {
+        tracks.into_iter().collect()
+    }
+
+    #[test]
+    fn test_incremental_scan() {
+        let dir = TempDir::new("library");
+        fs::create_dir_all(dir.join("album")).unwrap();
+        fs::write(dir.join("a.mp3"), b"a").unwrap();
+        fs::write(dir.join("album/b.flac"), b"b").unwrap();
+        fs::write(dir.join("album/cover.jpg"), b"").unwrap();
+        let folders = vec![dir.to_string_lossy().to_string()];
+
+        let mut reads: Vec<String> = Vec::new();
+        let mut read = |path: &str, size: u64, modified: u64| {
+            reads.push(extract_filename_without_extension(path));
+            LibraryTrack { path: path.to_string(), size, modified, ..LibraryTrack::default() }
+        };
+
+        let mut library = Library::default();
+        let summary = library.scan_with(&folders, &mut read);
+        assert_eq!(summary, ScanSummary { added: 2, ..ScanSummary::default() });
+        assert_eq!(library.len(), 2);
+        let a_path = dir.join("a.mp3").to_string_lossy().to_string();
+        let added = library.get(&a_path).unwrap().added;
+        assert!(added > 0);
+
+        // 大小变化的文件重新读取（保留加入时间），删除的文件被移除
+        fs::write(dir.join("a.mp3"), b"changed").unwrap();
+        fs::remove_file(dir.join("album/b.flac")).unwrap();
+        let summary = library.scan_with(&folders, &mut read);
+        assert_eq!(summary, ScanSummary { updated: 1, removed: 1, ..ScanSummary::default() });
+        assert_eq!(library.get(&a_path).unwrap().added, added);
+
+        let summary = library.scan_with(&folders, &mut read);
+        assert_eq!(summary, ScanSummary { unchanged: 1, ..ScanSummary::default() });
+        assert_eq!(reads, ["a", "b", "a"]);
+    }
+
+    #[test]
+    fn test_refresh_changed_paths() {
+        let dir = TempDir::new("library-refresh");
+        fs::create_dir_all(dir.join("old")).unwrap();
+        fs::write(dir.join("old/a.mp3"), b"a").unwrap();
+        fs::write(dir.join("old/b.mp3"), b"b").unwrap();
+        let folders = vec![dir.to_string_lossy().to_string()];
+        let read = |path: &str, size: u64, modified: u64| LibraryTrack { path: path.to_string(), size, modified, ..LibraryTrack::default() };
+
+        let mut library = Library::default();
+        library.scan_with(&folders, read);
+        assert_eq!(library.len(), 2);
+
+        // 文件夹改名：旧路径移除，新路径下的文件加入
+        fs::rename(dir.join("old"), dir.join("new")).unwrap();
+        let changed = [dir.join("old"), dir.join("new"), PathBuf::from("/elsewhere/c.mp3")]
+            .map(|path| path.to_string_lossy().to_string());
+        let summary = library.refresh_paths_with(&folders, &changed, read);
+        assert_eq!(summary, ScanSummary { added: 2, removed: 2, ..ScanSummary::default() });
+        assert!(library.get(&dir.join("new/a.mp3").to_string_lossy()).is_some());
+    }
+
+    #[test]
+    fn test_unavailable_folder_keeps_tracks() {
+        let mut library = library_of(vec![track("/mnt/missing-disk/a.mp3", None, None, None)]);
+        let summary = library.scan_with(&["/mnt/missing-disk".to_string()], |_, _, _| unreachable!());
+        assert_eq!(summary, ScanSummary::default());
+        assert_eq!(library.len(), 1);
+
+        // 不在任何文件夹中的曲目被移除
+        let summary = library.scan_with(&[], |_, _, _| unreachable!());
+        assert_eq!(summary.removed, 1);
+        assert!(library.is_empty());
+    }
+
+    #[test]
+    fn test_groups_and_group_tracks() {
+        let library = library_of(vec![
+            track("/m/1.mp3", Some("beta"), Some("Second"), Some("2/10")),
+            track("/m/2.mp3", Some("Alpha"), Some("First"), None),
+            track("/m/3.mp3", Some("Beta"), Some("Second"), Some("1/10")),
+            track("/m/4.mp3", None, None, None),
+        ]);
+
+        assert_eq!(library.groups(LibraryGrouping::Artist), [
+            ("Alpha".to_string(), 1),
+            ("beta".to_string(), 2),
+            (String::new(), 1),
+        ]);
+
+        let paths: Vec<&str> = library.group_tracks(LibraryGrouping::Artist, "beta")
+            .iter()
+            .map(|track| track.path.as_str())
+            .collect();
+        assert_eq!(paths, ["/m/3.mp3", "/m/1.mp3"]);
+        assert_eq!(library.group_tracks(LibraryGrouping::Genre, "").len(), 4);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let library = library_of(vec![track("/music/夏日.flac", Some("Artist"), None, Some("1"))]);
+        let content = library.to_json().unwrap();
+        assert_eq!(Library::from_json(&content).unwrap(), library);
+        assert!(Library::from_json("not json").is_err());
+    }
+}
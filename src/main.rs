@@ -1,14 +1,18 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use iced::{Font, window};
 use sys_locale::get_locale;
 
 use summer_player::{
     PlayerApp,
-    audio::{AudioFile, list_audio_devices},
+    headless,
+    ipc::{self, IpcRequest},
+    audio::{AudioFile, list_audio_devices, output_device_names},
     utils::format_duration,
     error::Result,
     config::{fonts, AppConfig},
 };
+#[cfg(unix)]
+use summer_player::instance::{self, InstanceLock};
 
 
 
@@ -19,6 +23,9 @@ const ICON_BYTES: &[u8] = include_bytes!("../icon.png");
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long, help = "List available audio output devices")]
     list_devices: bool,
 
@@ -28,13 +35,85 @@ struct Cli {
     #[arg(short, long, help = "Show audio file information and duration without playing")]
     info: bool,
 
-    #[arg(help = "Path to audio file")]
-    file: Option<String>,
+    #[arg(long, help = "Play in the terminal without opening the GUI")]
+    no_gui: bool,
+
+    #[arg(help = "Paths to audio files or playlists (M3U, PLS, XSPF, CUE)")]
+    files: Vec<String>,
+}
+
+/// 子命令
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Play audio files or playlists in the terminal without the GUI")]
+    Play {
+        #[arg(required = true, help = "Paths to audio files or playlists (M3U, PLS, XSPF, CUE)")]
+        files: Vec<String>,
+    },
+    #[command(about = "Control the running player")]
+    Ctl {
+        #[command(subcommand)]
+        command: CtlCommand,
+    },
+}
+
+/// 远程控制命令
+#[derive(Subcommand)]
+enum CtlCommand {
+    #[command(about = "Toggle play/pause")]
+    Toggle,
+    #[command(about = "Start or resume playback")]
+    Play,
+    #[command(about = "Pause playback")]
+    Pause,
+    #[command(about = "Play the next track")]
+    Next,
+    #[command(about = "Play the previous track")]
+    Previous,
+    #[command(about = "Stop playback")]
+    Stop,
+    #[command(about = "Seek to a position in seconds")]
+    Seek {
+        position: f64,
+    },
+    #[command(about = "Set the volume in percent (0-100)")]
+    Volume {
+        percent: f32,
+    },
+    #[command(about = "Open audio files or a playlist and start playing")]
+    Open {
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+    #[command(about = "Print the playback status as JSON")]
+    Status,
+}
+
+impl CtlCommand {
+    fn into_request(self) -> IpcRequest {
+        match self {
+            CtlCommand::Toggle => IpcRequest::PlayPause,
+            CtlCommand::Play => IpcRequest::Play,
+            CtlCommand::Pause => IpcRequest::Pause,
+            CtlCommand::Next => IpcRequest::Next,
+            CtlCommand::Previous => IpcRequest::Previous,
+            CtlCommand::Stop => IpcRequest::Stop,
+            CtlCommand::Seek { position } => IpcRequest::Seek { position },
+            CtlCommand::Volume { percent } => IpcRequest::SetVolume { volume: percent / 100.0 },
+            CtlCommand::Open { files } => IpcRequest::open(files),
+            CtlCommand::Status => IpcRequest::Status,
+        }
+    }
 }
 
 fn main() {
     let args = Cli::parse();
     
+    // 远程控制客户端只转发命令，不加载配置，避免额外输出干扰JSON结果
+    if let Some(Command::Ctl { command }) = args.command {
+        std::process::exit(run_ctl(command));
+    }
+    
     // 加载配置文件，检测是否从实际文件加载
     let (mut config, config_exists) = AppConfig::load_with_source();
     
@@ -67,7 +146,30 @@ fn main() {
         return;
     }
     
-    let file_path = args.file.unwrap_or_default();
+    // 命令行指定的设备索引转换为设备名称保存
+    if let Some(index) = args.device {
+        match output_device_names().map(|names| names.get(index).cloned()) {
+            Ok(Some(name)) => config.player.device_name = Some(name),
+            Ok(None) => eprintln!("Device index {} out of range, using configured device", index),
+            Err(e) => eprintln!("Error listing devices: {}", e),
+        }
+    }
+    
+    // 无界面模式：在终端中播放
+    let headless_files = match args.command {
+        Some(Command::Play { files }) => Some(files),
+        _ if args.no_gui => Some(args.files.clone()),
+        _ => None,
+    };
+    if let Some(files) = headless_files {
+        if let Err(e) = headless::run(&files, &config) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    
+    let file_path = args.files.first().cloned().unwrap_or_default();
     
     if args.info {
         if let Err(e) = get_audio_info(&file_path) {
@@ -76,6 +178,26 @@ fn main() {
         return;
     }
     
+    // 单实例：播放器已在运行时把文件交给它播放后退出（通过远程控制套接字转交，仅支持Unix）
+    #[cfg(unix)]
+    let _instance_lock = match InstanceLock::acquire() {
+        Ok(Some(lock)) => Some(lock),
+        Ok(None) => match instance::forward_to_running_instance(args.files) {
+            Ok(()) => {
+                println!("Summer Player is already running");
+                return;
+            }
+            Err(e) => {
+                eprintln!("Failed to reach the running instance, starting a new one: {}", e);
+                None
+            }
+        },
+        Err(e) => {
+            eprintln!("Failed to acquire instance lock: {}", e);
+            None
+        }
+    };
+    
     // 创建窗口图标
     let icon = window::icon::from_file_data(ICON_BYTES, None)
         .expect("Failed to load icon");
@@ -121,6 +243,26 @@ fn main() {
         .unwrap();
 }
 
+/// 向正在运行的播放器发送命令，返回进程退出码
+fn run_ctl(command: CtlCommand) -> i32 {
+    match ipc::send_request(&command.into_request()) {
+        Ok(response) if response.ok => {
+            if let Ok(json) = serde_json::to_string(&response.status) {
+                println!("{}", json);
+            }
+            0
+        }
+        Ok(response) => {
+            eprintln!("{}", response.error.unwrap_or_default());
+            1
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
 fn get_audio_info(file_path: &str) -> Result<()> {
     let info = AudioFile::get_info(file_path)?;
     
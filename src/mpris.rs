@@ -0,0 +1,649 @@
+//! MPRIS2模块
+//!
+//! 在Linux会话总线上实现 `org.mpris.MediaPlayer2` 和 `org.mpris.MediaPlayer2.Player` 接口，
+//! 供桌面媒体键、GNOME/KDE媒体控件和 `playerctl` 控制播放。
+//! 控制请求复用远程控制模块的 `IpcRequest`，由界面统一处理；界面把播放状态快照发布给总线服务。
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+
+use crate::audio::file::CoverArt;
+use crate::ipc::{IpcCall, PlayerStatus};
+
+/// 总线名称
+#[cfg(target_os = "linux")]
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.summer_player";
+
+/// 对象路径
+#[cfg(target_os = "linux")]
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+
+/// 没有曲目时的曲目ID
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+/// 曲目ID前缀，后接曲目在播放列表中的索引
+const TRACK_ID_PREFIX: &str = "/org/summer_player/track/";
+
+/// 发布给总线服务的播放状态快照
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MprisSnapshot {
+    /// 播放状态
+    pub status: PlayerStatus,
+    /// 专辑
+    pub album: Option<String>,
+    /// 导出的封面图片URL
+    pub art_url: Option<String>,
+    /// 当前曲目在播放列表中的索引
+    pub track_index: Option<usize>,
+    /// 是否可以切换到下一首
+    pub can_go_next: bool,
+    /// 是否可以切换到上一首
+    pub can_go_previous: bool,
+    /// 跳转次数，每次跳转后递增，用于发出Seeked信号
+    pub seek_count: u64,
+}
+
+impl MprisSnapshot {
+    /// 当前曲目的ID（D-Bus对象路径）
+    pub fn track_id(&self) -> String {
+        match (&self.status.file, self.track_index) {
+            (Some(_), Some(index)) => format!("{}{}", TRACK_ID_PREFIX, index),
+            _ => NO_TRACK.to_string(),
+        }
+    }
+
+    /// MPRIS播放状态：Playing、Paused或Stopped
+    pub fn playback_status(&self) -> &'static str {
+        if self.status.file.is_none() || self.status.stopped {
+            "Stopped"
+        } else if self.status.playing {
+            "Playing"
+        } else {
+            "Paused"
+        }
+    }
+}
+
+/// 总线服务产生的事件
+#[derive(Debug, Clone)]
+pub enum MprisEvent {
+    /// 总线服务已启动，界面通过该句柄发布播放状态
+    Ready(MprisHandle),
+    /// 收到控制请求
+    Call(IpcCall),
+}
+
+/// 已导出的封面
+#[derive(Debug, Default)]
+struct ExportedCover {
+    /// 封面所属的文件和图片大小
+    key: Option<(String, usize)>,
+    /// 导出的图片路径
+    path: Option<PathBuf>,
+}
+
+/// 界面发布播放状态的句柄
+#[derive(Debug, Clone)]
+pub struct MprisHandle {
+    state: Arc<watch::Sender<MprisSnapshot>>,
+    cover: Arc<Mutex<ExportedCover>>,
+}
+
+impl MprisHandle {
+    /// 创建句柄，返回总线服务读取快照的接收端
+    pub fn new() -> (Self, watch::Receiver<MprisSnapshot>) {
+        let (sender, receiver) = watch::channel(MprisSnapshot::default());
+        let handle = Self {
+            state: Arc::new(sender),
+            cover: Arc::new(Mutex::new(ExportedCover::default())),
+        };
+        (handle, receiver)
+    }
+
+    /// 发布播放状态，内容没有变化时不通知总线服务
+    ///
+    /// # 参数
+    /// * `snapshot` - 播放状态快照（`art_url` 会被重新填写）
+    /// * `cover_art` - 当前曲目的封面，切换曲目时导出到临时文件
+    pub fn publish(&self, mut snapshot: MprisSnapshot, cover_art: Option<&CoverArt>) {
+        snapshot.art_url = self.export_cover(snapshot.status.file.as_deref(), cover_art);
+        self.state.send_if_modified(|current| {
+            if *current == snapshot {
+                return false;
+            }
+            *current = snapshot;
+            true
+        });
+    }
+
+    /// 导出封面并返回其URL，同一曲目只导出一次
+    fn export_cover(&self, file: Option<&str>, cover_art: Option<&CoverArt>) -> Option<String> {
+        let mut cover = self.cover.lock().unwrap();
+        let key = file.zip(cover_art).map(|(file, art)| (file.to_string(), art.data.len()));
+        if cover.key != key {
+            if let Some(old) = cover.path.take() {
+                let _ = std::fs::remove_file(old);
+            }
+            cover.path = cover_art.filter(|_| key.is_some()).and_then(|art| match write_cover_art(art) {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    eprintln!("导出封面失败: {}", e);
+                    None
+                }
+            });
+            cover.key = key;
+        }
+        cover.path.as_deref().map(file_url)
+    }
+}
+
+/// 把封面写入临时目录
+///
+/// 文件名包含图片内容的哈希，使媒体控件在切换曲目后重新加载封面。
+fn write_cover_art(art: &CoverArt) -> std::io::Result<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    art.data.hash(&mut hasher);
+    let extension = match art.mime_type.as_str() {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/bmp" => "bmp",
+        _ => "jpg",
+    };
+    let path = std::env::temp_dir().join(format!("summer-player-cover-{:016x}.{}", hasher.finish(), extension));
+    std::fs::write(&path, &art.data)?;
+    Ok(path)
+}
+
+/// 将本地路径转换为 `file://` URL
+pub fn file_url(path: &Path) -> String {
+    let mut url = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => url.push(byte as char),
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+/// 将 `file://` URL 转换为本地路径
+///
+/// # 返回
+/// 不是 `file://` URL 或编码无效时返回None
+pub fn path_from_file_url(url: &str) -> Option<String> {
+    let encoded = url.strip_prefix("file://")?;
+    // 跳过主机名（通常为空或localhost）
+    let encoded = &encoded[encoded.find('/')?..];
+
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(target_os = "linux")]
+pub use server::listen;
+
+#[cfg(target_os = "linux")]
+mod server {
+    use std::collections::HashMap;
+
+    use iced::futures::{channel::mpsc, SinkExt, Stream};
+    use tokio::sync::{oneshot, watch};
+    use zbus::{fdo, interface, object_server::SignalEmitter, zvariant::{ObjectPath, OwnedValue, Value}};
+
+    use super::{path_from_file_url, MprisEvent, MprisHandle, MprisSnapshot, BUS_NAME, OBJECT_PATH};
+    use crate::audio::time_stretch::{MAX_SPEED, MIN_SPEED};
+    use crate::ipc::{IpcCall, IpcRequest};
+    use crate::utils::{extract_filename, is_supported_audio_file};
+
+    /// 支持打开的MIME类型
+    const SUPPORTED_MIME_TYPES: &[&str] = &[
+        "audio/mpeg", "audio/flac", "audio/x-flac", "audio/wav", "audio/x-wav", "audio/ogg",
+        "audio/aac", "audio/mp4", "audio/x-m4a", "audio/opus", "audio/x-mpegurl",
+    ];
+
+    /// 启动总线服务，产生总线服务事件
+    ///
+    /// 会话总线不可用时打印错误，返回的流直接结束。
+    pub fn listen() -> impl Stream<Item = MprisEvent> {
+        iced::stream::channel(16, |mut output: mpsc::Sender<MprisEvent>| async move {
+            let (handle, mut state) = MprisHandle::new();
+            let connection = match connect(output.clone(), state.clone()).await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    eprintln!("启动MPRIS服务失败: {}", e);
+                    return;
+                }
+            };
+            if output.send(MprisEvent::Ready(handle)).await.is_err() {
+                return;
+            }
+
+            let mut previous = state.borrow_and_update().clone();
+            while state.changed().await.is_ok() {
+                let current = state.borrow_and_update().clone();
+                if let Err(e) = emit_changes(&connection, &previous, &current).await {
+                    eprintln!("发送MPRIS信号失败: {}", e);
+                }
+                previous = current;
+            }
+        })
+    }
+
+    /// 连接会话总线并注册接口，总线名称已被占用时附加进程号
+    async fn connect(
+        calls: mpsc::Sender<MprisEvent>,
+        state: watch::Receiver<MprisSnapshot>,
+    ) -> zbus::Result<zbus::Connection> {
+        let connection = serve(zbus::connection::Builder::session()?, calls, state).await?;
+        if connection.request_name(BUS_NAME).await.is_err() {
+            connection.request_name(format!("{}.instance{}", BUS_NAME, std::process::id())).await?;
+        }
+        Ok(connection)
+    }
+
+    /// 在连接上注册两个接口
+    pub(super) async fn serve(
+        builder: zbus::connection::Builder<'_>,
+        calls: mpsc::Sender<MprisEvent>,
+        state: watch::Receiver<MprisSnapshot>,
+    ) -> zbus::Result<zbus::Connection> {
+        builder
+            .serve_at(OBJECT_PATH, MediaPlayer2)?
+            .serve_at(OBJECT_PATH, Player { calls, state })?
+            .build()
+            .await
+    }
+
+    /// 根据快照的变化发出PropertiesChanged和Seeked信号
+    async fn emit_changes(
+        connection: &zbus::Connection,
+        previous: &MprisSnapshot,
+        current: &MprisSnapshot,
+    ) -> zbus::Result<()> {
+        let iface = connection.object_server().interface::<_, Player>(OBJECT_PATH).await?;
+        let player = iface.get().await;
+        let emitter = iface.signal_emitter();
+        let (old, new) = (&previous.status, &current.status);
+
+        if previous.playback_status() != current.playback_status() {
+            player.playback_status_changed(emitter).await?;
+        }
+        if old.file != new.file
+            || old.title != new.title
+            || old.artist != new.artist
+            || old.duration != new.duration
+            || previous.album != current.album
+            || previous.art_url != current.art_url
+            || previous.track_index != current.track_index
+        {
+            player.metadata_changed(emitter).await?;
+        }
+        if old.volume != new.volume || old.muted != new.muted {
+            player.volume_changed(emitter).await?;
+        }
+        if old.speed != new.speed {
+            player.rate_changed(emitter).await?;
+        }
+        if previous.can_go_next != current.can_go_next {
+            player.can_go_next_changed(emitter).await?;
+        }
+        if previous.can_go_previous != current.can_go_previous {
+            player.can_go_previous_changed(emitter).await?;
+        }
+        if old.file.is_some() != new.file.is_some() {
+            player.can_play_changed(emitter).await?;
+            player.can_pause_changed(emitter).await?;
+            player.can_seek_changed(emitter).await?;
+        }
+        if previous.seek_count != current.seek_count {
+            Player::seeked(emitter, seconds_to_micros(new.position)).await?;
+        }
+        Ok(())
+    }
+
+    fn seconds_to_micros(seconds: f64) -> i64 {
+        (seconds * 1_000_000.0) as i64
+    }
+
+    fn owned<'a>(value: impl Into<Value<'a>>) -> OwnedValue {
+        value.into().try_to_owned().expect("元数据不包含文件描述符")
+    }
+
+    /// org.mpris.MediaPlayer2
+    struct MediaPlayer2;
+
+    #[interface(name = "org.mpris.MediaPlayer2")]
+    impl MediaPlayer2 {
+        fn raise(&self) {}
+
+        fn quit(&self) {}
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn can_quit(&self) -> bool {
+            false
+        }
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn can_raise(&self) -> bool {
+            false
+        }
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn has_track_list(&self) -> bool {
+            false
+        }
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn identity(&self) -> String {
+            "Summer Player".to_string()
+        }
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn desktop_entry(&self) -> String {
+            "summer-player".to_string()
+        }
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn supported_uri_schemes(&self) -> Vec<String> {
+            vec!["file".to_string()]
+        }
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn supported_mime_types(&self) -> Vec<String> {
+            SUPPORTED_MIME_TYPES.iter().map(|mime| mime.to_string()).collect()
+        }
+    }
+
+    /// org.mpris.MediaPlayer2.Player
+    struct Player {
+        calls: mpsc::Sender<MprisEvent>,
+        state: watch::Receiver<MprisSnapshot>,
+    }
+
+    impl Player {
+        fn snapshot(&self) -> MprisSnapshot {
+            self.state.borrow().clone()
+        }
+
+        /// 把请求交给界面处理并等待结果
+        async fn call(&self, request: IpcRequest) -> fdo::Result<()> {
+            let (sender, receiver) = oneshot::channel();
+            self.calls
+                .clone()
+                .send(MprisEvent::Call(IpcCall::new(request, sender)))
+                .await
+                .map_err(|_| fdo::Error::Failed("Player is not running".to_string()))?;
+            match receiver.await {
+                Ok(response) if response.ok => Ok(()),
+                Ok(response) => Err(fdo::Error::Failed(response.error.unwrap_or_default())),
+                Err(_) => Err(fdo::Error::Failed("Player did not respond".to_string())),
+            }
+        }
+    }
+
+    #[interface(name = "org.mpris.MediaPlayer2.Player")]
+    impl Player {
+        async fn next(&self) -> fdo::Result<()> {
+            self.call(IpcRequest::Next).await
+        }
+
+        async fn previous(&self) -> fdo::Result<()> {
+            self.call(IpcRequest::Previous).await
+        }
+
+        async fn pause(&self) -> fdo::Result<()> {
+            self.call(IpcRequest::Pause).await
+        }
+
+        async fn play_pause(&self) -> fdo::Result<()> {
+            self.call(IpcRequest::PlayPause).await
+        }
+
+        async fn stop(&self) -> fdo::Result<()> {
+            self.call(IpcRequest::Stop).await
+        }
+
+        async fn play(&self) -> fdo::Result<()> {
+            self.call(IpcRequest::Play).await
+        }
+
+        /// 相对当前位置跳转（微秒），超出曲目末尾时切换到下一首
+        async fn seek(&self, offset: i64) -> fdo::Result<()> {
+            let status = self.snapshot().status;
+            if status.file.is_none() {
+                return Ok(());
+            }
+            let position = (status.position + offset as f64 / 1_000_000.0).max(0.0);
+            if status.duration > 0.0 && position >= status.duration {
+                self.call(IpcRequest::Next).await
+            } else {
+                self.call(IpcRequest::Seek { position }).await
+            }
+        }
+
+        /// 跳转到指定位置（微秒），曲目ID与当前曲目不符时忽略
+        async fn set_position(&self, track_id: ObjectPath<'_>, position: i64) -> fdo::Result<()> {
+            let snapshot = self.snapshot();
+            let position = position as f64 / 1_000_000.0;
+            if track_id.as_str() != snapshot.track_id()
+                || position < 0.0
+                || (snapshot.status.duration > 0.0 && position > snapshot.status.duration)
+            {
+                return Ok(());
+            }
+            self.call(IpcRequest::Seek { position }).await
+        }
+
+        async fn open_uri(&self, uri: &str) -> fdo::Result<()> {
+            let path = path_from_file_url(uri)
+                .ok_or_else(|| fdo::Error::InvalidArgs(format!("Unsupported URI: {}", uri)))?;
+            if !is_supported_audio_file(&path) {
+                return Err(fdo::Error::InvalidArgs(format!("Unsupported file: {}", path)));
+            }
+            self.call(IpcRequest::Open { files: vec![path] }).await
+        }
+
+        #[zbus(signal)]
+        async fn seeked(emitter: &SignalEmitter<'_>, position: i64) -> zbus::Result<()>;
+
+        #[zbus(property)]
+        fn playback_status(&self) -> String {
+            self.snapshot().playback_status().to_string()
+        }
+
+        #[zbus(property)]
+        fn rate(&self) -> f64 {
+            self.snapshot().status.speed as f64
+        }
+
+        #[zbus(property)]
+        async fn set_rate(&self, rate: f64) {
+            // 规范要求忽略0，其余值由界面限制到支持的范围
+            if rate > 0.0 {
+                let _ = self.call(IpcRequest::SetSpeed { speed: rate as f32 }).await;
+            }
+        }
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn minimum_rate(&self) -> f64 {
+            MIN_SPEED as f64
+        }
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn maximum_rate(&self) -> f64 {
+            MAX_SPEED as f64
+        }
+
+        #[zbus(property)]
+        fn metadata(&self) -> HashMap<String, OwnedValue> {
+            let snapshot = self.snapshot();
+            let status = &snapshot.status;
+            let mut metadata = HashMap::new();
+
+            let track_id = ObjectPath::try_from(snapshot.track_id()).expect("曲目ID是合法的对象路径");
+            metadata.insert("mpris:trackid".to_string(), owned(track_id));
+            let Some(file) = &status.file else {
+                return metadata;
+            };
+
+            if status.duration > 0.0 {
+                metadata.insert("mpris:length".to_string(), owned(seconds_to_micros(status.duration)));
+            }
+            let title = status.title.clone().unwrap_or_else(|| extract_filename(file));
+            metadata.insert("xesam:title".to_string(), owned(title));
+            if let Some(artist) = &status.artist {
+                metadata.insert("xesam:artist".to_string(), owned(vec![artist.clone()]));
+            }
+            if let Some(album) = &snapshot.album {
+                metadata.insert("xesam:album".to_string(), owned(album.clone()));
+            }
+            if let Some(art_url) = &snapshot.art_url {
+                metadata.insert("mpris:artUrl".to_string(), owned(art_url.clone()));
+            }
+            metadata.insert("xesam:url".to_string(), owned(super::file_url(std::path::Path::new(file))));
+            metadata
+        }
+
+        #[zbus(property)]
+        fn volume(&self) -> f64 {
+            let status = self.snapshot().status;
+            if status.muted { 0.0 } else { status.volume as f64 }
+        }
+
+        #[zbus(property)]
+        async fn set_volume(&self, volume: f64) {
+            let _ = self.call(IpcRequest::SetVolume { volume: volume.clamp(0.0, 1.0) as f32 }).await;
+        }
+
+        /// 播放位置由客户端轮询，不发出属性变化信号
+        #[zbus(property(emits_changed_signal = "false"))]
+        fn position(&self) -> i64 {
+            seconds_to_micros(self.snapshot().status.position)
+        }
+
+        #[zbus(property)]
+        fn can_go_next(&self) -> bool {
+            self.snapshot().can_go_next
+        }
+
+        #[zbus(property)]
+        fn can_go_previous(&self) -> bool {
+            self.snapshot().can_go_previous
+        }
+
+        #[zbus(property)]
+        fn can_play(&self) -> bool {
+            self.snapshot().status.file.is_some()
+        }
+
+        #[zbus(property)]
+        fn can_pause(&self) -> bool {
+            self.snapshot().status.file.is_some()
+        }
+
+        #[zbus(property)]
+        fn can_seek(&self) -> bool {
+            self.snapshot().status.file.is_some()
+        }
+
+        #[zbus(property(emits_changed_signal = "const"))]
+        fn can_control(&self) -> bool {
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_url_round_trip() {
+        let path = "/home/user/音乐/My Song #1.flac";
+        let url = file_url(Path::new(path));
+        assert!(url.starts_with("file:///home/user/%E9%9F%B3%E4%B9%90/My%20Song%20%231.flac"));
+        assert_eq!(path_from_file_url(&url).as_deref(), Some(path));
+        assert_eq!(path_from_file_url("file://localhost/tmp/a.mp3").as_deref(), Some("/tmp/a.mp3"));
+        assert_eq!(path_from_file_url("https://example.com/a.mp3"), None);
+        assert_eq!(path_from_file_url("file:///bad%zz"), None);
+    }
+
+    #[test]
+    fn test_snapshot_status() {
+        let mut snapshot = MprisSnapshot::default();
+        assert_eq!(snapshot.playback_status(), "Stopped");
+        assert_eq!(snapshot.track_id(), NO_TRACK);
+
+        snapshot.status.file = Some("/music/a.flac".to_string());
+        snapshot.track_index = Some(3);
+        assert_eq!(snapshot.playback_status(), "Paused");
+        assert_eq!(snapshot.track_id(), "/org/summer_player/track/3");
+
+        snapshot.status.playing = true;
+        assert_eq!(snapshot.playback_status(), "Playing");
+
+        // 停止后仍保留当前文件，但不再有播放会话
+        snapshot.status.playing = false;
+        snapshot.status.stopped = true;
+        assert_eq!(snapshot.playback_status(), "Stopped");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_player_interface_over_p2p() {
+        use iced::futures::{channel::mpsc, StreamExt};
+        use zbus::zvariant::OwnedValue;
+
+        use crate::ipc::{IpcRequest, IpcResponse};
+
+        let (handle, state) = MprisHandle::new();
+        let mut snapshot = MprisSnapshot::default();
+        snapshot.status.file = Some("/music/a.flac".to_string());
+        snapshot.status.title = Some("Summer".to_string());
+        snapshot.status.playing = true;
+        snapshot.track_index = Some(0);
+        handle.publish(snapshot, None);
+
+        let (calls, mut received) = mpsc::channel(4);
+        let (server_stream, client_stream) = tokio::net::UnixStream::pair().unwrap();
+        let guid = zbus::Guid::generate();
+        let server_builder = zbus::connection::Builder::unix_stream(server_stream).server(guid).unwrap().p2p();
+        let client_builder = zbus::connection::Builder::unix_stream(client_stream).p2p();
+        let (server, client) = tokio::join!(server::serve(server_builder, calls, state), client_builder.build());
+        let (_server, client) = (server.unwrap(), client.unwrap());
+
+        let proxy = zbus::Proxy::new(&client, BUS_NAME, OBJECT_PATH, "org.mpris.MediaPlayer2.Player").await.unwrap();
+        let status: String = proxy.get_property("PlaybackStatus").await.unwrap();
+        assert_eq!(status, "Playing");
+        let metadata: std::collections::HashMap<String, OwnedValue> = proxy.get_property("Metadata").await.unwrap();
+        assert_eq!(String::try_from(metadata["xesam:title"].clone()).unwrap(), "Summer");
+
+        let responder = tokio::spawn(async move {
+            match received.next().await {
+                Some(MprisEvent::Call(call)) => {
+                    let request = call.request.clone();
+                    call.reply(IpcResponse::ok(Default::default()));
+                    request
+                }
+                _ => panic!("expected a control request"),
+            }
+        });
+        let _: () = proxy.call("PlayPause", &()).await.unwrap();
+        assert_eq!(responder.await.unwrap(), IpcRequest::PlayPause);
+    }
+}
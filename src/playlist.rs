@@ -4,16 +4,26 @@
 
 use std::{
     fs,
-    io::{BufRead, BufReader},
     path::{Path, PathBuf},
     collections::HashMap,
 };
 
 use crate::error::{PlayerError, Result};
-use crate::utils::{extract_filename, normalize_path};
+use crate::utils::{extract_filename, is_playlist_file, is_smart_playlist_file, normalize_path, unix_timestamp};
 use crate::audio::AudioFile;
+use crate::audio::file::AudioMetadata;
+use crate::library::{Library, LibraryTrack};
 use crate::ui::components::PlayMode;
 
+pub mod format;
+pub mod smart;
+
+use format::{format_for_path, PlaylistDocument, PlaylistEntry};
+use smart::{SmartPlaylist, TrackStats};
+
+/// 随机播放最多记录的历史曲目数
+const MAX_SHUFFLE_HISTORY: usize = 500;
+
 pub struct PlaylistExtraInfo {
     /// 文件路径
     pub path: String,
@@ -21,6 +31,10 @@ pub struct PlaylistExtraInfo {
     pub name: Option<String>,
     /// 文件大小
     pub duration: Option<f64>,
+    /// 该曲目前面的其他M3U指令（原样保留，保存时写回）
+    pub directives: Vec<String>,
+    /// 文件中包含的曲目数（CUE整轨镜像）
+    pub track_count: Option<usize>,
 }
 
 impl PlaylistExtraInfo {
@@ -36,6 +50,8 @@ impl PlaylistExtraInfo {
             path,
             name: None,
             duration: None,
+            directives: Vec::new(),
+            track_count: None,
         }
     }
     
@@ -62,6 +78,30 @@ impl PlaylistExtraInfo {
         self.name = Some(name);
         self
     }
+    
+    /// 设置曲目前面的其他M3U指令
+    /// 
+    /// # 参数
+    /// * `directives` - 指令行（含开头的 `#`）
+    /// 
+    /// # 返回
+    /// 更新后的PlaylistItem实例
+    pub fn with_directives(mut self, directives: Vec<String>) -> Self {
+        self.directives = directives;
+        self
+    }
+    
+    /// 设置文件中包含的曲目数
+    /// 
+    /// # 参数
+    /// * `track_count` - 曲目数，只有一首时为None
+    /// 
+    /// # 返回
+    /// 更新后的PlaylistItem实例
+    pub fn with_track_count(mut self, track_count: Option<usize>) -> Self {
+        self.track_count = track_count;
+        self
+    }
 }
 
 pub struct PlaylistItem {
@@ -167,6 +207,21 @@ impl AudioFileCache {
     }
 }
 
+/// 播放列表文件中除曲目外需要原样写回的内容
+#[derive(Debug, Clone, Default, PartialEq)]
+struct PlaylistDirectives {
+    /// 播放列表标题（XSPF）
+    title: Option<String>,
+    /// 文件头指令（M3U的 `#EXTM3U`、`#PLAYLIST:` 等）
+    header: Vec<String>,
+    /// 最后一首曲目之后的指令
+    trailer: Vec<String>,
+    /// 无法播放的条目（之前的已加载曲目数，条目），例如找不到的文件和网络流
+    missing_entries: Vec<(usize, PlaylistEntry)>,
+    /// 解析后的路径 -> 文件中记录的原始位置，写回原目录时保持原来的写法（如 `../` 开头的相对路径）
+    locations: HashMap<String, String>,
+}
+
 /// 播放列表
 pub struct Playlist {
     /// 播放列表文件路径
@@ -179,6 +234,12 @@ pub struct Playlist {
     extra_infos: HashMap<String, PlaylistExtraInfo>,
     /// 播放列表文件路径（临时播放列表为None）
     file_path: Option<String>,
+    /// 随机播放本轮尚未播放的曲目索引（末尾为下一首）
+    shuffle_bag: Vec<usize>,
+    /// 播放过的曲目索引（末尾为上一首）
+    history: Vec<usize>,
+    /// 从播放列表文件读入、保存时写回的其他内容
+    directives: PlaylistDirectives,
 }
 
 impl Playlist {
@@ -190,6 +251,9 @@ impl Playlist {
             name: None,
             extra_infos: HashMap::new(),
             file_path: None,
+            shuffle_bag: Vec::new(),
+            history: Vec::new(),
+            directives: PlaylistDirectives::default(),
         }
     }
     
@@ -204,6 +268,9 @@ impl Playlist {
             name: Some(name),
             extra_infos: HashMap::new(),
             file_path: None,
+            shuffle_bag: Vec::new(),
+            history: Vec::new(),
+            directives: PlaylistDirectives::default(),
         }
     }
     
@@ -213,6 +280,7 @@ impl Playlist {
     /// * `file_path` - 音频文件路径
     pub fn add_file(&mut self, file_path: String) {
         self.file_paths.push(file_path);
+        self.add_to_shuffle_bag(self.file_paths.len() - 1..self.file_paths.len());
     }
     
     /// 批量添加文件路径到播放列表
@@ -220,7 +288,9 @@ impl Playlist {
     /// # 参数
     /// * `file_paths` - 音频文件路径向量
     pub fn add_files(&mut self, file_paths: Vec<String>) {
+        let start = self.file_paths.len();
         self.file_paths.extend(file_paths);
+        self.add_to_shuffle_bag(start..self.file_paths.len());
     }
     
     /// 获取当前播放文件路径
@@ -291,46 +361,57 @@ impl Playlist {
             return (None, false);
         }
         
+        let should_restart = match play_mode {
+            PlayMode::SingleLoop => true,
+            PlayMode::ListLoop => false,
+            // 只有一首歌时随机播放等同于重复播放
+            PlayMode::Random => self.file_paths.len() == 1,
+        };
+        
+        if let Some(index) = self.peek_next_index_with_mode(play_mode) {
+            self.advance_to(index);
+        }
+        (self.current_file_path(), should_restart)
+    }
+    
+    /// 根据播放模式预先确定下一首的索引，但不改变当前播放位置
+    /// 
+    /// 用于无缝播放时提前准备下一首，调用方应保存返回的索引并在切换时通过
+    /// `set_current_index` 使用它。随机模式下本轮曲目都已播放时会开始新一轮洗牌，
+    /// 在切换前重复调用返回相同的结果。
+    /// 
+    /// # 参数
+    /// * `play_mode` - 播放模式
+    /// 
+    /// # 返回
+    /// 下一首的索引，播放列表为空时返回None
+    pub fn peek_next_index_with_mode(&mut self, play_mode: &PlayMode) -> Option<usize> {
+        if self.file_paths.is_empty() {
+            return None;
+        }
+        
         match play_mode {
             PlayMode::SingleLoop => {
                 // 单曲循环：保持当前歌曲
-                (self.current_file_path(), true)
+                self.current_index
             }
             PlayMode::ListLoop => {
                 // 列表循环：到末尾后回到开头
-                let next_index = match self.current_index {
-                    Some(current) => {
-                        if current + 1 < self.file_paths.len() {
-                            Some(current + 1)
-                        } else {
-                            Some(0) // 回到开头
-                        }
-                    }
-                    None => Some(0), // 开始播放
-                };
-                
-                self.current_index = next_index;
-                (self.current_file_path(), false)
+                match self.current_index {
+                    Some(current) if current + 1 < self.file_paths.len() => Some(current + 1),
+                    _ => Some(0), // 回到开头或开始播放
+                }
             }
             PlayMode::Random => {
-                // 随机播放：随机选择一首歌曲
+                // 随机播放：每一轮按洗牌顺序把所有曲目各播放一次
                 if self.file_paths.len() == 1 {
                     // 只有一首歌，重复播放
-                    (self.current_file_path(), true)
+                    self.current_index
                 } else {
-                    use rand::Rng;
-                    let mut rng = rand::thread_rng();
-                    let mut next_index = rng.gen_range(0..self.file_paths.len());
-                    
-                    // 确保不会连续播放同一首歌（如果可能的话）
-                    if let Some(current) = self.current_index {
-                        while next_index == current && self.file_paths.len() > 1 {
-                            next_index = rng.gen_range(0..self.file_paths.len());
-                        }
+                    if self.shuffle_bag.is_empty() {
+                        self.refill_shuffle_bag();
                     }
-                    
-                    self.current_index = Some(next_index);
-                    (self.current_file_path(), false)
+                    self.shuffle_bag.last().copied()
                 }
             }
         }
@@ -376,25 +457,20 @@ impl Playlist {
                 (self.current_file_path(), false)
             }
             PlayMode::Random => {
-                // 随机播放：随机选择一首歌曲
-                if self.file_paths.len() == 1 {
-                    // 只有一首歌，重复播放
-                    (self.current_file_path(), true)
-                } else {
-                    use rand::Rng;
-                    let mut rng = rand::thread_rng();
-                    let mut prev_index = rng.gen_range(0..self.file_paths.len());
-                    
-                    // 确保不会连续播放同一首歌（如果可能的话）
-                    if let Some(current) = self.current_index {
-                        while prev_index == current && self.file_paths.len() > 1 {
-                            prev_index = rng.gen_range(0..self.file_paths.len());
-                        }
-                    }
-                    
-                    self.current_index = Some(prev_index);
-                    (self.current_file_path(), false)
+                // 随机播放：回到实际播放过的上一首
+                let Some(prev_index) = self.history.pop() else {
+                    // 没有播放历史，重新播放当前歌曲
+                    return (self.current_file_path(), true);
+                };
+                
+                // 当前歌曲放回洗牌队列，再次切换下一首时回到这里
+                if let Some(current) = self.current_index {
+                    self.shuffle_bag.retain(|&index| index != current);
+                    self.shuffle_bag.push(current);
                 }
+                self.shuffle_bag.retain(|&index| index != prev_index);
+                self.current_index = Some(prev_index);
+                (self.current_file_path(), false)
             }
         }
     }
@@ -408,13 +484,53 @@ impl Playlist {
     /// 指定索引的文件路径引用，如果索引无效则返回None
     pub fn set_current_index(&mut self, index: usize) -> Option<&String> {
         if index < self.file_paths.len() {
-            self.current_index = Some(index);
+            self.advance_to(index);
             self.current_file_path()
         } else {
             None
         }
     }
     
+    /// 切换到指定曲目，记录播放历史并从本轮洗牌队列中移除该曲目
+    fn advance_to(&mut self, index: usize) {
+        if let Some(current) = self.current_index.filter(|&current| current != index) {
+            self.history.push(current);
+            if self.history.len() > MAX_SHUFFLE_HISTORY {
+                self.history.remove(0);
+            }
+        }
+        self.shuffle_bag.retain(|&queued| queued != index);
+        self.current_index = Some(index);
+    }
+    
+    /// 开始新一轮洗牌
+    /// 
+    /// 当前歌曲作为新一轮的第一首，其余曲目打乱顺序后依次播放。
+    fn refill_shuffle_bag(&mut self) {
+        use rand::seq::SliceRandom;
+        
+        let mut bag: Vec<usize> = (0..self.file_paths.len())
+            .filter(|&index| Some(index) != self.current_index)
+            .collect();
+        bag.shuffle(&mut rand::thread_rng());
+        self.shuffle_bag = bag;
+    }
+    
+    /// 把新添加的曲目随机插入本轮洗牌队列
+    fn add_to_shuffle_bag(&mut self, indices: std::ops::Range<usize>) {
+        use rand::Rng;
+        
+        // 队列为空时下一轮洗牌会包含新曲目
+        if self.shuffle_bag.is_empty() {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        for index in indices {
+            let position = rng.gen_range(0..=self.shuffle_bag.len());
+            self.shuffle_bag.insert(position, index);
+        }
+    }
+    
     /// 获取当前播放索引
     /// 
     /// # 返回
@@ -470,6 +586,18 @@ impl Playlist {
     pub fn is_temporary(&self) -> bool {
         self.file_path.is_none()
     }
+
+    /// 检查是否为按规则生成的智能播放列表（不能编辑）
+    pub fn is_smart(&self) -> bool {
+        self.file_path.as_deref().is_some_and(is_smart_playlist_file)
+    }
+
+    /// 检查是否不能编辑：智能播放列表或只读格式（如CUE）的播放列表文件
+    pub fn is_read_only(&self) -> bool {
+        self.is_smart() || self.file_path.as_deref()
+            .and_then(format_for_path)
+            .is_some_and(|format| !format.is_writable())
+    }
     
     /// 创建临时播放列表（用于多个音频文件）
     /// 
@@ -485,6 +613,9 @@ impl Playlist {
             name: None,
             extra_infos: HashMap::new(),
             file_path: None, // 临时播放列表没有文件路径
+            shuffle_bag: Vec::new(),
+            history: Vec::new(),
+            directives: PlaylistDirectives::default(),
         };
         
         // 为每个文件路径添加到播放列表
@@ -500,6 +631,25 @@ impl Playlist {
         playlist
     }
     
+    /// 用音乐库中的曲目创建临时播放列表，曲目名称使用 "艺术家 - 标题"
+    /// 
+    /// # 参数
+    /// * `tracks` - 音乐库曲目
+    /// 
+    /// # 返回
+    /// 临时播放列表实例
+    pub fn create_from_library_tracks(tracks: &[&LibraryTrack]) -> Self {
+        let mut playlist = Self::create_from_audio_files(tracks.iter().map(|track| track.path.clone()).collect());
+        for track in tracks {
+            let name = match track.display_artist() {
+                Some(artist) => format!("{} - {}", artist, track.display_title()),
+                None => track.display_title(),
+            };
+            playlist.set_extra_info(PlaylistExtraInfo::new(track.path.clone()).with_name(name).with_duration(track.duration));
+        }
+        playlist
+    }
+    
     /// 创建持久播放列表（从文件加载）
     /// 
     /// # 参数
@@ -510,80 +660,186 @@ impl Playlist {
     /// 成功时返回Playlist实例，失败时返回错误
     pub fn create_from_playlist_file(file_path: String) -> Result<Self> {
         let name = extract_filename(&file_path);
-        // 打开并读取播放列表文件
-        let file = fs::File::open(&file_path)
+        let format = format_for_path(&file_path)
+            .ok_or_else(|| PlayerError::PlaylistError(format!("Unsupported playlist format: {}", file_path)))?;
+        // 读取播放列表文件，非UTF-8内容按有损方式转换
+        let content = fs::read(&file_path)
             .map_err(|e| PlayerError::PlaylistError(format!("Failed to open playlist file: {}", e)))?;
+        let document = format.read(&String::from_utf8_lossy(&content))?;
 
-        let reader = BufReader::new(file);
         let mut playlist = Self {
             file_paths: Vec::new(),
             current_index: None,
             name: Some(name),
             extra_infos: HashMap::new(),
             file_path: Some(file_path.clone()),
+            shuffle_bag: Vec::new(),
+            history: Vec::new(),
+            directives: PlaylistDirectives {
+                title: document.title,
+                header: document.header,
+                trailer: document.trailer,
+                missing_entries: Vec::new(),
+                locations: HashMap::new(),
+            },
         };
 
         let playlist_dir = Path::new(&file_path).parent()
             .ok_or_else(|| PlayerError::PlaylistError("Invalid playlist path".to_string()))?;
 
-        let mut current_track_info: Option<(f64, String)> = None; // (duration, title)
-
-        for (line_num, line) in reader.lines().enumerate() {
-            let line = line.map_err(|e| PlayerError::PlaylistError(format!("Failed to read line {}: {}", line_num + 1, e)))?;
-            let line = line.trim();
-
-            // 跳过空行
-            if line.is_empty() {
-                continue;
-            }
-
-            // 处理M3U指令
-            if line.starts_with('#') {
-                if line.starts_with("#EXTINF:") {
-                    // 解析 #EXTINF: 指令
-                    if let Some(info_part) = line.strip_prefix("#EXTINF:") {
-                        if let Some(comma_pos) = info_part.find(',') {
-                            let duration_str = &info_part[..comma_pos];
-                            let title = &info_part[comma_pos + 1..];
-
-                            if let Ok(duration) = duration_str.parse::<f64>() {
-                                current_track_info = Some((duration, title.to_string()));
-                            }
-                        }
-                    }
-                }
-                // 跳过其他注释行
+        for mut entry in document.entries {
+            // 网络流不支持播放，保存时原样写回
+            if entry.location.contains("://") {
+                playlist.directives.missing_entries.push((playlist.len(), entry));
                 continue;
             }
 
             // 处理文件路径
-            let file_path = normalize_path(line, Some(playlist_dir));
+            let file_path = normalize_path(&entry.location, Some(playlist_dir));
+            playlist.directives.locations.insert(file_path.clone(), entry.location.clone());
 
             // 检查文件是否存在
             if !Path::new(&file_path).exists() {
                 eprintln!("Warning: File not found: {}", file_path);
+                // 保存时写回，避免丢失暂时不可用的条目
+                entry.location = file_path;
+                playlist.directives.missing_entries.push((playlist.len(), entry));
                 continue;
             }
 
             // 添加文件到播放列表
             playlist.add_file(file_path.clone());
 
-            // 如果有EXTINF信息，创建包含元数据的PlaylistExtraInfo
-            let extra_info = if let Some((duration, title)) = current_track_info.take() {
-                PlaylistExtraInfo::new(file_path.clone())
-                    .with_duration(Some(duration))
-                    .with_name(title)
-            } else {
-                PlaylistExtraInfo::new(file_path.clone())
-            };
-
-            // 记录额外信息（不在此处加载音频文件，避免重复加载）
+            // 记录标题、时长等额外信息（不在此处加载音频文件，避免重复加载）
+            let mut extra_info = PlaylistExtraInfo::new(file_path)
+                .with_duration(entry.duration)
+                .with_directives(entry.directives)
+                .with_track_count(entry.track_count);
+            if let Some(title) = entry.title {
+                extra_info = extra_info.with_name(title);
+            }
             playlist.set_extra_info(extra_info);
         }
 
         Ok(playlist)
     }
     
+    /// 将播放列表写回文件
+    /// 
+    /// 按文件扩展名对应的格式写出，曲目的时长和标题来自额外信息，读取时保留的
+    /// 文件头指令、未知指令以及无法播放的条目会原样写回。
+    /// 
+    /// # 返回
+    /// 临时播放列表或只读格式（如CUE）返回错误
+    pub fn save(&self) -> Result<()> {
+        let file_path = self.file_path.as_deref()
+            .ok_or_else(|| PlayerError::PlaylistError("Temporary playlist cannot be saved".to_string()))?;
+        if self.is_smart() {
+            return Err(PlayerError::PlaylistError("Smart playlist cannot be edited".to_string()));
+        }
+        self.export(file_path)
+    }
+    
+    /// 将播放列表写入指定文件，格式由扩展名决定
+    /// 
+    /// # 参数
+    /// * `target_path` - 目标文件路径
+    pub fn export(&self, target_path: &str) -> Result<()> {
+        let format = format_for_path(target_path)
+            .ok_or_else(|| PlayerError::PlaylistError(format!("Unsupported playlist format: {}", target_path)))?;
+        let content = format.write(&self.to_document(target_path))?;
+        fs::write(target_path, content)?;
+        Ok(())
+    }
+    
+    /// 转换为与格式无关的播放列表内容
+    /// 
+    /// # 参数
+    /// * `playlist_path` - 播放列表文件路径，位于其目录下的曲目写为相对路径；
+    ///   写回原来的目录时，从文件读取的曲目保持原来的写法
+    /// 
+    /// # 返回
+    /// 播放列表内容
+    pub fn to_document(&self, playlist_path: &str) -> PlaylistDocument {
+        let playlist_dir = Path::new(playlist_path).parent();
+        let same_dir = self.file_path.as_deref().map(|path| Path::new(path).parent()) == Some(playlist_dir);
+        let location = |file_path: &str| match self.directives.locations.get(file_path).filter(|_| same_dir) {
+            Some(original) => original.clone(),
+            None => relative_to_playlist(file_path, playlist_dir),
+        };
+        let relative = |entry: &PlaylistEntry| PlaylistEntry {
+            location: location(&entry.location),
+            ..entry.clone()
+        };
+        
+        let mut entries = Vec::with_capacity(self.file_paths.len() + self.directives.missing_entries.len());
+        let mut missing_entries = self.directives.missing_entries.iter().peekable();
+        for (index, file_path) in self.file_paths.iter().enumerate() {
+            while let Some((_, entry)) = missing_entries.next_if(|(position, _)| *position <= index) {
+                entries.push(relative(entry));
+            }
+            let mut entry = PlaylistEntry::new(location(file_path));
+            if let Some(extra_info) = self.extra_infos.get(file_path) {
+                entry.title = extra_info.name.clone();
+                entry.duration = extra_info.duration;
+                entry.directives = extra_info.directives.clone();
+            }
+            entries.push(entry);
+        }
+        entries.extend(missing_entries.map(|(_, entry)| relative(entry)));
+        
+        PlaylistDocument {
+            title: self.directives.title.clone(),
+            header: self.directives.header.clone(),
+            entries,
+            trailer: self.directives.trailer.clone(),
+        }
+    }
+    
+    /// 更新文件中记录的标题（M3U的 `#PLAYLIST:`、XSPF的 `<title>`，原来没有时不添加）
+    /// 
+    /// # 参数
+    /// * `title` - 新标题
+    pub fn set_title(&mut self, title: &str) {
+        for directive in self.directives.header.iter_mut() {
+            if directive.starts_with("#PLAYLIST:") {
+                *directive = format!("#PLAYLIST:{}", title);
+            }
+        }
+        if self.directives.title.is_some() {
+            self.directives.title = Some(title.to_string());
+        }
+    }
+    
+    /// 把曲目向前或向后移动一位
+    /// 
+    /// # 参数
+    /// * `index` - 曲目索引
+    /// * `up` - true为向开头移动，false为向末尾移动
+    /// 
+    /// # 返回
+    /// 是否发生了移动
+    pub fn move_file(&mut self, index: usize, up: bool) -> bool {
+        let target = if up { index.checked_sub(1) } else { Some(index + 1) };
+        let Some(target) = target.filter(|&target| index < self.file_paths.len() && target < self.file_paths.len()) else {
+            return false;
+        };
+        self.file_paths.swap(index, target);
+        
+        // 交换当前播放、洗牌队列和播放历史中的索引
+        let swap = |i: &mut usize| {
+            if *i == index {
+                *i = target;
+            } else if *i == target {
+                *i = index;
+            }
+        };
+        self.current_index.iter_mut().for_each(swap);
+        self.shuffle_bag.iter_mut().for_each(swap);
+        self.history.iter_mut().for_each(swap);
+        true
+    }
+    
     /// 移除指定索引的文件
     /// 
     /// # 参数
@@ -595,6 +851,23 @@ impl Playlist {
         if index < self.file_paths.len() {
             self.file_paths.remove(index);
             
+            // 调整找不到文件的条目的写回位置
+            for (position, _) in self.directives.missing_entries.iter_mut() {
+                if *position > index {
+                    *position -= 1;
+                }
+            }
+            
+            // 调整洗牌队列和播放历史中的索引
+            for indices in [&mut self.shuffle_bag, &mut self.history] {
+                indices.retain(|&i| i != index);
+                for i in indices.iter_mut() {
+                    if *i > index {
+                        *i -= 1;
+                    }
+                }
+            }
+            
             // 调整当前播放索引
             if let Some(current) = self.current_index {
                 if current == index {
@@ -632,9 +905,20 @@ impl Playlist {
         self.file_paths.clear();
         self.current_index = None;
         self.extra_infos.clear();
+        self.shuffle_bag.clear();
+        self.history.clear();
     }
 }
 
+/// 位于播放列表目录下的曲目转为相对路径，其他曲目和网络流保持原样
+fn relative_to_playlist(file_path: &str, playlist_dir: Option<&Path>) -> String {
+    playlist_dir
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .and_then(|dir| Path::new(file_path).strip_prefix(dir).ok())
+        .map(|relative| relative.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_path.to_string())
+}
+
 /// 播放列表管理器
 /// 
 /// 管理多个播放列表的缓存，避免重复加载播放列表文件
@@ -647,6 +931,8 @@ pub struct PlaylistManager {
     temporary_playlist: Option<Playlist>,
     /// 全局共享的AudioFile缓存（所有播放列表共享）
     audio_cache: HashMap<String, AudioFile>,
+    /// 定义文件路径 -> 智能播放列表定义
+    smart_playlists: HashMap<String, SmartPlaylist>,
 }
 
 impl PlaylistManager {
@@ -657,6 +943,7 @@ impl PlaylistManager {
             current_playlist_path: None,
             temporary_playlist: None,
             audio_cache: HashMap::new(),
+            smart_playlists: HashMap::new(),
         }
     }
     
@@ -669,8 +956,12 @@ impl PlaylistManager {
     /// 播放列表的可变引用
     pub fn get_or_load_playlist(&mut self, playlist_path: &str) -> Result<&mut Playlist> {
         if !self.playlists.contains_key(playlist_path) {
+            if is_smart_playlist_file(playlist_path) {
+                // 智能播放列表的曲目由 refresh_smart_playlists 生成
+                return Err(PlayerError::PlaylistError("Smart playlist not evaluated".to_string()));
+            }
             // 首次加载播放列表
-            let playlist = if playlist_path.ends_with(".m3u") || playlist_path.ends_with(".m3u8") {
+            let playlist = if is_playlist_file(playlist_path) {
                 Playlist::create_from_playlist_file(playlist_path.to_string())?
             } else {
                 // 单个音频文件创建播放列表
@@ -796,6 +1087,7 @@ impl PlaylistManager {
     /// * `playlist_path` - 播放列表文件路径
     pub fn remove_playlist(&mut self, playlist_path: &str) {
         self.playlists.remove(playlist_path);
+        self.smart_playlists.remove(playlist_path);
         if self.current_playlist_path.as_deref() == Some(playlist_path) {
             self.current_playlist_path = None;
         }
@@ -804,6 +1096,7 @@ impl PlaylistManager {
     /// 清空所有播放列表缓存
     pub fn clear_all(&mut self) {
         self.playlists.clear();
+        self.smart_playlists.clear();
         self.current_playlist_path = None;
     }
     
@@ -851,6 +1144,11 @@ impl PlaylistManager {
         self.audio_cache.get(file_path).and_then(|af| af.info.duration)
     }
 
+    /// 读取全局缓存中已存在的音频文件标签（只读，不触发加载）
+    pub fn get_cached_audio_metadata(&self, file_path: &str) -> Option<&AudioMetadata> {
+        self.audio_cache.get(file_path).map(|af| &af.info.metadata)
+    }
+
     /// 获取或加载全局共享的AudioFile（返回克隆以便安全使用）
     pub fn get_or_load_audio_file(&mut self, file_path: &str) -> Result<AudioFile> {
         if !self.audio_cache.contains_key(file_path) {
@@ -870,7 +1168,9 @@ impl PlaylistManager {
         }
     }
     
-    /// 加载配置目录下的所有播放列表文件
+    /// 加载配置目录下的所有播放列表文件和智能播放列表定义
+    /// 
+    /// 智能播放列表需要调用 `refresh_smart_playlists` 生成曲目后才会出现在缓存中。
     /// 
     /// # 返回
     /// 成功加载的播放列表数量
@@ -878,10 +1178,7 @@ impl PlaylistManager {
         use std::fs;
         
         // 获取配置目录
-        let config_dir = match dirs::config_dir() {
-            Some(dir) => dir.join("summer-player"),
-            None => return 0,
-        };
+        let Some(config_dir) = Self::config_playlist_dir() else { return 0 };
         
         // 如果配置目录不存在，返回0
         if !config_dir.exists() {
@@ -894,17 +1191,21 @@ impl PlaylistManager {
         if let Ok(entries) = fs::read_dir(&config_dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if let Some(extension) = path.extension() {
-                    if extension == "m3u" || extension == "m3u8" {
-                        if let Some(path_str) = path.to_str() {
-                            // 检查是否已经加载过
-                            if !self.playlists.contains_key(path_str) {
-                                // 尝试加载播放列表
-                                if let Ok(playlist) = Playlist::create_from_playlist_file(path_str.to_string()) {
-                                    self.playlists.insert(path_str.to_string(), playlist);
-                                    loaded_count += 1;
-                                }
+                if let Some(path_str) = path.to_str() {
+                    // 检查是否为支持的播放列表格式，且尚未加载过
+                    if is_playlist_file(path_str) && !self.playlists.contains_key(path_str) {
+                        // 尝试加载播放列表
+                        if let Ok(playlist) = Playlist::create_from_playlist_file(path_str.to_string()) {
+                            self.playlists.insert(path_str.to_string(), playlist);
+                            loaded_count += 1;
+                        }
+                    } else if is_smart_playlist_file(path_str) && !self.smart_playlists.contains_key(path_str) {
+                        match SmartPlaylist::load(path_str) {
+                            Ok(smart) => {
+                                self.smart_playlists.insert(path_str.to_string(), smart);
+                                loaded_count += 1;
                             }
+                            Err(e) => eprintln!("加载智能播放列表失败 {}: {}", path_str, e),
                         }
                     }
                 }
@@ -914,6 +1215,130 @@ impl PlaylistManager {
         loaded_count
     }
 
+    /// 存放播放列表文件的配置目录
+    pub fn config_playlist_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("summer-player"))
+    }
+
+    /// 重新加载被外部修改的播放列表文件
+    ///
+    /// 文件已删除时从缓存中移除；内容与缓存相同（例如本程序自己保存后）时不做处理；
+    /// 当前播放列表重新加载后仍指向原来播放的曲目。
+    /// 智能播放列表只更新定义，曲目由之后的 `refresh_smart_playlists` 重新生成。
+    ///
+    /// # 参数
+    /// * `playlist_path` - 播放列表文件路径
+    ///
+    /// # 返回
+    /// 缓存是否发生了变化
+    pub fn reload_playlist(&mut self, playlist_path: &str) -> bool {
+        if is_smart_playlist_file(playlist_path) {
+            return self.reload_smart_playlist(playlist_path);
+        }
+        if !is_playlist_file(playlist_path) {
+            return false;
+        }
+        if !Path::new(playlist_path).exists() {
+            let cached = self.playlists.contains_key(playlist_path);
+            self.remove_playlist(playlist_path);
+            return cached;
+        }
+
+        let mut playlist = match Playlist::create_from_playlist_file(playlist_path.to_string()) {
+            Ok(playlist) => playlist,
+            Err(e) => {
+                // 文件可能正在写入，等待下一次变化
+                eprintln!("重新加载播放列表失败: {}", e);
+                return false;
+            }
+        };
+        if let Some(cached) = self.playlists.get(playlist_path) {
+            // 按写入后的文本比较，写入器补充的内容（例如 `#EXTM3U`）不算变化
+            let (cached_document, document) = (cached.to_document(playlist_path), playlist.to_document(playlist_path));
+            let unchanged = match format_for_path(playlist_path).map(|format| (format.write(&cached_document), format.write(&document))) {
+                Some((Ok(cached_content), Ok(content))) => cached_content == content,
+                _ => cached_document == document,
+            };
+            if unchanged {
+                return false;
+            }
+            if self.current_playlist_path.as_deref() == Some(playlist_path) {
+                let current = cached.current_file_path()
+                    .and_then(|file_path| playlist.file_paths().iter().position(|path| path == file_path));
+                if let Some(index) = current {
+                    playlist.set_current_index(index);
+                }
+            }
+        }
+        self.playlists.insert(playlist_path.to_string(), playlist);
+        true
+    }
+
+    /// 重新加载智能播放列表定义，返回定义是否发生了变化
+    fn reload_smart_playlist(&mut self, playlist_path: &str) -> bool {
+        if !Path::new(playlist_path).exists() {
+            let cached = self.smart_playlists.contains_key(playlist_path);
+            self.remove_playlist(playlist_path);
+            return cached;
+        }
+        match SmartPlaylist::load(playlist_path) {
+            Ok(smart) => {
+                if self.smart_playlists.get(playlist_path) == Some(&smart) {
+                    return false;
+                }
+                self.smart_playlists.insert(playlist_path.to_string(), smart);
+                true
+            }
+            Err(e) => {
+                // 规则写错时保留原来的定义
+                eprintln!("重新加载智能播放列表失败: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 按音乐库和播放统计重新生成所有智能播放列表的曲目
+    ///
+    /// 曲目和名称都没有变化的播放列表保持不变；当前播放列表重新生成后仍指向原来播放的曲目。
+    ///
+    /// # 参数
+    /// * `library` - 音乐库
+    /// * `stats` - 查询曲目的播放统计
+    /// * `include_current` - 是否也重新生成当前播放列表，只有播放统计变化时应保持正在播放的列表不变
+    ///
+    /// # 返回
+    /// 当前播放列表的曲目是否发生了变化
+    pub fn refresh_smart_playlists(&mut self, library: &Library, stats: &dyn Fn(&str) -> TrackStats, include_current: bool) -> bool {
+        let now = unix_timestamp();
+        let mut current_changed = false;
+        for (path, smart) in &self.smart_playlists {
+            let is_current = self.current_playlist_path.as_deref() == Some(path.as_str());
+            if is_current && !include_current && self.playlists.contains_key(path) {
+                continue;
+            }
+            let mut playlist = Playlist::create_from_library_tracks(&smart.evaluate(library, stats, now));
+            playlist.file_path = Some(path.clone());
+            playlist.name = Some(smart.display_name(path));
+            let cached = self.playlists.get(path);
+            if cached.is_some_and(|cached| cached.file_paths == playlist.file_paths && cached.name == playlist.name) {
+                continue;
+            }
+
+            if is_current {
+                let current = cached.and_then(|cached| cached.current_file_path())
+                    .and_then(|file_path| playlist.file_paths.iter().position(|path| path == file_path));
+                if let Some(index) = current {
+                    playlist.set_current_index(index);
+                }
+                current_changed = true;
+            }
+            self.playlists.insert(path.clone(), playlist);
+        }
+        // 定义已删除的智能播放列表
+        self.playlists.retain(|path, _| !is_smart_playlist_file(path) || self.smart_playlists.contains_key(path));
+        current_changed
+    }
+
     /// 预加载当前播放列表中的音频到全局缓存
     pub fn preload_current_playlist_audio_to_cache(&mut self) {
         if let Some(playlist) = self.current_playlist_ref() {
@@ -975,6 +1400,8 @@ impl PlaylistManager {
     }
 
     /// 通过重命名文件来重命名播放列表，并更新缓存键
+    /// 
+    /// 播放列表通过写入器保存到新文件后再删除旧文件，`#PLAYLIST:` 标题随之更新。
     pub fn rename_playlist(&mut self, old_path: &str, new_name: &str) -> Result<String> {
         let old = PathBuf::from(old_path);
         if !old.exists() {
@@ -983,19 +1410,34 @@ impl PlaylistManager {
         let parent = old.parent().ok_or_else(|| PlayerError::PlaylistError("Invalid playlist path".to_string()))?;
         let mut base = new_name.trim();
         if base.is_empty() { base = "New Playlist"; }
-        let mut candidate = parent.join(format!("{}.m3u", base));
+        // 保持原来的文件格式
+        let extension = old.extension().and_then(|e| e.to_str()).unwrap_or("m3u");
+        let mut candidate = parent.join(format!("{}.{}", base, extension));
         let mut counter = 1usize;
         while candidate.exists() {
-            candidate = parent.join(format!("{} ({}).m3u", base, counter));
+            candidate = parent.join(format!("{} ({}).{}", base, counter, extension));
             counter += 1;
         }
-        fs::rename(&old, &candidate).map_err(|e| PlayerError::IoError(e))?;
         let new_path = candidate.to_string_lossy().to_string();
-        let _ = self.playlists.remove(old_path);
-        // 重新加载，确保名称等信息更新
-        if let Ok(new_pl) = Playlist::create_from_playlist_file(new_path.clone()) {
-            self.playlists.insert(new_path.clone(), new_pl);
+
+        if self.get_or_load_playlist(old_path)?.is_read_only() {
+            return Err(PlayerError::PlaylistError("Read-only playlist cannot be renamed".to_string()));
+        }
+        let Some(mut playlist) = self.playlists.remove(old_path) else {
+            return Err(PlayerError::PlaylistError("Playlist not loaded".to_string()));
+        };
+        playlist.file_path = Some(new_path.clone());
+        playlist.name = Some(extract_filename(&new_path));
+        playlist.set_title(base);
+        if let Err(e) = playlist.save() {
+            // 保存失败时恢复原来的缓存
+            playlist.file_path = Some(old_path.to_string());
+            playlist.name = Some(extract_filename(old_path));
+            self.playlists.insert(old_path.to_string(), playlist);
+            return Err(e);
         }
+        fs::remove_file(&old)?;
+        self.playlists.insert(new_path.clone(), playlist);
         if self.current_playlist_path.as_deref() == Some(old_path) {
             self.current_playlist_path = Some(new_path.clone());
         }
@@ -1012,31 +1454,28 @@ impl PlaylistManager {
         Ok(())
     }
 
-    /// 追加文件到指定的m3u播放列表文件，并更新缓存
+    /// 把播放列表导出为指定文件，格式由目标文件的扩展名决定
+    pub fn export_playlist(&mut self, playlist_path: &str, target_path: &str) -> Result<()> {
+        self.get_or_load_playlist(playlist_path)?.export(target_path)
+    }
+
+    /// 追加文件到指定的m3u播放列表，并写回播放列表文件
     pub fn append_files_to_playlist(&mut self, playlist_path: &str, files: &[String]) -> Result<()> {
         if files.is_empty() { return Ok(()); }
-        // 只支持持久播放列表
-        let mut file = std::fs::OpenOptions::new()
-            .append(true)
-            .create(false)
-            .open(playlist_path)
-            .map_err(|e| PlayerError::IoError(e))?;
-        use std::io::Write;
-        for f in files {
-            // 写入相对路径或绝对路径，保持简单使用绝对路径
-            writeln!(file, "{}", f).map_err(|e| PlayerError::IoError(e))?;
+        // 只支持持久播放列表，临时播放列表保存时返回错误
+        let playlist = self.get_or_load_playlist(playlist_path)?;
+        if playlist.is_read_only() {
+            return Err(PlayerError::PlaylistError("Read-only playlist cannot be edited".to_string()));
         }
-        // 刷新缓存中的该播放列表
-        if let Ok(pl) = Playlist::create_from_playlist_file(playlist_path.to_string()) {
-            self.playlists.insert(playlist_path.to_string(), pl);
-        }
-        Ok(())
+        playlist.add_files(files.to_vec());
+        playlist.save()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::TempDir;
 
     #[test]
     fn test_playlist_creation() {
@@ -1090,7 +1529,285 @@ mod tests {
         assert_eq!(paths[1], "song2.flac");
         assert_eq!(paths[2], "song3.wav");
     }
+
+    #[test]
+    fn test_peek_next_index_does_not_advance() {
+        let mut playlist = Playlist::create_from_audio_files(vec![
+            "song1.mp3".to_string(),
+            "song2.mp3".to_string(),
+        ]);
+        
+        assert_eq!(playlist.peek_next_index_with_mode(&PlayMode::ListLoop), Some(1));
+        assert_eq!(playlist.peek_next_index_with_mode(&PlayMode::SingleLoop), Some(0));
+        assert_eq!(playlist.peek_next_index_with_mode(&PlayMode::Random), Some(1));
+        assert_eq!(playlist.current_index(), Some(0));
+        
+        // 列表循环到末尾后回到开头
+        playlist.set_current_index(1);
+        assert_eq!(playlist.peek_next_index_with_mode(&PlayMode::ListLoop), Some(0));
+        let (next, should_restart) = playlist.next_file_with_mode(&PlayMode::ListLoop);
+        assert_eq!(next.map(String::as_str), Some("song1.mp3"));
+        assert!(!should_restart);
+    }
     
+    fn shuffle_playlist(count: usize) -> Playlist {
+        Playlist::create_from_audio_files((0..count).map(|i| format!("song{}.mp3", i)).collect())
+    }
+
+    #[test]
+    fn test_shuffle_plays_every_track_before_repeating() {
+        let mut playlist = shuffle_playlist(5);
+        
+        for _ in 0..3 {
+            // 每一轮从当前歌曲开始，其余曲目各播放一次
+            let mut round = vec![playlist.current_index().unwrap()];
+            for _ in 1..5 {
+                let peeked = playlist.peek_next_index_with_mode(&PlayMode::Random);
+                assert_eq!(playlist.peek_next_index_with_mode(&PlayMode::Random), peeked);
+                let (next, should_restart) = playlist.next_file_with_mode(&PlayMode::Random);
+                assert!(next.is_some());
+                assert!(!should_restart);
+                assert_eq!(playlist.current_index(), peeked);
+                round.push(playlist.current_index().unwrap());
+            }
+            round.sort();
+            assert_eq!(round, vec![0, 1, 2, 3, 4]);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_previous_follows_history() {
+        let mut playlist = shuffle_playlist(6);
+        let mut played = vec![playlist.current_index().unwrap()];
+        for _ in 0..3 {
+            playlist.next_file_with_mode(&PlayMode::Random);
+            played.push(playlist.current_index().unwrap());
+        }
+        
+        // 上一首按实际播放顺序回退
+        playlist.previous_file_with_mode(&PlayMode::Random);
+        assert_eq!(playlist.current_index(), Some(played[2]));
+        playlist.previous_file_with_mode(&PlayMode::Random);
+        assert_eq!(playlist.current_index(), Some(played[1]));
+        
+        // 回退后下一首回到刚才播放的曲目
+        playlist.next_file_with_mode(&PlayMode::Random);
+        assert_eq!(playlist.current_index(), Some(played[2]));
+        
+        // 手动选择的曲目也记入历史
+        let jumped = (0..6).find(|index| !played.contains(index)).unwrap();
+        playlist.set_current_index(jumped);
+        playlist.previous_file_with_mode(&PlayMode::Random);
+        assert_eq!(playlist.current_index(), Some(played[2]));
+        
+        for expected in [played[1], played[0]] {
+            playlist.previous_file_with_mode(&PlayMode::Random);
+            assert_eq!(playlist.current_index(), Some(expected));
+        }
+        // 没有更早的历史时重新播放当前歌曲
+        let (prev, should_restart) = playlist.previous_file_with_mode(&PlayMode::Random);
+        assert_eq!(prev.map(String::as_str), Some(format!("song{}.mp3", played[0]).as_str()));
+        assert!(should_restart);
+    }
+
+    #[test]
+    fn test_shuffle_state_follows_removed_tracks() {
+        let mut playlist = shuffle_playlist(4);
+        let mut played = vec![playlist.current_file_path().cloned().unwrap()];
+        played.push(playlist.next_file_with_mode(&PlayMode::Random).0.cloned().unwrap());
+        let removed = playlist.peek_next_index_with_mode(&PlayMode::Random).unwrap();
+        let removed_path = playlist.file_paths()[removed].clone();
+        playlist.remove_file(removed);
+        
+        // 本轮只剩下没有播放过、也没有被移除的那一首
+        let next = playlist.next_file_with_mode(&PlayMode::Random).0.cloned().unwrap();
+        assert!(!played.contains(&next));
+        assert_ne!(next, removed_path);
+        
+        playlist.previous_file_with_mode(&PlayMode::Random);
+        assert_eq!(playlist.current_file_path(), played.last());
+    }
+
+    /// 在临时目录中创建播放列表文件和其中引用的音频文件
+    ///
+    /// # 返回
+    /// 临时目录（离开作用域时删除）和播放列表文件路径
+    fn write_playlist_fixture(name: &str, content: &str, audio_files: &[&str]) -> (TempDir, PathBuf) {
+        let dir = TempDir::new(name);
+        fs::create_dir_all(dir.join("music")).unwrap();
+        for file in audio_files {
+            fs::write(dir.join(file), b"").unwrap();
+        }
+        let playlist_path = dir.join("list.m3u8");
+        fs::write(&playlist_path, content).unwrap();
+        (dir, playlist_path)
+    }
+
+    #[test]
+    fn test_m3u_round_trip_preserves_directives() {
+        let content = "#EXTM3U\n\
+            #PLAYLIST:Summer\n\
+            #EXTGRP:Favourites\n\
+            #EXTINF:215,Artist - Title\n\
+            music/a.mp3\n\
+            #EXTINF:-1 tvg-id=\"x\",Odd\n\
+            music/missing.mp3\n\
+            # plain comment\n\
+            #EXTINF:12.5,\n\
+            music/b.flac\n\
+            #EXT-X-ENDLIST\n";
+        let (_dir, playlist_path) = write_playlist_fixture("round-trip", content, &["music/a.mp3", "music/b.flac"]);
+        let path_str = playlist_path.to_string_lossy().to_string();
+
+        let playlist = Playlist::create_from_playlist_file(path_str.clone()).unwrap();
+        assert_eq!(playlist.len(), 2);
+        let first = playlist.extra_info_for(&playlist.file_paths()[0]).unwrap();
+        assert_eq!(first.name.as_deref(), Some("Artist - Title"));
+        assert_eq!(first.duration, Some(215.0));
+        assert_eq!(first.directives, ["#EXTGRP:Favourites"]);
+        let second = playlist.extra_info_for(&playlist.file_paths()[1]).unwrap();
+        assert_eq!(second.name, None);
+        assert_eq!(second.duration, Some(12.5));
+
+        playlist.save().unwrap();
+        assert_eq!(fs::read_to_string(&playlist_path).unwrap(), content);
+    }
+
+    #[test]
+    fn test_m3u_save_after_edit() {
+        let (dir, playlist_path) = write_playlist_fixture("edit", "music/a.mp3\nmusic/b.mp3\n", &["music/a.mp3", "music/b.mp3", "c.mp3"]);
+        let mut playlist = Playlist::create_from_playlist_file(playlist_path.to_string_lossy().to_string()).unwrap();
+        let outside = std::env::temp_dir().join("summer-player-elsewhere.mp3").to_string_lossy().to_string();
+
+        assert!(playlist.move_file(1, true));
+        assert!(!playlist.move_file(0, true));
+        playlist.add_files(vec![dir.join("c.mp3").to_string_lossy().to_string(), outside.clone()]);
+        playlist.remove_file(1);
+        playlist.save().unwrap();
+
+        // 播放列表目录下的曲目写为相对路径，其他位置保持绝对路径
+        let expected = format!("#EXTM3U\n{}\nc.mp3\n{}\n", Path::new("music").join("b.mp3").display(), outside);
+        assert_eq!(fs::read_to_string(&playlist_path).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_save_preserves_parent_relative_entries() {
+        let (dir, playlist_path) = write_playlist_fixture("parent", "", &["music/a.mp3", "music/c.mp3", "b.mp3"]);
+        // 播放列表位于子目录中，用 `../` 引用上级目录的文件；绝对路径和 `./` 也保持原样
+        let nested_path = dir.join("music").join("nested.m3u8");
+        let absolute = dir.join("music").join("c.mp3").to_string_lossy().to_string();
+        let content = format!("#EXTM3U\n../b.mp3\n../missing.mp3\n./a.mp3\n{}\n", absolute);
+        fs::write(&nested_path, &content).unwrap();
+
+        let mut playlist = Playlist::create_from_playlist_file(nested_path.to_string_lossy().to_string()).unwrap();
+        assert_eq!(playlist.len(), 3);
+        playlist.save().unwrap();
+        assert_eq!(fs::read_to_string(&nested_path).unwrap(), content);
+
+        assert!(playlist.move_file(1, true));
+        playlist.save().unwrap();
+        let expected = format!("#EXTM3U\n./a.mp3\n../missing.mp3\n../b.mp3\n{}\n", absolute);
+        assert_eq!(fs::read_to_string(&nested_path).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_reload_externally_edited_playlist() {
+        let (_dir, playlist_path) = write_playlist_fixture("reload", "music/a.mp3\nmusic/b.mp3\n", &["music/a.mp3", "music/b.mp3"]);
+        let path = playlist_path.to_string_lossy().to_string();
+        let mut manager = PlaylistManager::new();
+        manager.get_or_load_playlist(&path).unwrap();
+        manager.set_current_playlist(&path).unwrap();
+        manager.current_playlist().unwrap().set_current_index(1);
+
+        // 内容未变化（包括本程序自己保存）时不重新加载
+        assert!(!manager.reload_playlist(&path));
+        manager.current_playlist_ref().unwrap().save().unwrap();
+        assert!(!manager.reload_playlist(&path));
+
+        // 外部调整顺序后，当前曲目保持不变
+        fs::write(&playlist_path, "music/b.mp3\nmusic/a.mp3\n").unwrap();
+        assert!(manager.reload_playlist(&path));
+        assert_eq!(manager.current_playlist_ref().unwrap().current_index(), Some(0));
+
+        fs::remove_file(&playlist_path).unwrap();
+        assert!(manager.reload_playlist(&path));
+        assert!(!manager.contains_playlist(&path));
+        assert!(manager.current_playlist_path().is_none());
+    }
+
+    #[test]
+    fn test_refresh_smart_playlists() {
+        let dir = TempDir::new("smart");
+        let smart_path = dir.join("Jazz.smart.toml");
+        fs::write(&smart_path, "rule = \"genre = Jazz\"\nsort = \"title\"\n").unwrap();
+        let path = smart_path.to_string_lossy().to_string();
+        let track = |path: &str, title: &str, genre: &str| LibraryTrack {
+            path: path.to_string(),
+            title: Some(title.to_string()),
+            genre: Some(genre.to_string()),
+            ..LibraryTrack::default()
+        };
+        let mut library: Library = [track("/m/a.mp3", "A", "Jazz"), track("/m/b.mp3", "B", "Rock"), track("/m/c.mp3", "C", "Jazz")]
+            .into_iter()
+            .collect();
+        let no_stats = |_: &str| TrackStats::default();
+
+        let mut manager = PlaylistManager::new();
+        assert!(manager.reload_playlist(&path));
+        assert!(manager.get_or_load_playlist(&path).is_err());
+        assert!(!manager.refresh_smart_playlists(&library, &no_stats, true));
+        let playlist = manager.get_or_load_playlist(&path).unwrap();
+        assert!(playlist.is_smart());
+        assert_eq!(playlist.name(), Some("Jazz"));
+        assert_eq!(playlist.file_paths(), ["/m/a.mp3", "/m/c.mp3"]);
+        assert!(playlist.save().is_err());
+
+        // 曲目变化后重新生成，当前曲目保持不变
+        manager.set_current_playlist(&path).unwrap();
+        manager.current_playlist().unwrap().set_current_index(1);
+        assert!(!manager.refresh_smart_playlists(&library, &no_stats, true));
+        library = [track("/m/a.mp3", "A", "Rock"), track("/m/b.mp3", "B", "Jazz"), track("/m/c.mp3", "C", "Jazz")]
+            .into_iter()
+            .collect();
+        // 只有播放统计变化时，正在播放的列表保持不变
+        assert!(!manager.refresh_smart_playlists(&library, &no_stats, false));
+        assert_eq!(manager.current_playlist_ref().unwrap().file_paths(), ["/m/a.mp3", "/m/c.mp3"]);
+        assert!(manager.refresh_smart_playlists(&library, &no_stats, true));
+        let playlist = manager.current_playlist_ref().unwrap();
+        assert_eq!(playlist.file_paths(), ["/m/b.mp3", "/m/c.mp3"]);
+        assert_eq!(playlist.current_file_path().map(String::as_str), Some("/m/c.mp3"));
+
+        // 规则无效时保留原来的定义，文件删除后移除
+        fs::write(&smart_path, "rule = \"genre ? Jazz\"\n").unwrap();
+        assert!(!manager.reload_playlist(&path));
+        fs::remove_file(&smart_path).unwrap();
+        assert!(manager.reload_playlist(&path));
+        assert!(!manager.contains_playlist(&path));
+        assert!(manager.current_playlist_path().is_none());
+    }
+
+    #[test]
+    fn test_read_only_playlists() {
+        let dir = TempDir::new("read-only");
+        fs::write(dir.join("a.mp3"), b"").unwrap();
+        fs::write(dir.join("b.mp3"), b"").unwrap();
+        let cue_path = dir.join("album.cue");
+        fs::write(&cue_path, "FILE \"a.mp3\" MP3\n  TRACK 01 AUDIO\n").unwrap();
+        let path = cue_path.to_string_lossy().to_string();
+
+        // 只读的播放列表不能添加曲目和重命名，缓存中的内容保持与文件一致
+        let mut manager = PlaylistManager::new();
+        assert!(manager.get_or_load_playlist(&path).unwrap().is_read_only());
+        assert!(manager.append_files_to_playlist(&path, &[dir.join("b.mp3").to_string_lossy().to_string()]).is_err());
+        assert_eq!(manager.get_or_load_playlist(&path).unwrap().len(), 1);
+        assert!(manager.rename_playlist(&path, "Renamed").is_err());
+        assert!(cue_path.exists());
+        assert!(manager.contains_playlist(&path));
+
+        assert!(!Playlist::new().is_read_only());
+        assert!(!Playlist::create_from_audio_files(vec!["a.mp3".to_string()]).is_read_only());
+    }
+
     #[test]
     fn test_create_from_single_audio_file_convenience() {
         let playlist = Playlist::create_from_audio_files(vec!["single_song.mp3".to_string()]);
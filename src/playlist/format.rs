@@ -0,0 +1,129 @@
+//! 播放列表文件格式
+//!
+//! 每种格式实现 [`PlaylistFormat`]，在文本和与格式无关的 [`PlaylistDocument`] 之间转换，
+//! 路径解析、文件存在性检查等由 [`Playlist`](super::Playlist) 统一处理。
+//! 新增格式只需实现该trait并加入 [`FORMATS`]。
+
+use std::path::Path;
+
+use crate::error::{PlayerError, Result};
+
+mod cue;
+mod m3u;
+mod pls;
+mod xspf;
+
+pub use cue::Cue;
+pub use m3u::M3u;
+pub use pls::Pls;
+pub use xspf::Xspf;
+
+/// 播放列表中的一个条目
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaylistEntry {
+    /// 文件中记录的位置（绝对路径或相对于播放列表文件的路径）
+    pub location: String,
+    /// 标题
+    pub title: Option<String>,
+    /// 时长（秒）
+    pub duration: Option<f64>,
+    /// 该条目前面的其他指令（仅M3U使用，原样写回）
+    pub directives: Vec<String>,
+    /// 文件中包含的曲目数（仅CUE整轨镜像使用，一个文件包含多首曲目时）
+    pub track_count: Option<usize>,
+}
+
+impl PlaylistEntry {
+    /// 创建只有位置的条目
+    pub fn new(location: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// 与格式无关的播放列表内容
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaylistDocument {
+    /// 播放列表标题（XSPF的 `<title>`）
+    pub title: Option<String>,
+    /// 文件头指令（M3U的 `#EXTM3U`、`#PLAYLIST:` 等）
+    pub header: Vec<String>,
+    /// 按顺序排列的条目
+    pub entries: Vec<PlaylistEntry>,
+    /// 最后一个条目之后的指令（仅M3U使用）
+    pub trailer: Vec<String>,
+}
+
+/// 播放列表文件格式
+pub trait PlaylistFormat: Sync {
+    /// 格式名称，用于文件对话框的过滤器
+    fn name(&self) -> &'static str;
+
+    /// 文件扩展名（小写，不含点）
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// 解析播放列表文本
+    fn read(&self, content: &str) -> Result<PlaylistDocument>;
+
+    /// 是否支持写入
+    fn is_writable(&self) -> bool {
+        true
+    }
+
+    /// 生成播放列表文本
+    fn write(&self, document: &PlaylistDocument) -> Result<String> {
+        let _ = document;
+        Err(PlayerError::PlaylistError(format!("{} playlists are read-only", self.name())))
+    }
+}
+
+/// 所有支持的播放列表格式
+pub static FORMATS: &[&dyn PlaylistFormat] = &[&M3u, &Pls, &Xspf, &Cue];
+
+/// 根据扩展名查找播放列表格式
+///
+/// # 参数
+/// * `file_path` - 播放列表文件路径
+///
+/// # 返回
+/// 对应的格式，不是播放列表文件时返回None
+pub fn format_for_path(file_path: &str) -> Option<&'static dyn PlaylistFormat> {
+    let extension = Path::new(file_path).extension()?.to_str()?.to_lowercase();
+    FORMATS.iter().copied().find(|format| format.extensions().contains(&extension.as_str()))
+}
+
+/// 所有支持的播放列表扩展名
+pub fn supported_extensions() -> Vec<&'static str> {
+    FORMATS.iter().flat_map(|format| format.extensions().iter().copied()).collect()
+}
+
+/// 支持写入的播放列表扩展名
+pub fn writable_extensions() -> Vec<&'static str> {
+    FORMATS.iter()
+        .filter(|format| format.is_writable())
+        .flat_map(|format| format.extensions().iter().copied())
+        .collect()
+}
+
+/// 解析时长文本，负数表示未知
+fn parse_duration(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|duration| *duration >= 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_for_path() {
+        assert_eq!(format_for_path("/music/list.M3U8").map(|f| f.name()), Some("M3U"));
+        assert_eq!(format_for_path("list.pls").map(|f| f.name()), Some("PLS"));
+        assert_eq!(format_for_path("list.xspf").map(|f| f.name()), Some("XSPF"));
+        assert_eq!(format_for_path("album.cue").map(|f| f.name()), Some("CUE"));
+        assert!(format_for_path("song.mp3").is_none());
+        assert!(format_for_path("playlist").is_none());
+        assert!(!writable_extensions().contains(&"cue"));
+    }
+}
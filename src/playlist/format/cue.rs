@@ -0,0 +1,146 @@
+//! CUE 格式（只读）
+//!
+//! 每个 `FILE` 成为一个条目。播放引擎不支持只播放文件中的一段，
+//! 因此整轨镜像（一个文件包含多个 `TRACK`）仍作为一个条目，
+//! 标题使用专辑标题并记录文件中包含的曲目数，由界面注明；每个文件只有一首曲目时使用该曲目的标题和演唱者。
+
+use super::{PlaylistDocument, PlaylistEntry, PlaylistFormat};
+use crate::error::Result;
+
+/// CUE 表单
+pub struct Cue;
+
+/// CUE 中的一个音频文件及其曲目
+#[derive(Default)]
+struct CueFile {
+    path: String,
+    /// (标题，演唱者)
+    tracks: Vec<(Option<String>, Option<String>)>,
+}
+
+impl PlaylistFormat for Cue {
+    fn name(&self) -> &'static str {
+        "CUE"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["cue"]
+    }
+
+    fn is_writable(&self) -> bool {
+        false
+    }
+
+    fn read(&self, content: &str) -> Result<PlaylistDocument> {
+        let mut album_title: Option<String> = None;
+        let mut album_performer: Option<String> = None;
+        let mut files: Vec<CueFile> = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim().trim_start_matches('\u{feff}');
+            let (command, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let arguments = arguments.trim();
+
+            match command.to_ascii_uppercase().as_str() {
+                "FILE" => files.push(CueFile {
+                    path: unquote_file_name(arguments),
+                    ..CueFile::default()
+                }),
+                "TRACK" => {
+                    if let Some(file) = files.last_mut() {
+                        file.tracks.push((None, None));
+                    }
+                }
+                "TITLE" | "PERFORMER" => {
+                    let value = Some(unquote(arguments)).filter(|value| !value.is_empty());
+                    let is_title = command.eq_ignore_ascii_case("TITLE");
+                    match files.last_mut().and_then(|file| file.tracks.last_mut()) {
+                        Some(track) => {
+                            if is_title { track.0 = value } else { track.1 = value }
+                        }
+                        _ if is_title => album_title = value,
+                        _ => album_performer = value,
+                    }
+                }
+                // REM 注释及其他命令
+                _ => {}
+            }
+        }
+
+        let entries = files.into_iter()
+            .filter(|file| !file.path.is_empty())
+            .map(|file| {
+                let title = match file.tracks.as_slice() {
+                    [(title, performer)] => {
+                        match (performer.as_ref().or(album_performer.as_ref()), title) {
+                            (Some(performer), Some(title)) => Some(format!("{} - {}", performer, title)),
+                            (_, title) => title.clone(),
+                        }
+                    }
+                    _ => album_title.clone(),
+                };
+                // 整轨镜像只能整体播放，记录曲目数
+                let track_count = Some(file.tracks.len()).filter(|count| *count > 1);
+                PlaylistEntry {
+                    location: file.path,
+                    title,
+                    track_count,
+                    ..PlaylistEntry::default()
+                }
+            })
+            .collect();
+
+        Ok(PlaylistDocument {
+            title: album_title,
+            entries,
+            ..PlaylistDocument::default()
+        })
+    }
+}
+
+/// 去掉参数两端的引号
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value.strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// 解析 `FILE "name.flac" WAVE` 中的文件名（去掉末尾的文件类型）
+fn unquote_file_name(arguments: &str) -> String {
+    if let Some(quoted) = arguments.strip_prefix('"') {
+        return quoted.split('"').next().unwrap_or_default().to_string();
+    }
+    match arguments.rsplit_once(char::is_whitespace) {
+        Some((name, _file_type)) => name.trim().to_string(),
+        None => arguments.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cue_fixture() {
+        let content = include_str!("../../../tests/fixtures/playlists/album.cue");
+        let document = Cue.read(content).unwrap();
+
+        assert_eq!(document.title.as_deref(), Some("Summer Album"));
+        assert_eq!(document.entries, [
+            PlaylistEntry {
+                location: "01 Opening.flac".to_string(),
+                title: Some("The Band - Opening".to_string()),
+                ..PlaylistEntry::default()
+            },
+            PlaylistEntry {
+                location: "image.wav".to_string(),
+                title: Some("Summer Album".to_string()),
+                track_count: Some(2),
+                ..PlaylistEntry::default()
+            },
+        ]);
+        assert!(Cue.write(&document).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! M3U/M3U8 格式
+//!
+//! 时长和标题来自 `#EXTINF`，其他指令原样保留并写回。
+
+use super::{parse_duration, PlaylistDocument, PlaylistEntry, PlaylistFormat};
+use crate::error::Result;
+
+/// 作用于整个播放列表、保存时写在文件头部的指令
+const HEADER_DIRECTIVES: &[&str] = &["#EXTM3U", "#PLAYLIST:", "#EXTENC:"];
+
+/// M3U/M3U8 播放列表
+pub struct M3u;
+
+impl PlaylistFormat for M3u {
+    fn name(&self) -> &'static str {
+        "M3U"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["m3u", "m3u8"]
+    }
+
+    fn read(&self, content: &str) -> Result<PlaylistDocument> {
+        let mut document = PlaylistDocument::default();
+        let mut current_track_info: Option<(Option<f64>, Option<String>)> = None; // (duration, title)
+        let mut pending_directives: Vec<String> = Vec::new();
+
+        for line in content.lines() {
+            // 去掉UTF-8 BOM
+            let line = line.trim().trim_start_matches('\u{feff}');
+
+            // 跳过空行
+            if line.is_empty() {
+                continue;
+            }
+
+            // 处理M3U指令
+            if line.starts_with('#') {
+                if HEADER_DIRECTIVES.iter().any(|prefix| line.starts_with(prefix)) {
+                    document.header.push(line.to_string());
+                    continue;
+                }
+                // 解析 #EXTINF: 指令，无法解析的按未知指令原样保留
+                match line.strip_prefix("#EXTINF:").and_then(parse_extinf) {
+                    Some(info) => current_track_info = Some(info),
+                    None => pending_directives.push(line.to_string()),
+                }
+                continue;
+            }
+
+            let (duration, title) = current_track_info.take().unwrap_or_default();
+            document.entries.push(PlaylistEntry {
+                location: line.to_string(),
+                title,
+                duration,
+                directives: std::mem::take(&mut pending_directives),
+                ..PlaylistEntry::default()
+            });
+        }
+        // 最后一首之后的指令
+        document.trailer = pending_directives;
+
+        Ok(document)
+    }
+
+    fn write(&self, document: &PlaylistDocument) -> Result<String> {
+        let mut lines: Vec<String> = Vec::new();
+        if !document.header.first().is_some_and(|directive| directive.starts_with("#EXTM3U")) {
+            lines.push("#EXTM3U".to_string());
+        }
+        lines.extend(document.header.iter().cloned());
+
+        for entry in &document.entries {
+            lines.extend(entry.directives.iter().cloned());
+            if entry.title.is_some() || entry.duration.is_some() {
+                // 未知时长按惯例写为-1
+                let duration = entry.duration.map_or("-1".to_string(), |d| d.to_string());
+                lines.push(format!("#EXTINF:{},{}", duration, entry.title.as_deref().unwrap_or("")));
+            }
+            lines.push(entry.location.clone());
+        }
+        lines.extend(document.trailer.iter().cloned());
+
+        let mut content = lines.join("\n");
+        content.push('\n');
+        Ok(content)
+    }
+}
+
+/// 解析 `#EXTINF:` 之后的内容
+///
+/// # 返回
+/// (时长，标题)；格式不正确（例如带有属性）时返回None
+fn parse_extinf(info: &str) -> Option<(Option<f64>, Option<String>)> {
+    let (duration, title) = info.split_once(',')?;
+    // 先确认是数字，-1 等负数表示未知时长
+    duration.trim().parse::<f64>().ok()?;
+    Some((parse_duration(duration), Some(title.to_string()).filter(|title| !title.is_empty())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_m3u_fixture_round_trip() {
+        let content = include_str!("../../../tests/fixtures/playlists/extended.m3u");
+        let document = M3u.read(content).unwrap();
+
+        assert_eq!(document.header, ["#EXTM3U", "#PLAYLIST:Summer"]);
+        assert_eq!(document.entries.len(), 3);
+        assert_eq!(document.entries[0].title.as_deref(), Some("Artist - Title"));
+        assert_eq!(document.entries[0].duration, Some(215.0));
+        assert_eq!(document.entries[0].directives, ["#EXTGRP:Favourites"]);
+        // 带属性的EXTINF按未知指令保留
+        assert_eq!(document.entries[1].title, None);
+        assert_eq!(document.entries[1].directives.len(), 1);
+        assert_eq!(document.entries[2].duration, Some(12.5));
+        assert_eq!(document.trailer, ["#EXT-X-ENDLIST"]);
+
+        assert_eq!(M3u.write(&document).unwrap(), content);
+    }
+
+    #[test]
+    fn test_m3u_plain_list_gets_header() {
+        let document = M3u.read("\u{feff}a.mp3\r\nb.mp3\r\n").unwrap();
+        assert_eq!(document.entries, [PlaylistEntry::new("a.mp3"), PlaylistEntry::new("b.mp3")]);
+        assert_eq!(M3u.write(&document).unwrap(), "#EXTM3U\na.mp3\nb.mp3\n");
+    }
+}
@@ -0,0 +1,115 @@
+//! PLS 格式
+//!
+//! INI风格的 `[playlist]` 段，`FileN`/`TitleN`/`LengthN` 按编号描述第N个条目。
+
+use std::collections::BTreeMap;
+
+use super::{parse_duration, PlaylistDocument, PlaylistEntry, PlaylistFormat};
+use crate::error::{PlayerError, Result};
+
+/// PLS 播放列表
+pub struct Pls;
+
+impl PlaylistFormat for Pls {
+    fn name(&self) -> &'static str {
+        "PLS"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pls"]
+    }
+
+    fn read(&self, content: &str) -> Result<PlaylistDocument> {
+        // 编号 -> 条目，按编号排序
+        let mut entries: BTreeMap<u32, PlaylistEntry> = BTreeMap::new();
+        let mut in_playlist_section = false;
+        let mut has_section = false;
+
+        for line in content.lines() {
+            let line = line.trim().trim_start_matches('\u{feff}');
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                in_playlist_section = line[1..line.len() - 1].trim().eq_ignore_ascii_case("playlist");
+                has_section |= in_playlist_section;
+                continue;
+            }
+            if !in_playlist_section {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else { continue };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            // 键由字段名和编号组成，例如 File1、Title1、Length1
+            let split = key.find(|c: char| c.is_ascii_digit()).unwrap_or(key.len());
+            let (field, number) = key.split_at(split);
+            let Ok(number) = number.parse::<u32>() else { continue };
+            let entry = entries.entry(number).or_default();
+            match field {
+                "file" => entry.location = value.to_string(),
+                "title" => entry.title = Some(value.to_string()).filter(|title| !title.is_empty()),
+                "length" => entry.duration = parse_duration(value),
+                _ => {}
+            }
+        }
+
+        if !has_section {
+            return Err(PlayerError::PlaylistError("Missing [playlist] section".to_string()));
+        }
+
+        Ok(PlaylistDocument {
+            // 只有标题或时长而没有文件的编号无效
+            entries: entries.into_values().filter(|entry| !entry.location.is_empty()).collect(),
+            ..PlaylistDocument::default()
+        })
+    }
+
+    fn write(&self, document: &PlaylistDocument) -> Result<String> {
+        let mut content = String::from("[playlist]\n");
+        for (index, entry) in document.entries.iter().enumerate() {
+            let number = index + 1;
+            content.push_str(&format!("File{}={}\n", number, entry.location));
+            if let Some(title) = &entry.title {
+                content.push_str(&format!("Title{}={}\n", number, title));
+            }
+            // 未知时长按惯例写为-1
+            let duration = entry.duration.map_or("-1".to_string(), |d| d.to_string());
+            content.push_str(&format!("Length{}={}\n", number, duration));
+        }
+        content.push_str(&format!("NumberOfEntries={}\n", document.entries.len()));
+        content.push_str("Version=2\n");
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pls_fixture_round_trip() {
+        let content = include_str!("../../../tests/fixtures/playlists/summer.pls");
+        let document = Pls.read(content).unwrap();
+
+        assert_eq!(document.entries.len(), 3);
+        assert_eq!(document.entries[0].location, "music/a.mp3");
+        assert_eq!(document.entries[0].title.as_deref(), Some("Artist - Title"));
+        assert_eq!(document.entries[0].duration, Some(215.0));
+        assert_eq!(document.entries[1].title, None);
+        assert_eq!(document.entries[1].duration, None);
+        assert_eq!(document.entries[2].location, "/srv/music/夏日.flac");
+
+        assert_eq!(Pls.write(&document).unwrap(), content);
+    }
+
+    #[test]
+    fn test_pls_out_of_order_keys() {
+        let content = "[Playlist]\nnumberofentries=2\nFile2=b.mp3\nfile1=a.mp3\nTitle3=orphan\nVersion=2\n";
+        let document = Pls.read(content).unwrap();
+        assert_eq!(document.entries, [PlaylistEntry::new("a.mp3"), PlaylistEntry::new("b.mp3")]);
+
+        assert!(Pls.read("File1=a.mp3\n").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+//! XSPF 格式
+//!
+//! XML播放列表，`<location>` 为URI（本地文件使用 `file://`，相对位置为相对URI），
+//! `<duration>` 以毫秒为单位。
+
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+
+use super::{PlaylistDocument, PlaylistEntry, PlaylistFormat};
+use crate::error::{PlayerError, Result};
+
+/// XSPF 播放列表
+pub struct Xspf;
+
+impl PlaylistFormat for Xspf {
+    fn name(&self) -> &'static str {
+        "XSPF"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xspf"]
+    }
+
+    fn read(&self, content: &str) -> Result<PlaylistDocument> {
+        let mut reader = Reader::from_str(content);
+        reader.config_mut().trim_text(true);
+
+        let mut document = PlaylistDocument::default();
+        // 当前所在的元素路径（本地名称）
+        let mut elements: Vec<String> = Vec::new();
+        let mut text = String::new();
+        let mut track: Option<PlaylistEntry> = None;
+        let mut has_root = false;
+
+        loop {
+            let event = reader.read_event()
+                .map_err(|e| PlayerError::PlaylistError(format!("Invalid XSPF playlist: {}", e)))?;
+            match event {
+                Event::Start(element) => {
+                    let name = String::from_utf8_lossy(element.local_name().as_ref()).to_string();
+                    if elements.is_empty() {
+                        has_root = name == "playlist";
+                    }
+                    if name == "track" && elements.last().map(String::as_str) == Some("trackList") {
+                        track = Some(PlaylistEntry::default());
+                    }
+                    elements.push(name);
+                    text.clear();
+                }
+                Event::Text(value) => {
+                    let value = value.unescape()
+                        .map_err(|e| PlayerError::PlaylistError(format!("Invalid XSPF playlist: {}", e)))?;
+                    text.push_str(&value);
+                }
+                Event::CData(value) => text.push_str(&String::from_utf8_lossy(&value.into_inner())),
+                Event::End(_) => {
+                    let Some(name) = elements.pop() else { continue };
+                    let parent = elements.last().map(String::as_str);
+                    let value = std::mem::take(&mut text);
+                    match (name.as_str(), parent, track.as_mut()) {
+                        ("location", Some("track"), Some(entry)) if entry.location.is_empty() => {
+                            entry.location = uri_to_location(value.trim());
+                        }
+                        ("title", Some("track"), Some(entry)) => {
+                            entry.title = Some(value).filter(|title| !title.is_empty());
+                        }
+                        ("duration", Some("track"), Some(entry)) => {
+                            entry.duration = value.trim().parse::<f64>().ok()
+                                .filter(|duration| *duration >= 0.0)
+                                .map(|milliseconds| milliseconds / 1000.0);
+                        }
+                        ("title", Some("playlist"), _) => {
+                            document.title = Some(value).filter(|title| !title.is_empty());
+                        }
+                        ("track", _, _) => {
+                            // 没有位置的曲目无法播放
+                            if let Some(entry) = track.take().filter(|entry| !entry.location.is_empty()) {
+                                document.entries.push(entry);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if !has_root {
+            return Err(PlayerError::PlaylistError("Missing <playlist> element".to_string()));
+        }
+        Ok(document)
+    }
+
+    fn write(&self, document: &PlaylistDocument) -> Result<String> {
+        let mut content = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        content.push_str("<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n");
+        if let Some(title) = &document.title {
+            content.push_str(&format!("  <title>{}</title>\n", escape(title.as_str())));
+        }
+        content.push_str("  <trackList>\n");
+        for entry in &document.entries {
+            content.push_str("    <track>\n");
+            content.push_str(&format!("      <location>{}</location>\n", escape(location_to_uri(&entry.location).as_str())));
+            if let Some(title) = &entry.title {
+                content.push_str(&format!("      <title>{}</title>\n", escape(title.as_str())));
+            }
+            if let Some(duration) = entry.duration {
+                content.push_str(&format!("      <duration>{}</duration>\n", (duration * 1000.0).round() as u64));
+            }
+            content.push_str("    </track>\n");
+        }
+        content.push_str("  </trackList>\n");
+        content.push_str("</playlist>\n");
+        Ok(content)
+    }
+}
+
+/// 把 `<location>` 中的URI转换为路径，非本地文件的URI保持原样
+fn uri_to_location(uri: &str) -> String {
+    if let Some(path) = uri.strip_prefix("file://") {
+        // file://localhost/path 与 file:///path 等价
+        let path = path.strip_prefix("localhost").unwrap_or(path);
+        let path = percent_decode(path);
+        // Windows 盘符路径写作 file:///C:/...
+        let bytes = path.as_bytes();
+        if bytes.len() > 2 && bytes[0] == b'/' && bytes[1].is_ascii_alphabetic() && bytes[2] == b':' {
+            return path[1..].to_string();
+        }
+        path
+    } else if uri.contains("://") {
+        uri.to_string()
+    } else {
+        percent_decode(uri)
+    }
+}
+
+/// 把路径转换为 `<location>` 中的URI，绝对路径使用 `file://`
+fn location_to_uri(location: &str) -> String {
+    if location.contains("://") {
+        return location.to_string();
+    }
+    let path = location.replace('\\', "/");
+    let encoded = percent_encode(&path);
+    if path.starts_with('/') {
+        format!("file://{}", encoded)
+    } else if path.as_bytes().get(1) == Some(&b':') {
+        // Windows 盘符路径
+        format!("file:///{}", encoded)
+    } else {
+        encoded
+    }
+}
+
+/// 对URI路径做百分号编码，保留非保留字符、`/` 和 `:`
+fn percent_encode(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// 解码百分号编码，无效的编码保持原样
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = |byte: u8| (byte as char).to_digit(16);
+            if let (Some(high), Some(low)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                decoded.push((high * 16 + low) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xspf_fixture_round_trip() {
+        let content = include_str!("../../../tests/fixtures/playlists/summer.xspf");
+        let document = Xspf.read(content).unwrap();
+
+        assert_eq!(document.title.as_deref(), Some("Summer & Sun"));
+        assert_eq!(document.entries.len(), 3);
+        assert_eq!(document.entries[0].location, "music/a b.mp3");
+        assert_eq!(document.entries[0].title.as_deref(), Some("Artist - Title"));
+        assert_eq!(document.entries[0].duration, Some(215.0));
+        assert_eq!(document.entries[1].location, "http://radio.example/stream");
+        assert_eq!(document.entries[2].location, "/srv/music/夏日.flac");
+        assert_eq!(document.entries[2].duration, Some(12.5));
+
+        assert_eq!(Xspf.write(&document).unwrap(), content);
+    }
+
+    #[test]
+    fn test_xspf_locations() {
+        assert_eq!(uri_to_location("file:///C:/Music/a%20b.mp3"), "C:/Music/a b.mp3");
+        assert_eq!(uri_to_location("file://localhost/music/a.mp3"), "/music/a.mp3");
+        assert_eq!(location_to_uri("C:\\Music\\a b.mp3"), "file:///C:/Music/a%20b.mp3");
+        assert_eq!(percent_decode("100%"), "100%");
+
+        assert!(Xspf.read("<html></html>").is_err());
+    }
+}
@@ -0,0 +1,492 @@
+//! 智能播放列表
+//!
+//! 按规则从音乐库中动态选出曲目。定义保存在配置目录下的 `*.smart.toml` 文件中，
+//! 与 `.m3u` 播放列表放在一起：
+//!
+//! ```toml
+//! name = "Modern Jazz"
+//! rule = "genre = Jazz AND year >= 2000"
+//! sort = "most_played"
+//! limit = 50
+//! ```
+//!
+//! 规则由 `AND` / `OR` 连接的条件组成（`AND` 优先），每个条件为 `字段 运算符 值`：
+//! - 文本字段 `title`、`artist`、`album`、`album_artist`、`genre`、`path` 支持 `=`、`!=`、`~`（包含），不区分大小写
+//! - 数值字段 `year`、`duration`（秒）、`plays`、`skips` 支持 `=`、`!=`、`>`、`>=`、`<`、`<=`
+//! - 时间字段 `added`（加入音乐库）、`played`（最近播放）支持 `in last N days`
+//!
+//! 值中含有空格或 `AND` / `OR` 时可以加双引号。规则为空时选出全部曲目。
+
+use std::cmp::Reverse;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PlayerError, Result};
+use crate::library::{Library, LibraryTrack};
+use crate::utils::extract_filename;
+
+/// 一天的秒数
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// 规则求值使用的播放统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrackStats {
+    /// 播放次数
+    pub play_count: u32,
+    /// 跳过次数
+    pub skip_count: u32,
+    /// 最近一次播放的时间（Unix时间戳，秒）
+    pub last_played: Option<u64>,
+}
+
+/// 智能播放列表的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmartSort {
+    /// 按艺术家、专辑、音轨号
+    #[default]
+    Artist,
+    /// 按标题
+    Title,
+    /// 按专辑、音轨号
+    Album,
+    /// 按年份，新的在前
+    Year,
+    /// 最近加入音乐库的在前
+    RecentlyAdded,
+    /// 播放次数多的在前（只包含播放过的曲目）
+    MostPlayed,
+    /// 最近播放的在前（只包含播放过的曲目）
+    RecentlyPlayed,
+}
+
+/// 智能播放列表定义
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SmartPlaylist {
+    /// 显示名称，没有时使用文件名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 规则文本
+    #[serde(default)]
+    pub rule: String,
+    /// 排序方式
+    #[serde(default)]
+    pub sort: SmartSort,
+    /// 最多包含的曲目数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// 解析后的规则
+    #[serde(skip)]
+    parsed: Rule,
+}
+
+impl SmartPlaylist {
+    /// 从定义文件加载
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| PlayerError::PlaylistError(format!("Failed to open smart playlist: {}", e)))?;
+        Self::from_toml(&content)
+    }
+
+    /// 从TOML文本解析，规则无效时返回错误
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let mut playlist: Self = toml::from_str(content)
+            .map_err(|e| PlayerError::PlaylistError(format!("Invalid smart playlist: {}", e)))?;
+        playlist.parsed = Rule::parse(&playlist.rule)?;
+        Ok(playlist)
+    }
+
+    /// 序列化为TOML文本
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|e| PlayerError::PlaylistError(e.to_string()))
+    }
+
+    /// 显示名称，没有设置时使用去掉 `.smart.toml` 的文件名
+    pub fn display_name(&self, path: &str) -> String {
+        self.name.clone().filter(|name| !name.trim().is_empty()).unwrap_or_else(|| {
+            let file_name = extract_filename(path);
+            let stem_len = file_name.len().saturating_sub(".smart.toml".len());
+            file_name.get(..stem_len).unwrap_or(&file_name).to_string()
+        })
+    }
+
+    /// 从音乐库中选出符合规则的曲目
+    ///
+    /// # 参数
+    /// * `library` - 音乐库
+    /// * `stats` - 查询曲目的播放统计
+    /// * `now` - 当前时间（Unix时间戳，秒），用于 `in last N days`
+    ///
+    /// # 返回
+    /// 按排序方式排列、不超过数量限制的曲目
+    pub fn evaluate<'a>(&self, library: &'a Library, stats: &dyn Fn(&str) -> TrackStats, now: u64) -> Vec<&'a LibraryTrack> {
+        let mut tracks: Vec<(&LibraryTrack, TrackStats)> = library.tracks()
+            .map(|track| (track, stats(&track.path)))
+            .filter(|(track, stats)| self.parsed.matches(track, stats, now))
+            .collect();
+
+        match self.sort {
+            SmartSort::Artist => tracks.sort_by_cached_key(|(track, _)| {
+                (lowercase(track.display_artist()), lowercase(track.album.as_deref()), track.track_number_value(), track.display_title().to_lowercase())
+            }),
+            SmartSort::Title => tracks.sort_by_cached_key(|(track, _)| track.display_title().to_lowercase()),
+            SmartSort::Album => tracks.sort_by_cached_key(|(track, _)| {
+                (lowercase(track.album.as_deref()), track.track_number_value(), track.display_title().to_lowercase())
+            }),
+            SmartSort::Year => tracks.sort_by_key(|(track, _)| Reverse(year_value(track).map(|year| year as u32))),
+            SmartSort::RecentlyAdded => tracks.sort_by_key(|(track, _)| Reverse(track.added_time())),
+            SmartSort::MostPlayed => {
+                tracks.retain(|(_, stats)| stats.play_count > 0);
+                tracks.sort_by_key(|(_, stats)| Reverse((stats.play_count, stats.last_played)));
+            }
+            SmartSort::RecentlyPlayed => {
+                tracks.retain(|(_, stats)| stats.last_played.is_some());
+                tracks.sort_by_key(|(_, stats)| Reverse(stats.last_played));
+            }
+        }
+        if let Some(limit) = self.limit {
+            tracks.truncate(limit);
+        }
+        tracks.into_iter().map(|(track, _)| track).collect()
+    }
+}
+
+/// 规则中的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Artist,
+    Album,
+    AlbumArtist,
+    Genre,
+    Path,
+    Year,
+    Duration,
+    Plays,
+    Skips,
+    Added,
+    Played,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "title" => Field::Title,
+            "artist" => Field::Artist,
+            "album" => Field::Album,
+            "album_artist" => Field::AlbumArtist,
+            "genre" => Field::Genre,
+            "path" => Field::Path,
+            "year" => Field::Year,
+            "duration" => Field::Duration,
+            "plays" => Field::Plays,
+            "skips" => Field::Skips,
+            "added" => Field::Added,
+            "played" => Field::Played,
+            _ => return None,
+        })
+    }
+
+    /// 文本字段的值（小写），没有该标签时为空文本
+    fn text(self, track: &LibraryTrack) -> String {
+        let value = match self {
+            Field::Title => return track.display_title().to_lowercase(),
+            Field::Artist => track.display_artist(),
+            Field::Album => track.album.as_deref(),
+            Field::AlbumArtist => track.album_artist.as_deref(),
+            Field::Genre => track.genre.as_deref(),
+            _ => Some(track.path.as_str()),
+        };
+        lowercase(value)
+    }
+
+    /// 数值字段的值
+    fn number(self, track: &LibraryTrack, stats: &TrackStats) -> Option<f64> {
+        match self {
+            Field::Year => year_value(track),
+            Field::Duration => track.duration,
+            Field::Plays => Some(stats.play_count as f64),
+            _ => Some(stats.skip_count as f64),
+        }
+    }
+
+    /// 时间字段的值（Unix时间戳，秒）
+    fn time(self, track: &LibraryTrack, stats: &TrackStats) -> Option<u64> {
+        match self {
+            Field::Added => Some(track.added_time()),
+            _ => stats.last_played,
+        }
+    }
+}
+
+/// 文本比较方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextOp {
+    Equal,
+    NotEqual,
+    Contains,
+}
+
+/// 数值比较方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberOp {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+/// 单个条件
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Text { field: Field, op: TextOp, value: String },
+    Number { field: Field, op: NumberOp, value: f64 },
+    /// 时间在最近N天内
+    Recent { field: Field, days: u64 },
+}
+
+impl Condition {
+    /// 解析 `字段 运算符 值` 形式的条件
+    fn parse(text: &str) -> Result<Self> {
+        let invalid = |reason: &str| PlayerError::PlaylistError(format!("Invalid smart playlist rule \"{}\": {}", text, reason));
+
+        let name_len = text.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(text.len());
+        let name = text[..name_len].to_lowercase();
+        let field = Field::from_name(&name).ok_or_else(|| invalid("unknown field"))?;
+        let rest = text[name_len..].trim();
+
+        match field {
+            Field::Added | Field::Played => {
+                // in last N days
+                let words: Vec<String> = rest.split_whitespace().map(str::to_lowercase).collect();
+                match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+                    ["in", "last", days, "day" | "days"] => {
+                        let days = days.parse().map_err(|_| invalid("expected a number of days"))?;
+                        Ok(Condition::Recent { field, days })
+                    }
+                    _ => Err(invalid("expected \"in last N days\"")),
+                }
+            }
+            Field::Year | Field::Duration | Field::Plays | Field::Skips => {
+                const OPERATORS: [(&str, NumberOp); 6] = [
+                    (">=", NumberOp::GreaterOrEqual),
+                    ("<=", NumberOp::LessOrEqual),
+                    ("!=", NumberOp::NotEqual),
+                    ("=", NumberOp::Equal),
+                    (">", NumberOp::Greater),
+                    ("<", NumberOp::Less),
+                ];
+                let (value, op) = OPERATORS.iter()
+                    .find_map(|(symbol, op)| rest.strip_prefix(symbol).map(|value| (value, *op)))
+                    .ok_or_else(|| invalid("expected =, !=, >, >=, < or <="))?;
+                let value = unquote(value).parse().map_err(|_| invalid("expected a number"))?;
+                Ok(Condition::Number { field, op, value })
+            }
+            _ => {
+                const OPERATORS: [(&str, TextOp); 3] = [
+                    ("!=", TextOp::NotEqual),
+                    ("=", TextOp::Equal),
+                    ("~", TextOp::Contains),
+                ];
+                let (value, op) = OPERATORS.iter()
+                    .find_map(|(symbol, op)| rest.strip_prefix(symbol).map(|value| (value, *op)))
+                    .ok_or_else(|| invalid("expected =, != or ~"))?;
+                Ok(Condition::Text { field, op, value: unquote(value).to_lowercase() })
+            }
+        }
+    }
+
+    fn matches(&self, track: &LibraryTrack, stats: &TrackStats, now: u64) -> bool {
+        match self {
+            Condition::Text { field, op, value } => {
+                let text = field.text(track);
+                match op {
+                    TextOp::Equal => text == *value,
+                    TextOp::NotEqual => text != *value,
+                    TextOp::Contains => text.contains(value.as_str()),
+                }
+            }
+            Condition::Number { field, op, value } => match field.number(track, stats) {
+                Some(number) => match op {
+                    NumberOp::Equal => number == *value,
+                    NumberOp::NotEqual => number != *value,
+                    NumberOp::Greater => number > *value,
+                    NumberOp::GreaterOrEqual => number >= *value,
+                    NumberOp::Less => number < *value,
+                    NumberOp::LessOrEqual => number <= *value,
+                },
+                // 没有该值的曲目只满足 `!=`
+                None => *op == NumberOp::NotEqual,
+            },
+            Condition::Recent { field, days } => field.time(track, stats)
+                .is_some_and(|time| now.saturating_sub(time) <= days * SECONDS_PER_DAY),
+        }
+    }
+}
+
+/// 解析后的规则：满足任一组（OR），每组中的条件全部满足（AND），没有条件时全部满足
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Rule {
+    groups: Vec<Vec<Condition>>,
+}
+
+impl Rule {
+    fn parse(rule: &str) -> Result<Self> {
+        if rule.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        let mut groups = vec![Vec::new()];
+        let mut start = 0;
+        let keywords = words(rule).into_iter()
+            .filter(|&(word_start, word_end)| matches!(&rule[word_start..word_end], "AND" | "OR"))
+            .map(|(word_start, word_end)| (word_start, word_end, &rule[word_start..word_end]))
+            .chain(std::iter::once((rule.len(), rule.len(), "")));
+        for (word_start, word_end, keyword) in keywords {
+            let condition = rule[start..word_start].trim();
+            if condition.is_empty() {
+                return Err(PlayerError::PlaylistError(format!("Invalid smart playlist rule \"{}\": empty condition", rule)));
+            }
+            groups.last_mut().unwrap().push(Condition::parse(condition)?);
+            if keyword == "OR" {
+                groups.push(Vec::new());
+            }
+            start = word_end;
+        }
+        Ok(Self { groups })
+    }
+
+    fn matches(&self, track: &LibraryTrack, stats: &TrackStats, now: u64) -> bool {
+        self.groups.is_empty() || self.groups.iter().any(|group| group.iter().all(|condition| condition.matches(track, stats, now)))
+    }
+}
+
+/// 按空白拆分单词（引号内的空白不拆分）
+///
+/// # 返回
+/// 每个单词的 (起始位置，结束位置)
+fn words(text: &str) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (index, c) in text.char_indices() {
+        if c.is_whitespace() && !quoted {
+            if let Some(word_start) = start.take() {
+                words.push((word_start, index));
+            }
+            continue;
+        }
+        if c == '"' {
+            quoted = !quoted;
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(word_start) = start {
+        words.push((word_start, text.len()));
+    }
+    words
+}
+
+/// 去掉值两端的空白和引号
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value.strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+fn lowercase(value: Option<&str>) -> String {
+    value.map(str::to_lowercase).unwrap_or_default()
+}
+
+/// 年份标签的数值（例如 "2004-05-01" 取 2004）
+fn year_value(track: &LibraryTrack) -> Option<f64> {
+    let year = track.year.as_deref()?.trim();
+    let digits = year.find(|c: char| !c.is_ascii_digit()).unwrap_or(year.len());
+    year[..digits].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn track(path: &str, genre: &str, year: &str, added_days_ago: u64) -> LibraryTrack {
+        LibraryTrack {
+            path: path.to_string(),
+            genre: Some(genre.to_string()).filter(|genre| !genre.is_empty()),
+            year: Some(year.to_string()).filter(|year| !year.is_empty()),
+            added: NOW - added_days_ago * SECONDS_PER_DAY,
+            ..LibraryTrack::default()
+        }
+    }
+
+    fn paths(tracks: Vec<&LibraryTrack>) -> Vec<&str> {
+        tracks.into_iter().map(|track| track.path.as_str()).collect()
+    }
+
+    fn library() -> Library {
+        [
+            track("/m/a.mp3", "Jazz", "1999", 100),
+            track("/m/b.mp3", "jazz", "2004-05-01", 10),
+            track("/m/c.mp3", "Smooth Jazz", "2010", 40),
+            track("/m/d.mp3", "Rock", "2020", 1),
+            track("/m/e.mp3", "", "", 5),
+        ].into_iter().collect()
+    }
+
+    fn stats(path: &str) -> TrackStats {
+        match path {
+            "/m/a.mp3" => TrackStats { play_count: 3, skip_count: 0, last_played: Some(NOW - 100) },
+            "/m/c.mp3" => TrackStats { play_count: 7, skip_count: 2, last_played: Some(NOW - 2 * SECONDS_PER_DAY) },
+            "/m/d.mp3" => TrackStats { play_count: 3, skip_count: 1, last_played: Some(NOW - 50) },
+            _ => TrackStats::default(),
+        }
+    }
+
+    fn evaluate(content: &str) -> Vec<String> {
+        let library = library();
+        let playlist = SmartPlaylist::from_toml(content).unwrap();
+        paths(playlist.evaluate(&library, &stats, NOW)).into_iter().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(evaluate("rule = \"genre = Jazz AND year >= 2000\""), ["/m/b.mp3"]);
+        assert_eq!(evaluate("rule = \"genre ~ jazz\"\nsort = \"year\""), ["/m/c.mp3", "/m/b.mp3", "/m/a.mp3"]);
+        assert_eq!(evaluate("rule = 'genre = \"Smooth Jazz\" OR year < 2000'"), ["/m/a.mp3", "/m/c.mp3"]);
+        assert_eq!(evaluate("rule = \"added in last 30 days\"\nsort = \"recently_added\""), ["/m/d.mp3", "/m/e.mp3", "/m/b.mp3"]);
+        assert_eq!(evaluate("rule = \"played in last 1 day AND skips > 0\""), ["/m/d.mp3"]);
+        // 没有年份的曲目只满足 `!=`
+        assert_eq!(evaluate("rule = \"year != 2020 AND genre = \\\"\\\"\""), ["/m/e.mp3"]);
+        assert_eq!(evaluate("").len(), 5);
+    }
+
+    #[test]
+    fn test_most_played_with_limit() {
+        // 只包含播放过的曲目，次数相同时最近播放的在前
+        assert_eq!(evaluate("sort = \"most_played\"\nlimit = 50"), ["/m/c.mp3", "/m/d.mp3", "/m/a.mp3"]);
+        assert_eq!(evaluate("sort = \"most_played\"\nlimit = 2"), ["/m/c.mp3", "/m/d.mp3"]);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        for rule in ["genre", "mood = happy", "year >= recent", "added > 30", "genre ~ Jazz AND", "OR year = 1"] {
+            let content = format!("rule = \"{}\"", rule);
+            assert!(SmartPlaylist::from_toml(&content).is_err(), "{}", rule);
+        }
+        assert!(SmartPlaylist::from_toml("limit = \"all\"").is_err());
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let playlist = SmartPlaylist::from_toml("name = \"Modern Jazz\"\nrule = \"genre = Jazz\"\nsort = \"most_played\"\nlimit = 50\n").unwrap();
+        assert_eq!(playlist.display_name("/config/jazz.smart.toml"), "Modern Jazz");
+        assert_eq!(SmartPlaylist::from_toml(&playlist.to_toml().unwrap()).unwrap(), playlist);
+        assert_eq!(SmartPlaylist::default().display_name("/config/Recently Added.smart.toml"), "Recently Added");
+    }
+}
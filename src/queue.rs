@@ -0,0 +1,177 @@
+//! 播放队列模块
+//!
+//! 用户通过“下一首播放”和“添加到队列”排入的曲目，优先于播放列表播放，
+//! 与播放列表互相独立，持久化到配置目录下的 `queue.toml`。
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{config_sibling_path, load_or_default};
+use crate::error::{PlayerError, Result};
+
+/// 队列文件名
+const QUEUE_FILE_NAME: &str = "queue.toml";
+
+/// 播放队列
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayQueue {
+    /// 按播放顺序排列的文件路径
+    #[serde(default)]
+    items: Vec<String>,
+}
+
+impl PlayQueue {
+    /// 获取队列文件路径
+    pub fn file_path() -> Result<PathBuf> {
+        config_sibling_path(QUEUE_FILE_NAME)
+    }
+
+    /// 从队列文件加载，文件不存在或损坏时返回空队列
+    pub fn load() -> Self {
+        load_or_default(QUEUE_FILE_NAME, "播放队列", Self::from_toml)
+    }
+
+    /// 保存到队列文件
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(Self::file_path()?, self.to_toml()?)?;
+        Ok(())
+    }
+
+    /// 安全保存队列（忽略错误）
+    pub fn save_safe(&self) {
+        if let Err(e) = self.save() {
+            eprintln!("保存播放队列失败: {}", e);
+        }
+    }
+
+    /// 从TOML文本解析
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|e| PlayerError::Other(format!("Invalid queue file: {}", e)))
+    }
+
+    /// 序列化为TOML文本
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).map_err(|e| PlayerError::Other(e.to_string()))
+    }
+
+    /// 队列中的曲目
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// 队列长度
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// 队列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 下一首将要播放的曲目
+    pub fn peek(&self) -> Option<&String> {
+        self.items.first()
+    }
+
+    /// 取出下一首将要播放的曲目
+    pub fn pop(&mut self) -> Option<String> {
+        if self.items.is_empty() {
+            None
+        } else {
+            Some(self.items.remove(0))
+        }
+    }
+
+    /// 插入到队首，作为下一首播放
+    pub fn play_next(&mut self, file_path: String) {
+        self.items.insert(0, file_path);
+    }
+
+    /// 添加到队尾
+    pub fn enqueue(&mut self, file_path: String) {
+        self.items.push(file_path);
+    }
+
+    /// 移除指定位置的曲目
+    ///
+    /// # 返回
+    /// 被移除的曲目，索引无效时返回None
+    pub fn remove(&mut self, index: usize) -> Option<String> {
+        (index < self.items.len()).then(|| self.items.remove(index))
+    }
+
+    /// 把曲目向前或向后移动一位
+    ///
+    /// # 参数
+    /// * `index` - 曲目在队列中的索引
+    /// * `up` - true为向队首移动，false为向队尾移动
+    ///
+    /// # 返回
+    /// 是否发生了移动
+    pub fn move_item(&mut self, index: usize, up: bool) -> bool {
+        let target = if up { index.checked_sub(1) } else { Some(index + 1) };
+        match target {
+            Some(target) if index < self.items.len() && target < self.items.len() => {
+                self.items.swap(index, target);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 清空队列
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play_next_and_enqueue_order() {
+        let mut queue = PlayQueue::default();
+        queue.enqueue("b.mp3".to_string());
+        queue.enqueue("c.mp3".to_string());
+        queue.play_next("a.mp3".to_string());
+
+        assert_eq!(queue.peek().map(String::as_str), Some("a.mp3"));
+        assert_eq!(queue.pop().as_deref(), Some("a.mp3"));
+        assert_eq!(queue.pop().as_deref(), Some("b.mp3"));
+        assert_eq!(queue.pop().as_deref(), Some("c.mp3"));
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn test_reorder_and_remove() {
+        let mut queue = PlayQueue::default();
+        for name in ["a", "b", "c"] {
+            queue.enqueue(name.to_string());
+        }
+
+        assert!(queue.move_item(2, true));
+        assert_eq!(queue.items(), ["a", "c", "b"]);
+        assert!(!queue.move_item(0, true));
+        assert!(!queue.move_item(2, false));
+        assert!(queue.move_item(0, false));
+        assert_eq!(queue.items(), ["c", "a", "b"]);
+
+        assert_eq!(queue.remove(1).as_deref(), Some("a"));
+        assert!(queue.remove(5).is_none());
+        assert_eq!(queue.items(), ["c", "b"]);
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let mut queue = PlayQueue::default();
+        queue.enqueue("/music/夏日 1.flac".to_string());
+        queue.enqueue("C:\\Music\\b.mp3".to_string());
+
+        let content = queue.to_toml().unwrap();
+        assert_eq!(PlayQueue::from_toml(&content).unwrap(), queue);
+        assert_eq!(PlayQueue::from_toml("").unwrap(), PlayQueue::default());
+    }
+}
@@ -0,0 +1,403 @@
+//! 搜索模块
+//!
+//! 在音乐库和已缓存的播放列表中按标题、艺术家、专辑、文件名搜索曲目，可选搜索歌词文本。
+//! 匹配不区分大小写，支持模糊匹配（字符按顺序出现即可），中文文本还可以用拼音全拼或首字母匹配。
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use pinyin::ToPinyin;
+
+use crate::library::Library;
+use crate::lyrics::load_lyrics_for_audio;
+use crate::playlist::PlaylistManager;
+use crate::utils::extract_filename_without_extension;
+
+/// 最多返回的结果数
+const MAX_RESULTS: usize = 200;
+
+/// 歌词匹配的分数，低于标签和文件名的匹配
+const LYRICS_SCORE: u32 = 200;
+
+/// 搜索结果所在的位置
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchSource {
+    /// 音乐库
+    Library,
+    /// 播放列表中的曲目
+    Playlist {
+        /// 播放列表文件路径
+        path: String,
+        /// 播放列表名称
+        name: String,
+        /// 曲目在播放列表中的索引
+        index: usize,
+    },
+}
+
+/// 匹配到的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SearchField {
+    Title,
+    Artist,
+    Album,
+    FileName,
+    Lyrics,
+}
+
+/// 搜索结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// 音频文件路径
+    pub path: String,
+    /// 显示用的标题
+    pub title: String,
+    /// 艺术家
+    pub artist: Option<String>,
+    /// 所在位置
+    pub source: SearchSource,
+    /// 匹配到的字段
+    pub field: SearchField,
+    /// 匹配到的歌词行（按歌词匹配时）
+    pub lyric_line: Option<String>,
+    /// 匹配分数，越大越相关
+    score: u32,
+}
+
+/// 参与搜索的一首曲目
+struct Candidate<'a> {
+    path: &'a str,
+    title: String,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    source: SearchSource,
+}
+
+/// 预处理后的查询
+struct SearchQuery {
+    /// 小写的查询文本
+    text: String,
+    /// 去掉空白的查询文本，用于拼音和模糊匹配
+    compact: String,
+}
+
+impl SearchQuery {
+    fn new(query: &str) -> Self {
+        let text = query.trim().to_lowercase();
+        let compact = text.chars().filter(|c| !c.is_whitespace()).collect();
+        Self { text, compact }
+    }
+
+    /// 计算文本的匹配分数，不匹配时返回None
+    ///
+    /// 依次尝试：完全相同、前缀、子串、拼音（全拼或首字母）、字符按顺序出现的模糊匹配。
+    fn score(&self, text: &str) -> Option<u32> {
+        let lower = text.to_lowercase();
+        if lower == self.text {
+            return Some(1000);
+        }
+        if lower.starts_with(&self.text) {
+            return Some(900);
+        }
+        if let Some(position) = lower.find(&self.text) {
+            return Some(800 - lower[..position].chars().count().min(100) as u32);
+        }
+
+        let pinyin = pinyin_forms(text);
+        if let Some((full, initials)) = &pinyin {
+            if full.starts_with(&self.compact) || initials.starts_with(&self.compact) {
+                return Some(700);
+            }
+            if full.contains(&self.compact) || initials.contains(&self.compact) {
+                return Some(600);
+            }
+        }
+
+        let fuzzy = fuzzy_score(&self.compact, &lower);
+        let fuzzy_pinyin = pinyin.and_then(|(full, _)| fuzzy_score(&self.compact, &full)).map(|score| score - 100);
+        fuzzy.max(fuzzy_pinyin)
+    }
+
+    /// 查找第一行包含查询文本的歌词
+    fn lyric_line<'a>(&self, lyrics: &'a str) -> Option<&'a str> {
+        lyrics.lines().find(|line| line.to_lowercase().contains(&self.text))
+    }
+}
+
+/// 模糊匹配：查询的字符按顺序出现在文本中，且分布不太分散
+///
+/// 分数在100到300之间，字符间隔越小分数越高。
+fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let query: Vec<char> = query.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    // 单个字符的模糊匹配几乎总能命中，没有意义
+    if query.len() < 2 {
+        return None;
+    }
+
+    // 从每个可能的起点贪心匹配，取跨度最小的一次
+    let shortest_span = (0..text.len())
+        .filter(|&start| text[start] == query[0])
+        .filter_map(|start| {
+            let mut position = start;
+            for &c in &query[1..] {
+                position += 1 + text[position + 1..].iter().position(|&t| t == c)?;
+            }
+            Some(position - start + 1)
+        })
+        .min()?;
+
+    // 跨度超过查询长度的3倍时认为是巧合
+    let gaps = shortest_span - query.len();
+    if gaps > query.len() * 2 {
+        return None;
+    }
+    Some(300u32.saturating_sub(gaps as u32 * 10).max(100))
+}
+
+/// 中文文本的拼音全拼和首字母（小写，不含空白），不含中文时返回None
+///
+/// 非中文的字母和数字原样保留，例如 "Jay周杰伦" 得到 ("jayzhoujielun", "jayzjl")。
+fn pinyin_forms(text: &str) -> Option<(String, String)> {
+    let mut full = String::new();
+    let mut initials = String::new();
+    let mut has_chinese = false;
+    for (c, pinyin) in text.chars().zip(text.to_pinyin()) {
+        match pinyin {
+            Some(pinyin) => {
+                has_chinese = true;
+                full.push_str(pinyin.plain());
+                initials.push_str(pinyin.first_letter());
+            }
+            None if c.is_alphanumeric() => {
+                full.extend(c.to_lowercase());
+                initials.extend(c.to_lowercase());
+            }
+            None => {}
+        }
+    }
+    has_chinese.then_some((full, initials))
+}
+
+/// 在音乐库和已缓存的播放列表中搜索
+///
+/// # 参数
+/// * `query` - 搜索文本
+/// * `library` - 音乐库
+/// * `playlists` - 播放列表管理器（搜索其中缓存的持久播放列表）
+/// * `lyrics` - 文件路径 -> 歌词文本，None表示不搜索歌词
+///
+/// # 返回
+/// 按相关程度排序的结果，最多200条
+pub fn search(query: &str, library: &Library, playlists: &PlaylistManager, lyrics: Option<&HashMap<String, String>>) -> Vec<SearchResult> {
+    let query = SearchQuery::new(query);
+    if query.compact.is_empty() {
+        return Vec::new();
+    }
+
+    let mut results: Vec<SearchResult> = candidates(library, playlists)
+        .into_iter()
+        .filter_map(|candidate| {
+            let file_name = Path::new(candidate.path).file_name().map(|name| name.to_string_lossy());
+            let fields = [
+                (SearchField::Title, Some(candidate.title.as_str())),
+                (SearchField::Artist, candidate.artist),
+                (SearchField::Album, candidate.album),
+                (SearchField::FileName, file_name.as_deref()),
+            ];
+            // 分数相同时优先标题，其次艺术家、专辑、文件名
+            let best = fields.into_iter()
+                .filter_map(|(field, value)| Some((query.score(value?)?, field)))
+                .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+            let (score, field, lyric_line) = match best {
+                Some((score, field)) => (score, field, None),
+                None => {
+                    let line = query.lyric_line(lyrics?.get(candidate.path)?)?;
+                    (LYRICS_SCORE, SearchField::Lyrics, Some(line.trim().to_string()))
+                }
+            };
+            Some(SearchResult {
+                path: candidate.path.to_string(),
+                title: candidate.title,
+                artist: candidate.artist.map(str::to_string),
+                source: candidate.source,
+                field,
+                lyric_line,
+                score,
+            })
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score.cmp(&a.score)
+            .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    results.truncate(MAX_RESULTS);
+    results
+}
+
+/// 参与搜索的曲目：音乐库中的全部曲目和持久播放列表中的每一项
+fn candidates<'a>(library: &'a Library, playlists: &'a PlaylistManager) -> Vec<Candidate<'a>> {
+    let mut candidates: Vec<Candidate> = library.tracks()
+        .map(|track| Candidate {
+            path: &track.path,
+            title: track.display_title(),
+            artist: track.display_artist(),
+            album: track.album.as_deref(),
+            source: SearchSource::Library,
+        })
+        .collect();
+
+    let mut persistent = playlists.get_persistent_playlists_with_paths();
+    persistent.sort_by_key(|(path, _)| *path);
+    for (playlist_path, playlist) in persistent {
+        let name = playlist.name().unwrap_or("Unknown Playlist").to_string();
+        for (index, path) in playlist.file_paths().iter().enumerate() {
+            // 优先使用音乐库中扫描到的标签，其次使用已加载的音频标签和播放列表中的标题
+            let (title, artist, album) = match library.get(path) {
+                Some(track) => (track.display_title(), track.display_artist(), track.album.as_deref()),
+                None => {
+                    let metadata = playlists.get_cached_audio_metadata(path);
+                    let title = metadata.and_then(|metadata| metadata.title.clone())
+                        .or_else(|| playlist.extra_info_for(path).and_then(|info| info.name.clone()))
+                        .unwrap_or_else(|| extract_filename_without_extension(path));
+                    (title, metadata.and_then(|metadata| metadata.artist.as_deref()), metadata.and_then(|metadata| metadata.album.as_deref()))
+                }
+            };
+            candidates.push(Candidate {
+                path,
+                title,
+                artist,
+                album,
+                source: SearchSource::Playlist {
+                    path: playlist_path.to_string(),
+                    name: name.clone(),
+                    index,
+                },
+            });
+        }
+    }
+    candidates
+}
+
+/// 参与搜索的本地音频文件路径（去重），用于加载歌词
+pub fn searchable_paths(library: &Library, playlists: &PlaylistManager) -> Vec<String> {
+    candidates(library, playlists)
+        .into_iter()
+        .filter(|candidate| !candidate.path.contains("://"))
+        .map(|candidate| candidate.path.to_string())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// 加载音频文件的歌词文本（每行一句）
+///
+/// 没有歌词的文件对应空文本，避免重复加载。
+pub fn load_lyrics_text(paths: &[String]) -> HashMap<String, String> {
+    paths.iter()
+        .map(|path| {
+            let text = match load_lyrics_for_audio(path) {
+                Ok(Some(lyrics)) => lyrics.lines.iter()
+                    .map(|line| line.text.as_str())
+                    .filter(|text| !text.trim().is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => String::new(),
+            };
+            (path.clone(), text)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::library::LibraryTrack;
+    use std::fs;
+
+    fn track(path: &str, title: &str, artist: &str, album: &str) -> LibraryTrack {
+        LibraryTrack {
+            path: path.to_string(),
+            title: Some(title.to_string()),
+            artist: Some(artist.to_string()),
+            album: Some(album.to_string()),
+            ..LibraryTrack::default()
+        }
+    }
+
+    #[test]
+    fn test_match_score() {
+        let query = SearchQuery::new("Sun");
+        assert_eq!(query.score("sun"), Some(1000));
+        assert_eq!(query.score("Sunshine"), Some(900));
+        assert_eq!(query.score("Summer Sun"), Some(793));
+        assert_eq!(query.score("Rain"), None);
+
+        // 字符按顺序出现即可匹配，间隔越大分数越低
+        assert_eq!(SearchQuery::new("smr").score("Summer"), Some(270));
+        assert!(SearchQuery::new("sr").score("Summer") < SearchQuery::new("smr").score("Summer"));
+        assert_eq!(SearchQuery::new("ab").score("a..........b"), None);
+
+        // 中文标题支持拼音全拼和首字母
+        assert_eq!(SearchQuery::new("qingtian").score("晴天"), Some(700));
+        assert_eq!(SearchQuery::new("QT").score("晴天"), Some(700));
+        assert_eq!(SearchQuery::new("zhou jie lun").score("周杰伦"), Some(700));
+        assert_eq!(SearchQuery::new("jielun").score("周杰伦"), Some(600));
+        assert_eq!(SearchQuery::new("周伦").score("周杰伦"), Some(290));
+        assert!(SearchQuery::new("qngtn").score("晴天").is_some());
+    }
+
+    #[test]
+    fn test_search_library_and_playlists() {
+        let dir = TempDir::new("search");
+        let path_of = |name: &str| dir.join(name).to_string_lossy().to_string();
+        for name in ["qingtian.mp3", "summer.flac", "rain.mp3"] {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+        let playlist_path = path_of("favorites.m3u");
+        fs::write(&playlist_path, "#EXTM3U\n#EXTINF:-1,Rain Song\nrain.mp3\nsummer.flac\n").unwrap();
+
+        let library: Library = [
+            track(&path_of("qingtian.mp3"), "晴天", "周杰伦", "叶惠美"),
+            track(&path_of("summer.flac"), "Summer", "Joe Hisaishi", "Kikujiro"),
+        ].into_iter().collect();
+        let mut playlists = PlaylistManager::new();
+        playlists.get_or_load_playlist(&playlist_path).unwrap();
+
+        let results = search("summer", &library, &playlists, None);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.path == path_of("summer.flac") && result.field == SearchField::Title));
+        assert_eq!(results[1].source, SearchSource::Playlist {
+            path: playlist_path.clone(),
+            name: "favorites.m3u".to_string(),
+            index: 1,
+        });
+
+        // 不在音乐库中的曲目使用播放列表中的标题
+        let results = search("rain song", &library, &playlists, None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Rain Song");
+
+        let results = search("zjl", &library, &playlists, None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].field, SearchField::Artist);
+
+        assert_eq!(search("kikujiro", &library, &playlists, None)[0].field, SearchField::Album);
+        assert_eq!(search("qingtian.mp3", &library, &playlists, None)[0].field, SearchField::FileName);
+        assert!(search("  ", &library, &playlists, None).is_empty());
+
+        // 歌词只在提供歌词文本时搜索
+        let lyrics = HashMap::from([(path_of("qingtian.mp3"), "故事的小黄花\n从出生那年就飘着".to_string())]);
+        assert!(search("小黄花", &library, &playlists, None).is_empty());
+        let results = search("小黄花", &library, &playlists, Some(&lyrics));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].field, SearchField::Lyrics);
+        assert_eq!(results[0].lyric_line.as_deref(), Some("故事的小黄花"));
+
+        assert_eq!(searchable_paths(&library, &playlists), [path_of("qingtian.mp3"), path_of("rain.mp3"), path_of("summer.flac")]);
+    }
+}
@@ -0,0 +1,45 @@
+//! 测试辅助工具
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// 测试用的临时目录
+///
+/// 创建时清空同名的旧目录，离开作用域时删除，测试断言失败（panic）时也会清理。
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// 在系统临时目录下创建空目录
+    ///
+    /// # 参数
+    /// * `name` - 目录名称，与进程号一起区分不同的测试
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("summer-player-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -3,11 +3,10 @@
 //! 包含PlayerApp的实现和主要的应用程序逻辑。
 
 use std::time::Duration;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use iced::{
     widget::{column, row, container},
     window::Event as WindowEvent,
-    time,
     Element,
     Length,
     Subscription,
@@ -18,12 +17,22 @@ use iced::{
 use iced::advanced::text::Shaping;
 use tokio::sync::mpsc;
 
-use crate::audio::{AudioInfo, PlaybackState, PlaybackCommand, start_audio_playback, AudioSource};
+use crate::audio::{AudioInfo, PlaybackState, PlaybackCommand, PlaybackEvent, start_audio_playback, AudioSource, VolumeControl, EqualizerControl, SpeedControl, ReplayGainSettings, output_device_names};
+use crate::audio::time_stretch::{MIN_SPEED, MAX_SPEED};
+use crate::audio::equalizer::{builtin_preset, EQ_BAND_COUNT};
 use crate::audio::file::estimate_duration_by_parsing;
 use crate::playlist::{Playlist, PlaylistManager, PlaylistExtraInfo};
 use crate::lyrics::Lyrics;
-use crate::utils::{is_m3u_playlist, is_supported_audio_file};
-use crate::config::AppConfig;
+use crate::utils::{format_duration, is_playlist_file, is_supported_audio_file, unix_timestamp};
+use crate::bookmarks::BookmarkStore;
+use crate::queue::PlayQueue;
+use crate::library::{Library, LibraryGrouping, ScanSummary};
+use crate::watcher::{WatchEvent, WatchTargets};
+use crate::search::{SearchResult, SearchSource};
+use crate::history::{PlayHistory, PlayRecord, PlaySession};
+use crate::ipc::{IpcCall, IpcRequest, IpcResponse, PlayerStatus};
+use crate::mpris::{MprisEvent, MprisHandle, MprisSnapshot};
+use crate::config::{AppConfig, EqualizerPreset, ResampleQuality};
 use super::Message;
 use super::components::*;
 use super::theme::{AppThemeVariant};
@@ -34,6 +43,15 @@ use super::widgets::styled_text::TextStyle;
 const RIGHT_PANEL_WIDTH: f32 = 720.0;
 const LEFT_INFO_WIDTH: f32 = 260.0;
 
+/// 预排到播放引擎中的下一首的来源
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QueuedTrack {
+    /// 播放队列的队首
+    Queue,
+    /// 当前播放列表中的索引
+    Playlist(usize),
+}
+
 /// 主应用程序结构
 pub struct PlayerApp {
     /// 播放状态
@@ -48,6 +66,12 @@ pub struct PlayerApp {
     command_sender: Option<mpsc::UnboundedSender<PlaybackCommand>>,
     /// 音频处理任务句柄
     audio_handle: Option<tokio::task::JoinHandle<()>>,
+    /// 当前播放会话编号，用于忽略已停止会话发出的事件
+    playback_session: u64,
+    /// 已预排到播放引擎中的下一首
+    queued_next: Option<QueuedTrack>,
+    /// 播放队列（优先于播放列表播放）
+    play_queue: PlayQueue,
     /// 播放列表管理器
     playlist_manager: PlaylistManager,
     /// 播放列表是否已加载
@@ -66,6 +90,30 @@ pub struct PlayerApp {
     current_language: String,
     /// 当前播放模式
     play_mode: PlayMode,
+    /// 当前音量（0.0 - 1.0）
+    volume: f32,
+    /// 是否静音
+    is_muted: bool,
+    /// 可选的输出设备名称（进入设置页面时刷新）
+    output_devices: Vec<String>,
+    /// 最近一次输出设备错误
+    device_error: Option<String>,
+    /// 保存均衡器预设时输入的名称
+    equalizer_preset_name: String,
+    /// A-B循环的A点（秒）
+    loop_start: Option<f64>,
+    /// A-B循环的B点（秒）
+    loop_end: Option<f64>,
+    /// 各文件的书签
+    bookmarks: BookmarkStore,
+    /// 添加书签时输入的名称
+    bookmark_name: String,
+    /// MPRIS总线服务句柄（服务启动后设置）
+    mpris: Option<MprisHandle>,
+    /// 跳转次数，用于通知MPRIS客户端
+    seek_count: u64,
+    /// 下次开始播放时跳转到的位置（恢复上次会话时设置）
+    resume_position: Option<f64>,
     /// 应用程序配置
     app_config: AppConfig,
     /// 是否正在创建播放列表
@@ -78,6 +126,34 @@ pub struct PlayerApp {
     renaming_playlist_path: Option<String>,
     /// 重命名输入内容
     renaming_playlist_name: String,
+    /// 音乐库
+    library: Library,
+    /// 音乐库浏览方式
+    library_grouping: LibraryGrouping,
+    /// 音乐库中选中的分组名称
+    library_group: Option<String>,
+    /// 是否正在后台扫描音乐库
+    library_scanning: bool,
+    /// 扫描过程中文件夹有变化，扫描完成后需要再扫描一次
+    library_rescan_pending: bool,
+    /// 最近一次扫描的结果
+    library_last_scan: Option<ScanSummary>,
+    /// 首页搜索框文本
+    search_query: String,
+    /// 是否同时搜索歌词
+    search_lyrics: bool,
+    /// 已加载的歌词文本（文件路径 -> 歌词文本，没有歌词为空文本）
+    search_lyrics_texts: HashMap<String, String>,
+    /// 是否正在后台加载歌词文本
+    search_lyrics_loading: bool,
+    /// 当前的搜索结果
+    search_results: Vec<SearchResult>,
+    /// 播放历史
+    play_history: PlayHistory,
+    /// 正在进行的播放（结束时写入播放历史）
+    play_session: Option<PlaySession>,
+    /// 尚未写入播放历史文件的播放记录
+    pending_play_records: Vec<PlayRecord>,
 }
 
 impl Default for PlayerApp {
@@ -89,6 +165,9 @@ impl Default for PlayerApp {
             is_playing: false,
             command_sender: None,
             audio_handle: None,
+            playback_session: 0,
+            queued_next: None,
+            play_queue: PlayQueue::default(),
             playlist_manager: PlaylistManager::new(),
             playlist_loaded: false,
             current_page: PageType::default(),
@@ -98,12 +177,38 @@ impl Default for PlayerApp {
             current_theme: AppThemeVariant::default(),
             current_language: "en".to_string(),
             play_mode: PlayMode::default(),
+            volume: 0.8,
+            is_muted: false,
+            output_devices: Vec::new(),
+            device_error: None,
+            equalizer_preset_name: String::new(),
+            loop_start: None,
+            loop_end: None,
+            bookmarks: BookmarkStore::default(),
+            bookmark_name: String::new(),
+            mpris: None,
+            seek_count: 0,
+            resume_position: None,
             app_config: AppConfig::default(),
             creating_playlist: false,
             creating_playlist_name: String::new(),
             menu_playlist_path: None,
             renaming_playlist_path: None,
             renaming_playlist_name: String::new(),
+            library: Library::default(),
+            library_grouping: LibraryGrouping::default(),
+            library_group: None,
+            library_scanning: false,
+            library_rescan_pending: false,
+            library_last_scan: None,
+            search_query: String::new(),
+            search_lyrics: false,
+            search_lyrics_texts: HashMap::new(),
+            search_lyrics_loading: false,
+            search_results: Vec::new(),
+            play_history: PlayHistory::default(),
+            play_session: None,
+            pending_play_records: Vec::new(),
         }
     }
 }
@@ -179,7 +284,7 @@ impl PlayerApp {
         // 过滤支持的音频格式并排除m3u
         let valid_files: Vec<String> = file_paths
             .into_iter()
-            .filter(|p| !is_m3u_playlist(p) && is_supported_audio_file(p))
+            .filter(|p| !is_playlist_file(p) && is_supported_audio_file(p))
             .collect();
         if valid_files.is_empty() { return Task::none(); }
         if let Err(e) = self.playlist_manager.append_files_to_playlist(&playlist_path, &valid_files) {
@@ -189,6 +294,27 @@ impl PlayerApp {
         if self.menu_playlist_path.as_deref() == Some(playlist_path.as_str()) { self.menu_playlist_path = None; }
         Task::none()
     }
+    fn handle_playlist_card_export(&mut self, playlist_path: String) -> Task<Message> {
+        self.menu_playlist_path = None;
+        let file_name = std::path::Path::new(&playlist_path)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("playlist.m3u")
+            .to_string();
+        Task::perform(save_playlist_dialog(file_name), move |target| Message::PlaylistExportPathSelected(playlist_path.clone(), target))
+    }
+
+    fn handle_playlist_export_path_selected(&mut self, playlist_path: String, target: Option<String>) -> Task<Message> {
+        let Some(mut target) = target else { return Task::none(); };
+        // 没有填写可识别的扩展名时默认导出为M3U
+        if !is_playlist_file(&target) {
+            target.push_str(".m3u");
+        }
+        if let Err(e) = self.playlist_manager.export_playlist(&playlist_path, &target) {
+            eprintln!("导出播放列表失败: {}", e);
+        }
+        Task::none()
+    }
     /// 创建新的应用程序实例
     pub fn new(initial_file: Option<String>, current_language: String) -> (Self, Task<Message>) {
         // 加载配置
@@ -203,7 +329,7 @@ impl PlayerApp {
     }
 
     /// 使用指定配置创建新的应用程序实例
-    pub fn new_with_config(_initial_file: Option<String>, config: AppConfig) -> (Self, Task<Message>) {
+    pub fn new_with_config(initial_file: Option<String>, config: AppConfig) -> (Self, Task<Message>) {
 
         let mut app = Self {
             window_size: (config.window.width, config.window.height),
@@ -212,35 +338,42 @@ impl PlayerApp {
             current_page: config.ui.current_page.clone().into(),
             current_view: config.ui.current_view.clone().into(),
             play_mode: config.player.play_mode.clone().into(),
+            volume: config.player.volume.clamp(0.0, 1.0) as f32,
+            is_muted: config.player.muted,
             app_config: config,
+            play_queue: PlayQueue::load(),
+            bookmarks: BookmarkStore::load(),
+            library: Library::load(),
+            play_history: PlayHistory::load(),
             ..Self::default()
         };
         
+        if app.current_page == PageType::Settings {
+            app.refresh_output_devices();
+        }
+        
         // 自动加载配置目录下的播放列表文件
         let loaded_count = app.playlist_manager.load_config_playlists();
         if loaded_count > 0 {
             println!("自动加载了 {} 个播放列表文件", loaded_count);
         }
+        app.refresh_smart_playlists(true);
         
-        // 如果配置中有最后播放的文件且没有传入初始文件，使用配置中的文件
-        //let file_to_load = initial_file.or_else(|| app.app_config.player.last_file_path.clone());
-        
-        // 如果有文件需要加载，加载它并开始播放
-        /*if let Some(file_path) = file_to_load {
-            if !file_path.is_empty() {
-                app.handle_initial_file_load(&file_path);
-                // 自动开始播放（如果配置中启用了记住播放位置）
-                if !app.file_path.is_empty() {
-                    let file_path_clone = app.file_path.clone();
-                    return (app, Task::perform(
-                        start_audio_playback(AudioSource::FilePath(file_path_clone), None),
-                        |(sender, _handle)| Message::AudioSessionStarted(sender)
-                    ));
-                }
-            }
-        }*/
-        
-        (app, Task::none())
+        // 启动时增量扫描音乐库，只重新读取有变化的文件
+        let scan_task = if app.app_config.library.folders.is_empty() {
+            Task::none()
+        } else {
+            app.start_library_scan()
+        };
+
+        // 命令行传入的文件直接播放，否则恢复上次的会话
+        if let Some(file_path) = initial_file.filter(|path| !path.is_empty()) {
+            let task = app.handle_multiple_audio_files_selected(vec![file_path]);
+            return (app, Task::batch([scan_task, task]));
+        }
+
+        let task = app.restore_last_session();
+        (app, Task::batch([scan_task, task]))
     }
 
     /// 获取应用程序标题
@@ -250,11 +383,13 @@ impl PlayerApp {
 
     /// 处理应用程序消息
     pub fn update(&mut self, message: Message) -> Task<Message> {
-        match message {
+        let task = match message {
             Message::PlayPause => self.handle_play_pause(),
             Message::OpenFile => self.handle_open_file(),
             Message::MultipleAudioFilesSelected(file_paths) => self.handle_multiple_audio_files_selected(file_paths),
             Message::PlaylistItemSelected(index) => self.handle_playlist_item_selected(index),
+            Message::PlaylistItemMove(index, up) => self.handle_playlist_item_move(index, up),
+            Message::PlaylistItemRemove(index) => self.handle_playlist_item_remove(index),
             Message::PlaylistCardToggled(playlist_path) => self.handle_playlist_card_toggled(playlist_path),
             Message::PlaylistCardMoreClicked(playlist_path) => self.handle_playlist_card_more_clicked(playlist_path),
             Message::PlaylistCardActionRenameStart(playlist_path) => self.handle_playlist_card_rename_start(playlist_path),
@@ -264,28 +399,101 @@ impl PlayerApp {
             Message::PlaylistCardActionDelete(playlist_path) => self.handle_playlist_card_delete(playlist_path),
             Message::PlaylistCardActionAddMusic(playlist_path) => self.handle_playlist_card_add_music(playlist_path),
             Message::PlaylistAddMusicFilesSelected(playlist_path, files) => self.handle_playlist_add_music_files_selected(playlist_path, files),
+            Message::PlaylistCardActionExport(playlist_path) => self.handle_playlist_card_export(playlist_path),
+            Message::PlaylistExportPathSelected(playlist_path, target) => self.handle_playlist_export_path_selected(playlist_path, target),
             Message::StartCreatePlaylist => { self.creating_playlist = true; Task::none() },
             Message::CreatePlaylistNameChanged(name) => { self.creating_playlist_name = name; Task::none() },
             Message::ConfirmCreatePlaylist => self.handle_confirm_create_playlist(),
             Message::CancelCreatePlaylist => { self.creating_playlist = false; self.creating_playlist_name.clear(); Task::none() },
             Message::NextTrack => self.handle_next_track(),
             Message::PreviousTrack => self.handle_previous_track(),
-            Message::Tick => self.handle_tick(),
             Message::PlaybackStateUpdate(state) => self.handle_playback_state_update(state),
             Message::AudioSessionStarted(sender) => self.handle_audio_session_started(sender),
+            Message::PlaybackEvent(session, event) => self.handle_playback_event(session, event),
             Message::EventOccurred(event) => self.handle_event_occurred(event),
             Message::ToggleView => self.handle_toggle_view(),
             Message::WindowResized(width, height) => self.handle_window_resized(width, height),
             Message::ProgressChanged(progress) => self.handle_progress_changed(progress),
+            Message::VolumeChanged(volume) => self.handle_volume_changed(volume),
+            Message::VolumeReleased => { self.app_config.save_safe(); Task::none() },
+            Message::ToggleMute => self.handle_toggle_mute(),
             Message::ToggleTheme => self.handle_toggle_theme(),
             Message::PageChanged(page) => self.handle_page_changed(page),
             Message::TogglePlayMode => self.handle_toggle_play_mode(),
             Message::ConfigUpdate => self.handle_config_update(),
             Message::LanguageChanged(lang) => self.handle_language_changed(lang),
             Message::ResetConfig => self.handle_reset_config(),
+            Message::OutputDeviceSelected(device) => self.handle_output_device_selected(device),
+            Message::ResampleQualityChanged(quality) => self.handle_resample_quality_changed(quality),
+            Message::PlaybackSpeedChanged(speed) => self.handle_playback_speed_changed(speed),
+            Message::SetLoopStart => self.handle_set_loop_start(),
+            Message::SetLoopEnd => self.handle_set_loop_end(),
+            Message::ClearLoop => self.handle_clear_loop(),
+            Message::BookmarkNameChanged(name) => {
+                self.bookmark_name = name;
+                Task::none()
+            }
+            Message::AddBookmark => self.handle_add_bookmark(),
+            Message::JumpToBookmark(position) => self.handle_jump_to_bookmark(position),
+            Message::DeleteBookmark(index) => self.handle_delete_bookmark(index),
+            Message::EqualizerToggled(enabled) => self.handle_equalizer_toggled(enabled),
+            Message::EqualizerBandChanged(band, gain) => self.handle_equalizer_band_changed(band, gain),
+            Message::EqualizerBandReleased => { self.app_config.save_safe(); Task::none() },
+            Message::EqualizerPresetSelected(preset) => self.handle_equalizer_preset_selected(preset),
+            Message::EqualizerPresetNameChanged(name) => {
+                self.equalizer_preset_name = name;
+                Task::none()
+            }
+            Message::SaveEqualizerPreset => self.handle_save_equalizer_preset(),
+            Message::DeleteEqualizerPreset => self.handle_delete_equalizer_preset(),
             Message::AudioFileLoaded(file_path, success) => self.handle_audio_file_loaded(file_path, success),
             Message::AudioDurationEstimated(file_path, duration) => self.handle_audio_duration_estimated(file_path, duration),
-        }
+            Message::QueuePlayNext(file_path) => self.handle_queue_add(file_path, true),
+            Message::QueueAdd(file_path) => self.handle_queue_add(file_path, false),
+            Message::QueueMove(index, up) => self.handle_queue_move(index, up),
+            Message::QueueRemove(index) => self.handle_queue_remove(index),
+            Message::QueueClear => self.handle_queue_clear(),
+            Message::LibraryAddFolder => Task::perform(pick_folder_dialog(), Message::LibraryFolderSelected),
+            Message::LibraryFolderSelected(folder) => self.handle_library_folder_selected(folder),
+            Message::LibraryRemoveFolder(index) => self.handle_library_remove_folder(index),
+            Message::LibraryRescan => self.start_library_scan(),
+            Message::LibraryScanned(result) => self.handle_library_scanned(result),
+            Message::LibraryGroupingChanged(grouping) => {
+                self.library_grouping = grouping;
+                self.library_group = None;
+                Task::none()
+            }
+            Message::LibraryGroupSelected(name) => { self.library_group = Some(name); Task::none() },
+            Message::LibraryTrackSelected(index) => self.handle_library_track_selected(index),
+            Message::SearchQueryChanged(query) => {
+                self.search_query = query;
+                self.refresh_search();
+                self.load_search_lyrics()
+            }
+            Message::SearchLyricsToggled(enabled) => {
+                self.search_lyrics = enabled;
+                self.refresh_search();
+                self.load_search_lyrics()
+            }
+            Message::SearchLyricsLoaded(texts) => {
+                self.search_lyrics_loading = false;
+                self.search_lyrics_texts.extend(texts);
+                self.refresh_search();
+                Task::none()
+            }
+            Message::SearchResultSelected(index) => self.handle_search_result_selected(index),
+            Message::HistoryExport => Task::perform(save_history_dialog(), Message::HistoryExportPathSelected),
+            Message::HistoryExportPathSelected(target) => self.handle_history_export_path_selected(target),
+            Message::FilesChanged(event) => self.handle_files_changed(event),
+            Message::Ipc(call) => self.handle_ipc_call(call),
+            Message::Mpris(MprisEvent::Ready(handle)) => {
+                self.mpris = Some(handle);
+                Task::none()
+            }
+            Message::Mpris(MprisEvent::Call(call)) => self.handle_ipc_call(call),
+        };
+        self.publish_mpris();
+        Task::batch([task, self.save_play_history()])
     }
 
     /// 获取当前主题
@@ -299,9 +507,15 @@ impl PlayerApp {
         let nav = navigation_sidebar(&self.current_page);
         let top_row: Element<Message> = match self.current_page {
             PageType::Home => {
-                // 左侧面板：播放列表文件网格视图（自适应宽度和高度）
+                // 左侧面板：搜索框，下面是播放列表文件网格视图（自适应宽度和高度），搜索时显示搜索结果
+                let content = if self.search_query.trim().is_empty() {
+                    playlist_files_grid_view(&self.playlist_manager, self.creating_playlist, &self.creating_playlist_name, self.menu_playlist_path.as_deref(), self.renaming_playlist_path.as_deref(), &self.renaming_playlist_name)
+                } else {
+                    search_results_view(&self.search_results, &self.file_path)
+                };
                 let left_panel = column![
-                    playlist_files_grid_view(&self.playlist_manager, self.creating_playlist, &self.creating_playlist_name, self.menu_playlist_path.as_deref(), self.renaming_playlist_path.as_deref(), &self.renaming_playlist_name),
+                    search_bar(&self.search_query, self.search_lyrics, self.search_lyrics_loading),
+                    content,
                 ].spacing(16)
                  .width(Length::Fill)
                  .height(Length::Fill);
@@ -332,9 +546,59 @@ impl PlayerApp {
                 .height(Length::Fill)
                 .into()
             }
+            PageType::Library => {
+                let library = StyledContainer::new(
+                    library_page(
+                        &self.library,
+                        &self.app_config.library.folders,
+                        self.library_grouping,
+                        self.library_group.as_deref(),
+                        self.library_scanning,
+                        self.library_last_scan,
+                        &self.file_path,
+                    )
+                )
+                .style(super::widgets::styled_container::ContainerStyle::Card)
+                .padding(constants::PADDING_MEDIUM)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .build();
+
+                row![
+                    nav,
+                    library,
+                ]
+                .spacing(constants::SPACING_LARGE)
+                .height(Length::Fill)
+                .into()
+            }
+            PageType::History => {
+                let history = StyledContainer::new(history_page(&self.play_history, &self.file_path))
+                    .style(super::widgets::styled_container::ContainerStyle::Card)
+                    .padding(constants::PADDING_MEDIUM)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .build();
+
+                row![
+                    nav,
+                    history,
+                ]
+                .spacing(constants::SPACING_LARGE)
+                .height(Length::Fill)
+                .into()
+            }
             PageType::Settings => {
                 let settings = StyledContainer::new(
-                    settings_page(&self.current_theme, &self.current_language)
+                    settings_page(
+                        &self.current_theme,
+                        &self.current_language,
+                        &self.output_devices,
+                        &OutputDevice::from_name(self.app_config.player.device_name.clone()),
+                        self.device_error.as_deref(),
+                        self.app_config.player.resample_quality,
+                        equalizer_panel(&self.app_config.equalizer, &self.equalizer_preset_name),
+                    )
                 )
                 .style(super::widgets::styled_container::ContainerStyle::Card)
                 .padding(constants::PADDING_MEDIUM)
@@ -369,6 +633,7 @@ impl PlayerApp {
             simple_time_view(&self.playback_state),
             compact_file_button(),
             compact_play_mode_button(self.play_mode.clone()),
+            compact_speed_picker(self.app_config.player.playback_speed),
             compact_view_toggle_button(self.current_view.clone()),
         ]
         .spacing(constants::SPACING_SMALL)
@@ -377,7 +642,7 @@ impl PlayerApp {
         let bottom_bar = StyledContainer::new(
             row![
                 left_info,
-                container(control_buttons_view(self.is_playing))
+                container(control_buttons_view(self.is_playing, self.volume, self.is_muted))
                     .width(Length::Fill)
                     .align_x(Horizontal::Center),
                 right_controls,
@@ -409,10 +674,7 @@ impl PlayerApp {
 
     /// 创建应用程序订阅
     pub fn subscription(&self) -> Subscription<Message> {
-        use crate::config::ui::PROGRESS_UPDATE_INTERVAL;
-        
         let subscriptions = vec![
-            time::every(Duration::from_millis(PROGRESS_UPDATE_INTERVAL)).map(|_| Message::Tick),
             event::listen().map(|event| {
                 match event {
                     Event::Window(WindowEvent::Resized(size)) => {
@@ -421,6 +683,10 @@ impl PlayerApp {
                     _ => Message::EventOccurred(event),
                 }
             }),
+            ipc_subscription(),
+            mpris_subscription(),
+            // 监视位置变化（例如添加音乐文件夹）时重新建立监视
+            Subscription::run_with(self.watch_targets(), crate::watcher::watch).map(Message::FilesChanged),
         ];
         
         Subscription::batch(subscriptions)
@@ -505,7 +771,7 @@ impl PlayerApp {
         // 记录是否之前正在播放
         let was_playing = self.is_playing;
 
-        if is_m3u_playlist(&path) {
+        if is_playlist_file(&path) {
             // 使用播放列表管理器加载播放列表
             match self.playlist_manager.set_current_playlist(&path) {
                 Ok(_) => {
@@ -581,8 +847,8 @@ impl PlayerApp {
         }
 
         // 验证文件选择的合法性
-        let playlist_files: Vec<&String> = file_paths.iter().filter(|path| is_m3u_playlist(path)).collect();
-        let audio_files: Vec<String> = file_paths.iter().filter(|path| !is_m3u_playlist(path)).cloned().collect();
+        let playlist_files: Vec<&String> = file_paths.iter().filter(|path| is_playlist_file(path)).collect();
+        let audio_files: Vec<String> = file_paths.iter().filter(|path| !is_playlist_file(path)).cloned().collect();
 
         // 验证选择规则
         if !playlist_files.is_empty() && !audio_files.is_empty() {
@@ -650,7 +916,38 @@ impl PlayerApp {
         Task::none()
     }
 
+    fn handle_playlist_item_move(&mut self, index: usize, up: bool) -> Task<Message> {
+        if self.playlist_manager.current_playlist().is_some_and(|playlist| !playlist.is_read_only() && playlist.move_file(index, up)) {
+            self.current_playlist_edited();
+        }
+        Task::none()
+    }
+
+    fn handle_playlist_item_remove(&mut self, index: usize) -> Task<Message> {
+        // 正在播放的曲目和只读播放列表（智能播放列表、CUE）中的曲目不能移除
+        let removed = self.playlist_manager.current_playlist().is_some_and(|playlist| {
+            !playlist.is_read_only() && playlist.current_index() != Some(index) && playlist.remove_file(index)
+        });
+        if removed {
+            self.current_playlist_edited();
+        }
+        Task::none()
+    }
+
+    /// 写回被编辑的持久播放列表，并重新预排下一首
+    fn current_playlist_edited(&mut self) {
+        if let Some(playlist) = self.playlist_manager.current_playlist_ref().filter(|playlist| !playlist.is_temporary()) {
+            if let Err(e) = playlist.save() {
+                eprintln!("保存播放列表失败: {}", e);
+            }
+        }
+        self.queue_next_track();
+    }
+
     fn handle_next_track(&mut self) -> Task<Message> {
+        if let Some(task) = self.play_from_queue() {
+            return task;
+        }
         if self.playlist_loaded {
             if let Some(playlist) = self.playlist_manager.current_playlist() {
                 let (next_item, should_restart) = playlist.next_file_with_mode(&self.play_mode);
@@ -696,24 +993,66 @@ impl PlayerApp {
         Task::none()
     }
 
-    fn handle_tick(&mut self) -> Task<Message> {
-        // 只有在真正播放状态下（is_playing = true 且 is_paused = false）才更新时间
-        if self.is_playing && !self.playback_state.is_paused {
-            self.playback_state.current_time += 0.1;
-            if self.playback_state.total_duration > 0.0 && 
-               self.playback_state.current_time >= self.playback_state.total_duration {
-                self.handle_track_finished()
-            } else {
-                Task::none()
-            }
+    fn handle_playback_state_update(&mut self, state: PlaybackState) -> Task<Message> {
+        // 引擎无法获取时长时保留界面上已知（或后台估算）的时长
+        let total_duration = if state.total_duration > 0.0 {
+            state.total_duration
         } else {
-            Task::none()
+            self.playback_state.total_duration
+        };
+        self.playback_state = state.clone();
+        self.playback_state.total_duration = total_duration;
+        self.is_playing = state.is_playing && !state.is_paused;
+        if let Some(session) = &mut self.play_session {
+            session.update_position(state.current_time);
         }
+        Task::none()
     }
 
-    fn handle_playback_state_update(&mut self, state: PlaybackState) -> Task<Message> {
-        self.playback_state = state.clone();
-        self.is_playing = state.is_playing && !state.is_paused;
+    fn handle_playback_event(&mut self, session: u64, event: PlaybackEvent) -> Task<Message> {
+        // 忽略已停止的播放会话发出的事件
+        if session != self.playback_session {
+            return Task::none();
+        }
+        match event {
+            PlaybackEvent::State(state) => self.handle_playback_state_update(state),
+            PlaybackEvent::TrackChanged(file_path) => self.handle_queued_track_started(file_path),
+            PlaybackEvent::EndOfStream => self.handle_track_finished(),
+            PlaybackEvent::DeviceError(error) => self.handle_audio_device_error(error),
+        }
+    }
+
+    /// 引擎已开始播放预排的下一首（无缝播放或交叉淡化），只需更新界面状态
+    fn handle_queued_track_started(&mut self, file_path: String) -> Task<Message> {
+        // 上一首已经播放完毕
+        self.finish_play(true);
+        self.play_session = Some(PlaySession::new(file_path.clone(), unix_timestamp()));
+        let queued = self.queued_next.take();
+        if queued == Some(QueuedTrack::Queue) && self.play_queue.peek() == Some(&file_path) {
+            // 队列中的曲目不改变播放列表的当前位置
+            self.play_queue.pop();
+            self.play_queue.save_safe();
+            self.update_ui_for_track(&file_path);
+            self.queue_next_track();
+            return Task::none();
+        }
+        let queued_index = match queued {
+            Some(QueuedTrack::Playlist(index)) => Some(index),
+            _ => None,
+        };
+        let index = self.playlist_manager.current_playlist().and_then(|playlist| {
+            let paths = playlist.file_paths();
+            queued_index
+                .filter(|&index| paths.get(index) == Some(&file_path))
+                .or_else(|| paths.iter().position(|path| path == &file_path))
+        });
+        if let Some(index) = index {
+            if let Some(playlist) = self.playlist_manager.current_playlist() {
+                playlist.set_current_index(index);
+            }
+        }
+        self.update_ui_for_track(&file_path);
+        self.queue_next_track();
         Task::none()
     }
 
@@ -722,6 +1061,11 @@ impl PlayerApp {
         self.is_playing = true;
         self.playback_state.is_playing = true;
         self.playback_state.is_paused = false;
+        // 恢复上次会话后第一次播放，从保存的位置开始
+        if let Some(position) = self.resume_position.take() {
+            self.seek_to(position);
+        }
+        self.queue_next_track();
         Task::none()
     }
 
@@ -768,6 +1112,7 @@ impl PlayerApp {
         
         // 更新当前时间状态，提供即时UI反馈
         self.playback_state.current_time = new_time;
+        self.seek_count += 1;
         
         // 如果有播放会话，发送跳转命令
         if let Some(sender) = &self.command_sender {
@@ -776,11 +1121,130 @@ impl PlayerApp {
             } else {
                 println!("UI: Seek command sent for {:.2}s ({:.1}%)", new_time, progress * 100.0);
             }
+        } else if self.resume_position.is_some() {
+            self.resume_position = Some(new_time);
         }
         
         Task::none()
     }
 
+    /// 跳转到指定时间（秒）
+    fn seek_to(&mut self, time: f64) {
+        self.playback_state.current_time = time;
+        self.seek_count += 1;
+        if let Some(sender) = &self.command_sender {
+            if let Err(e) = sender.send(PlaybackCommand::Seek(time)) {
+                eprintln!("Failed to send seek command: {}", e);
+            }
+        } else if self.resume_position.is_some() {
+            self.resume_position = Some(time);
+        }
+    }
+
+    /// 把A-B循环区间发送到正在进行的播放会话
+    fn apply_loop(&self) {
+        let range = self.loop_start.zip(self.loop_end);
+        if let Some(sender) = &self.command_sender {
+            if let Err(e) = sender.send(PlaybackCommand::SetLoop(range)) {
+                eprintln!("Failed to send loop command: {}", e);
+            }
+        }
+    }
+
+    fn handle_set_loop_start(&mut self) -> Task<Message> {
+        if self.file_path.is_empty() {
+            return Task::none();
+        }
+        let start = self.playback_state.current_time;
+        self.loop_start = Some(start);
+        // 原来的B点在新A点之前时作废
+        if self.loop_end.is_some_and(|end| end <= start) {
+            self.loop_end = None;
+        }
+        self.apply_loop();
+        Task::none()
+    }
+
+    fn handle_set_loop_end(&mut self) -> Task<Message> {
+        let end = self.playback_state.current_time;
+        match self.loop_start {
+            Some(start) if end > start => {
+                self.loop_end = Some(end);
+                self.apply_loop();
+                // 设置B点后立即从A点开始练习
+                self.seek_to(start);
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    fn handle_clear_loop(&mut self) -> Task<Message> {
+        self.loop_start = None;
+        self.loop_end = None;
+        self.apply_loop();
+        Task::none()
+    }
+
+    fn handle_add_bookmark(&mut self) -> Task<Message> {
+        if self.file_path.is_empty() {
+            return Task::none();
+        }
+        let position = self.playback_state.current_time;
+        let name = match self.bookmark_name.trim() {
+            "" => format_duration(position),
+            name => name.to_string(),
+        };
+        self.bookmarks.add(&self.file_path, name, position);
+        self.bookmarks.save_safe();
+        self.bookmark_name.clear();
+        Task::none()
+    }
+
+    fn handle_jump_to_bookmark(&mut self, position: f64) -> Task<Message> {
+        if self.file_path.is_empty() {
+            return Task::none();
+        }
+        self.seek_to(position);
+        Task::none()
+    }
+
+    fn handle_delete_bookmark(&mut self, index: usize) -> Task<Message> {
+        if self.bookmarks.remove(&self.file_path, index).is_some() {
+            self.bookmarks.save_safe();
+        }
+        Task::none()
+    }
+
+    fn handle_volume_changed(&mut self, volume: f32) -> Task<Message> {
+        self.volume = volume.clamp(0.0, 1.0);
+        
+        if let Some(sender) = &self.command_sender {
+            if let Err(e) = sender.send(PlaybackCommand::SetVolume(self.volume)) {
+                eprintln!("Failed to send volume command: {}", e);
+            }
+        }
+        
+        // 更新配置，拖动滑块时松开后再保存
+        self.app_config.player.volume = self.volume as f64;
+        Task::none()
+    }
+
+    fn handle_toggle_mute(&mut self) -> Task<Message> {
+        self.is_muted = !self.is_muted;
+        
+        if let Some(sender) = &self.command_sender {
+            if let Err(e) = sender.send(PlaybackCommand::SetMuted(self.is_muted)) {
+                eprintln!("Failed to send mute command: {}", e);
+            }
+        }
+        
+        // 更新配置
+        self.app_config.player.muted = self.is_muted;
+        self.app_config.save_safe();
+        Task::none()
+    }
+
     fn handle_toggle_theme(&mut self) -> Task<Message> {
         self.current_theme = self.current_theme.toggle();
         // 更新配置
@@ -790,6 +1254,9 @@ impl PlayerApp {
     }
 
     fn handle_page_changed(&mut self, page: PageType) -> Task<Message> {
+        if page == PageType::Settings {
+            self.refresh_output_devices();
+        }
         self.current_page = page.clone();
         // 更新配置
         self.app_config.ui.current_page = page.into();
@@ -802,6 +1269,8 @@ impl PlayerApp {
         // 更新配置
         self.app_config.player.play_mode = self.play_mode.clone().into();
         self.app_config.save_safe();
+        // 播放模式变化后重新预排下一首
+        self.queue_next_track();
         Task::none()
     }
 
@@ -822,8 +1291,13 @@ impl PlayerApp {
     }
 
     fn handle_reset_config(&mut self) -> Task<Message> {
-        // 重置配置为默认值
+        // 重置配置为默认值，用户自定义的均衡器预设和音乐库文件夹属于用户数据，予以保留
+        let previous_device = self.app_config.player.device_name.take();
+        let user_presets = std::mem::take(&mut self.app_config.equalizer.user_presets);
+        let library = std::mem::take(&mut self.app_config.library);
         self.app_config = AppConfig::default();
+        self.app_config.equalizer.user_presets = user_presets;
+        self.app_config.library = library;
         
         // 更新应用状态以匹配默认配置
         self.current_theme = self.app_config.ui.theme.clone().into();
@@ -831,6 +1305,19 @@ impl PlayerApp {
         self.current_page = self.app_config.ui.current_page.clone().into();
         self.current_view = self.app_config.ui.current_view.clone().into();
         self.play_mode = self.app_config.player.play_mode.clone().into();
+        self.volume = self.app_config.player.volume as f32;
+        self.is_muted = self.app_config.player.muted;
+        
+        // 同步到正在进行的播放会话
+        if let Some(sender) = &self.command_sender {
+            let _ = sender.send(PlaybackCommand::SetVolume(self.volume));
+            let _ = sender.send(PlaybackCommand::SetMuted(self.is_muted));
+            let _ = sender.send(PlaybackCommand::SetEqualizer(self.app_config.equalizer.effective_gains()));
+            let _ = sender.send(PlaybackCommand::SetSpeed(self.app_config.player.playback_speed));
+            if previous_device.is_some() {
+                let _ = sender.send(PlaybackCommand::SetDevice(None));
+            }
+        }
         
         // 保存重置后的配置
         self.app_config.save_safe();
@@ -838,6 +1325,132 @@ impl PlayerApp {
         Task::none()
     }
 
+    fn handle_output_device_selected(&mut self, device: OutputDevice) -> Task<Message> {
+        let device_name = device.name().map(str::to_string);
+        if self.app_config.player.device_name == device_name {
+            return Task::none();
+        }
+        
+        // 播放中直接切换音频流，保持当前播放位置
+        if let Some(sender) = &self.command_sender {
+            if let Err(e) = sender.send(PlaybackCommand::SetDevice(device_name.clone())) {
+                eprintln!("Failed to send device command: {}", e);
+            }
+        }
+        
+        // 按名称保存，设备重新枚举后顺序变化也不受影响
+        self.app_config.player.device_name = device_name;
+        self.app_config.save_safe();
+        self.device_error = None;
+        Task::none()
+    }
+
+    fn handle_resample_quality_changed(&mut self, quality: ResampleQuality) -> Task<Message> {
+        // 从下一个播放会话开始生效
+        self.app_config.player.resample_quality = quality;
+        self.app_config.save_safe();
+        Task::none()
+    }
+
+    fn handle_playback_speed_changed(&mut self, speed: f32) -> Task<Message> {
+        let speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+        // 播放中立即生效，位置和歌词同步仍按曲目时间计算
+        if let Some(sender) = &self.command_sender {
+            if let Err(e) = sender.send(PlaybackCommand::SetSpeed(speed)) {
+                eprintln!("Failed to send speed command: {}", e);
+            }
+        }
+        self.app_config.player.playback_speed = speed;
+        self.app_config.save_safe();
+        Task::none()
+    }
+
+    /// 把当前均衡器设置发送到正在进行的播放会话
+    fn apply_equalizer(&self) {
+        if let Some(sender) = &self.command_sender {
+            if let Err(e) = sender.send(PlaybackCommand::SetEqualizer(self.app_config.equalizer.effective_gains())) {
+                eprintln!("Failed to send equalizer command: {}", e);
+            }
+        }
+    }
+
+    fn handle_equalizer_toggled(&mut self, enabled: bool) -> Task<Message> {
+        self.app_config.equalizer.enabled = enabled;
+        self.apply_equalizer();
+        self.app_config.save_safe();
+        Task::none()
+    }
+
+    fn handle_equalizer_band_changed(&mut self, band: usize, gain: f32) -> Task<Message> {
+        let equalizer = &mut self.app_config.equalizer;
+        equalizer.gains.resize(EQ_BAND_COUNT, 0.0);
+        if let Some(value) = equalizer.gains.get_mut(band) {
+            *value = gain;
+        }
+        // 手动调整后不再对应任何预设
+        equalizer.preset = None;
+        // 拖动滑块时实时生效，松开后再保存
+        self.apply_equalizer();
+        Task::none()
+    }
+
+    fn handle_equalizer_preset_selected(&mut self, preset: EqualizerPresetOption) -> Task<Message> {
+        let equalizer = &mut self.app_config.equalizer;
+        let gains = builtin_preset(&preset.0).map(|gains| gains.to_vec()).or_else(|| {
+            equalizer.user_presets.iter().find(|p| p.name == preset.0).map(|p| p.gains.clone())
+        });
+        let Some(mut gains) = gains else {
+            return Task::none();
+        };
+        gains.resize(EQ_BAND_COUNT, 0.0);
+        equalizer.gains = gains;
+        // 选择用户预设时填入名称，方便修改后覆盖保存
+        self.equalizer_preset_name = if preset.is_builtin() { String::new() } else { preset.0.clone() };
+        equalizer.preset = Some(preset.0);
+        self.apply_equalizer();
+        self.app_config.save_safe();
+        Task::none()
+    }
+
+    fn handle_save_equalizer_preset(&mut self) -> Task<Message> {
+        let name = self.equalizer_preset_name.trim().to_string();
+        if name.is_empty() || builtin_preset(&name).is_some() {
+            return Task::none();
+        }
+        
+        let equalizer = &mut self.app_config.equalizer;
+        let gains = equalizer.gains.clone();
+        // 同名预设直接覆盖
+        match equalizer.user_presets.iter_mut().find(|p| p.name == name) {
+            Some(preset) => preset.gains = gains,
+            None => equalizer.user_presets.push(EqualizerPreset { name: name.clone(), gains }),
+        }
+        equalizer.preset = Some(name);
+        self.app_config.save_safe();
+        Task::none()
+    }
+
+    fn handle_delete_equalizer_preset(&mut self) -> Task<Message> {
+        let equalizer = &mut self.app_config.equalizer;
+        let Some(name) = equalizer.preset.clone().filter(|name| builtin_preset(name).is_none()) else {
+            return Task::none();
+        };
+        equalizer.user_presets.retain(|p| p.name != name);
+        // 保留当前增益，只是不再关联到预设
+        equalizer.preset = None;
+        self.equalizer_preset_name.clear();
+        self.app_config.save_safe();
+        Task::none()
+    }
+
+    fn handle_audio_device_error(&mut self, error: String) -> Task<Message> {
+        // 引擎已切换到默认设备继续播放（默认设备也不可用时已暂停）；保留配置中的设备，下次播放时设备恢复即可使用
+        eprintln!("Audio device error: {}", error);
+        self.device_error = Some(error);
+        self.refresh_output_devices();
+        Task::none()
+    }
+
     fn handle_audio_file_loaded(&mut self, file_path: String, success: bool) -> Task<Message> {
         if success {
             println!("AudioFile loaded successfully: {}", file_path);
@@ -848,7 +1461,7 @@ impl PlayerApp {
                     if let Some(existing) = playlist.extra_info_for(&file_path) {
                         let mut updated = PlaylistExtraInfo::new(existing.path.clone());
                         if let Some(name) = existing.name.clone() { updated = updated.with_name(name); }
-                        let updated = updated.with_duration(duration);
+                        let updated = updated.with_duration(duration).with_track_count(existing.track_count);
                         playlist.set_extra_info(updated);
                     } else {
                         let info = PlaylistExtraInfo::new(file_path.clone()).with_duration(duration);
@@ -871,7 +1484,7 @@ impl PlayerApp {
             if let Some(existing) = playlist.extra_info_for(&file_path) {
                 let mut updated_info = PlaylistExtraInfo::new(existing.path.clone());
                 if let Some(name) = existing.name.clone() { updated_info = updated_info.with_name(name); }
-                let updated_info = updated_info.with_duration(duration);
+                let updated_info = updated_info.with_duration(duration).with_track_count(existing.track_count);
                 playlist.set_extra_info(updated_info);
             } else {
                 let info = PlaylistExtraInfo::new(file_path.clone()).with_duration(duration);
@@ -898,6 +1511,80 @@ impl PlayerApp {
         Task::none()
     }
 
+    fn handle_ipc_call(&mut self, call: IpcCall) -> Task<Message> {
+        let task = match call.request.clone() {
+            IpcRequest::PlayPause => self.handle_play_pause(),
+            IpcRequest::Play if !self.is_playing => self.handle_play_pause(),
+            IpcRequest::Pause if self.is_playing => self.handle_play_pause(),
+            IpcRequest::Play | IpcRequest::Pause | IpcRequest::Status => Task::none(),
+            IpcRequest::Next => self.handle_next_track(),
+            IpcRequest::Previous => self.handle_previous_track(),
+            IpcRequest::Stop => {
+                self.finish_play(false);
+                self.stop_current_playback();
+                Task::none()
+            }
+            IpcRequest::SetVolume { volume } => {
+                // 界面上的滑块松开时才保存，通过IPC设置的音量直接保存
+                let task = self.handle_volume_changed(volume);
+                self.app_config.save_safe();
+                task
+            }
+            IpcRequest::SetSpeed { speed } => self.handle_playback_speed_changed(speed),
+            IpcRequest::Seek { position } => {
+                if self.file_path.is_empty() {
+                    call.reply(IpcResponse::error("No file loaded"));
+                    return Task::none();
+                }
+                let duration = self.playback_state.total_duration;
+                let position = if duration > 0.0 { position.clamp(0.0, duration) } else { position.max(0.0) };
+                self.seek_to(position);
+                Task::none()
+            }
+            IpcRequest::Open { files } => self.handle_multiple_audio_files_selected(files),
+        };
+
+        call.reply(IpcResponse::ok(self.ipc_status()));
+        task
+    }
+
+    /// 生成远程控制使用的播放状态快照
+    fn ipc_status(&self) -> PlayerStatus {
+        let metadata = self.audio_info.as_ref().map(|info| &info.metadata);
+        PlayerStatus {
+            file: (!self.file_path.is_empty()).then(|| self.file_path.clone()),
+            title: metadata.and_then(|m| m.title.clone()),
+            artist: metadata.and_then(|m| m.artist.clone()),
+            playing: self.is_playing,
+            stopped: self.command_sender.is_none(),
+            position: self.playback_state.current_time,
+            duration: self.playback_state.total_duration,
+            volume: self.volume,
+            muted: self.is_muted,
+            speed: self.app_config.player.playback_speed,
+        }
+    }
+
+    /// 把播放状态发布给MPRIS客户端
+    fn publish_mpris(&self) {
+        let Some(mpris) = &self.mpris else {
+            return;
+        };
+        let playlist = self.playlist_manager.current_playlist_ref().filter(|_| self.playlist_loaded);
+        let has_other_tracks = playlist.is_some_and(|playlist| playlist.len() > 1);
+        let metadata = self.audio_info.as_ref().map(|info| &info.metadata);
+        let snapshot = MprisSnapshot {
+            status: self.ipc_status(),
+            album: metadata.and_then(|m| m.album.clone()),
+            art_url: None,
+            track_index: playlist.and_then(|playlist| playlist.current_index()).or(Some(0)),
+            can_go_next: has_other_tracks || !self.play_queue.is_empty(),
+            can_go_previous: has_other_tracks,
+            seek_count: self.seek_count,
+        };
+        mpris.publish(snapshot, metadata.and_then(|m| m.cover_art.as_ref()));
+    }
+
     fn handle_confirm_create_playlist(&mut self) -> Task<Message> {
         let name = self.creating_playlist_name.trim().to_string();
         if name.is_empty() {
@@ -954,10 +1641,15 @@ impl PlayerApp {
         self.app_config.ui.current_page = self.current_page.clone().into();
         self.app_config.ui.current_view = self.current_view.clone().into();
         self.app_config.player.play_mode = self.play_mode.clone().into();
+        self.app_config.player.volume = self.volume as f64;
+        self.app_config.player.muted = self.is_muted;
         
         if !self.file_path.is_empty() {
             self.app_config.player.last_file_path = Some(self.file_path.clone());
         }
+        self.app_config.player.last_playlist_path = self.playlist_manager.current_playlist_path()
+            .filter(|_| !self.playlist_manager.is_current_temporary())
+            .map(str::to_string);
         
         // 记住播放位置
         if self.app_config.player.remember_position {
@@ -965,19 +1657,54 @@ impl PlayerApp {
         }
     }
 
+    /// 从配置中读取ReplayGain设置
+    fn replay_gain_settings(&self) -> ReplayGainSettings {
+        ReplayGainSettings {
+            mode: self.app_config.player.replay_gain_mode,
+            preamp_db: self.app_config.player.replay_gain_preamp as f32,
+        }
+    }
+
+    /// 重新枚举输出设备
+    fn refresh_output_devices(&mut self) {
+        match output_device_names() {
+            Ok(names) => self.output_devices = names,
+            Err(e) => eprintln!("Error listing devices: {}", e),
+        }
+    }
+
     /// 启动音频播放，优先使用全局缓存的AudioFile实例
+    /// 
+    /// 同时订阅播放引擎的事件流，播放位置和曲目结束均以引擎上报为准。
     fn start_audio_playback_task(&mut self, file_path: String) -> Task<Message> {
-        if let Ok(audio_file) = self.playlist_manager.get_or_load_audio_file(&file_path) {
-            return Task::perform(
-                start_audio_playback(AudioSource::AudioFile(audio_file), None),
-                |(sender, _handle)| Message::AudioSessionStarted(sender)
-            );
-        }
-        // 回退到路径方式
-        Task::perform(
-            start_audio_playback(AudioSource::FilePath(file_path), None),
+        // 上一首没有播放完毕就切换的记为跳过
+        self.finish_play(false);
+        self.play_session = Some(PlaySession::new(file_path.clone(), unix_timestamp()));
+        let volume = VolumeControl::new(self.volume, self.is_muted);
+        let replay_gain = self.replay_gain_settings();
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        self.playback_session += 1;
+        let session = self.playback_session;
+        
+        let source = match self.playlist_manager.get_or_load_audio_file(&file_path) {
+            Ok(audio_file) => AudioSource::AudioFile(audio_file),
+            // 回退到路径方式
+            Err(_) => AudioSource::FilePath(file_path),
+        };
+        let device_name = self.app_config.player.device_name.clone();
+        let resample_quality = self.app_config.player.resample_quality;
+        let equalizer = EqualizerControl::new(self.app_config.equalizer.effective_gains());
+        let speed = SpeedControl::new(self.app_config.player.playback_speed);
+        let playback_task = Task::perform(
+            start_audio_playback(source, Some(event_sender), device_name, volume, equalizer, speed, replay_gain, resample_quality),
             |(sender, _handle)| Message::AudioSessionStarted(sender)
-        )
+        );
+        let events = iced::futures::stream::unfold(event_receiver, |mut receiver| async move {
+            receiver.recv().await.map(|event| (event, receiver))
+        });
+        let event_task = Task::run(events, move |event| Message::PlaybackEvent(session, event));
+        
+        Task::batch([playback_task, event_task])
     }
 
     /// 启动后台来刷新AudioInfo中的duration信息
@@ -1047,8 +1774,16 @@ impl PlayerApp {
     fn update_ui_for_track(&mut self, file_path: &str) {
         self.file_path = file_path.to_string();
         
+        // A-B循环只对原来的曲目有效
+        if self.loop_start.is_some() {
+            self.loop_start = None;
+            self.loop_end = None;
+            self.apply_loop();
+        }
+        
         // 重置播放状态
         self.playback_state.current_time = 0.0;
+        self.resume_position = None;
         self.playback_state.current_samples = 0;
         
         // 从全局缓存获取AudioFile信息
@@ -1082,6 +1817,64 @@ impl PlayerApp {
 
 
 
+    /// 将下一首预排到播放引擎，实现无缝播放或交叉淡化
+    /// 
+    /// 播放队列不为空时预排队首（单曲循环除外），否则预排播放列表中的下一首。
+    /// 只有与当前曲目采样率和声道数一致的下一首才会被预排，
+    /// 其他情况仍在曲目结束后重新建立播放会话。单曲循环时不做交叉淡化。
+    fn queue_next_track(&mut self) {
+        let Some(sender) = self.command_sender.clone() else {
+            self.queued_next = None;
+            return;
+        };
+        // 下一首可能已经改变，先取消之前预排的曲目
+        if self.queued_next.take().is_some() {
+            let _ = sender.send(PlaybackCommand::ClearNext);
+        }
+        let Some(current_info) = self.audio_info.clone() else {
+            return;
+        };
+        
+        let next = match self.play_queue.peek() {
+            Some(file_path) if self.play_mode != PlayMode::SingleLoop => {
+                Some((QueuedTrack::Queue, file_path.clone(), false))
+            }
+            _ if !self.playlist_loaded => None,
+            _ => self.playlist_manager.current_playlist().and_then(|playlist| {
+                let index = playlist.peek_next_index_with_mode(&self.play_mode)?;
+                let is_same_track = playlist.current_index() == Some(index);
+                playlist.file_paths().get(index).cloned().map(|path| (QueuedTrack::Playlist(index), path, is_same_track))
+            }),
+        };
+        let Some((queued, file_path, is_same_track)) = next else {
+            return;
+        };
+        
+        // 与引擎一致：曲目不足淡化时长的两倍时直接衔接
+        let crossfade_seconds = self.app_config.player.crossfade_seconds
+            .clamp(0.0, crate::config::MAX_CROSSFADE_SECONDS);
+        let crossfade = if self.play_mode == PlayMode::SingleLoop
+            || is_same_track
+            || self.playback_state.total_duration < crossfade_seconds * 2.0 {
+            0.0
+        } else {
+            crossfade_seconds
+        };
+        
+        match self.playlist_manager.get_or_load_audio_file(&file_path) {
+            Ok(audio_file) => {
+                if audio_file.info.sample_rate != current_info.sample_rate
+                    || audio_file.info.channels != current_info.channels {
+                    return;
+                }
+                if sender.send(PlaybackCommand::QueueNext(Box::new(audio_file), crossfade)).is_ok() {
+                    self.queued_next = Some(queued);
+                }
+            }
+            Err(e) => eprintln!("预排下一首失败: {}", e),
+        }
+    }
+
     fn stop_current_playback(&mut self) {
         if let Some(sender) = &self.command_sender {
             let _ = sender.send(PlaybackCommand::Stop);
@@ -1095,6 +1888,7 @@ impl PlayerApp {
         self.playback_state.is_paused = false;
         self.playback_state.current_time = 0.0;
         self.command_sender = None;
+        self.queued_next = None;
         if let Some(handle) = self.audio_handle.take() {
             handle.abort();
         }
@@ -1111,12 +1905,374 @@ impl PlayerApp {
         self.playback_state.is_paused = false;
         self.command_sender = None;
         self.audio_handle = None;
+
+        // 保存会话，下次启动时恢复
+        self.update_config_from_state();
+        self.app_config.save_safe();
+        PlayHistory::append_safe(&std::mem::take(&mut self.pending_play_records));
+    }
+
+    /// 恢复上次退出时的会话
+    /// 
+    /// 重新打开上次的播放列表并选中上次的曲目，定位到保存的播放位置，保持暂停状态。
+    fn restore_last_session(&mut self) -> Task<Message> {
+        let player = &self.app_config.player;
+        let Some(file_path) = player.last_file_path.clone().filter(|path| std::path::Path::new(path).exists()) else {
+            return Task::none();
+        };
+        let last_playlist_path = player.last_playlist_path.clone();
+        let position = if player.remember_position { player.last_position } else { 0.0 };
+
+        let playlist_restored = last_playlist_path.is_some_and(|path| self.restore_playlist(&path, &file_path));
+        if !playlist_restored {
+            // 上次播放的是临时播放列表，或播放列表已不存在
+            let mut playlist = Playlist::create_from_audio_files(vec![file_path.clone()]);
+            playlist.set_current_index(0);
+            self.playlist_manager.insert_and_set_current_playlist(playlist);
+        }
+
+        self.update_ui_for_track(&file_path);
+        let duration = self.playback_state.total_duration;
+        if position > 0.0 && (duration <= 0.0 || position < duration) {
+            self.playback_state.current_time = position;
+            self.resume_position = Some(position);
+        }
+        self.start_background_audio_duration_loading()
+    }
+
+    /// 把指定播放列表设为当前播放列表并选中其中的曲目
+    /// 
+    /// # 参数
+    /// * `playlist_path` - 播放列表文件路径
+    /// * `file_path` - 要选中的音频文件路径
+    /// 
+    /// # 返回
+    /// 播放列表无法加载或不包含该曲目时返回false
+    fn restore_playlist(&mut self, playlist_path: &str, file_path: &str) -> bool {
+        if let Err(e) = self.playlist_manager.get_or_load_playlist(playlist_path) {
+            eprintln!("恢复播放列表失败: {}", e);
+            return false;
+        }
+        if self.playlist_manager.set_current_playlist(playlist_path).is_err() {
+            return false;
+        }
+        let Some(playlist) = self.playlist_manager.current_playlist() else { return false };
+        let Some(index) = playlist.file_paths().iter().position(|path| path == file_path) else { return false };
+        playlist.set_current_index(index);
+        self.playlist_loaded = true;
+        self.playlist_manager.preload_current_playlist_audio_to_cache();
+        true
+    }
+
+    /// 播放队列中的下一首
+    /// 
+    /// # 返回
+    /// 队列为空时返回None
+    fn play_from_queue(&mut self) -> Option<Task<Message>> {
+        let file_path = self.play_queue.pop()?;
+        self.play_queue.save_safe();
+        self.update_ui_for_track(&file_path);
+        self.stop_current_playback();
+        Some(self.start_audio_playback_task(file_path))
+    }
+
+    fn handle_queue_add(&mut self, file_path: String, play_next: bool) -> Task<Message> {
+        if play_next {
+            self.play_queue.play_next(file_path);
+        } else {
+            self.play_queue.enqueue(file_path);
+        }
+        self.play_queue_changed();
+        Task::none()
+    }
+
+    fn handle_queue_move(&mut self, index: usize, up: bool) -> Task<Message> {
+        if self.play_queue.move_item(index, up) {
+            self.play_queue_changed();
+        }
+        Task::none()
+    }
+
+    fn handle_queue_remove(&mut self, index: usize) -> Task<Message> {
+        if self.play_queue.remove(index).is_some() {
+            self.play_queue_changed();
+        }
+        Task::none()
+    }
+
+    fn handle_queue_clear(&mut self) -> Task<Message> {
+        self.play_queue.clear();
+        self.play_queue_changed();
+        Task::none()
+    }
+
+    /// 保存队列，并在队首变化时重新预排下一首
+    fn play_queue_changed(&mut self) {
+        self.play_queue.save_safe();
+        self.queue_next_track();
+    }
+
+    fn handle_library_folder_selected(&mut self, folder: Option<String>) -> Task<Message> {
+        let Some(folder) = folder else { return Task::none(); };
+        if self.app_config.library.folders.contains(&folder) {
+            return Task::none();
+        }
+        self.app_config.library.folders.push(folder);
+        self.app_config.save_safe();
+        self.start_library_scan()
+    }
+
+    fn handle_library_remove_folder(&mut self, index: usize) -> Task<Message> {
+        if index >= self.app_config.library.folders.len() {
+            return Task::none();
+        }
+        self.app_config.library.folders.remove(index);
+        self.app_config.save_safe();
+        // 重新扫描以移除该文件夹中的曲目
+        self.start_library_scan()
+    }
+
+    /// 在后台扫描音乐库文件夹
+    fn start_library_scan(&mut self) -> Task<Message> {
+        self.start_library_update(None)
+    }
+
+    /// 在后台更新音乐库
+    ///
+    /// # 参数
+    /// * `changed_paths` - 只更新这些发生变化的路径，None为扫描全部文件夹
+    fn start_library_update(&mut self, changed_paths: Option<Vec<String>>) -> Task<Message> {
+        if self.library_scanning {
+            // 当前扫描完成后再完整扫描一次，其中包含这些变化
+            self.library_rescan_pending = true;
+            return Task::none();
+        }
+        self.library_scanning = true;
+
+        let mut library = self.library.clone();
+        let folders = self.app_config.library.folders.clone();
+        Task::perform(async move {
+            // 扫描需要读取大量文件，放到阻塞线程中执行
+            tokio::task::spawn_blocking(move || {
+                let summary = match changed_paths {
+                    Some(paths) => library.refresh_paths(&folders, &paths),
+                    None => library.scan(&folders),
+                };
+                library.save_safe();
+                (library, summary)
+            }).await.ok()
+        }, Message::LibraryScanned)
+    }
+
+    /// 按当前音乐库重新生成智能播放列表
+    ///
+    /// # 参数
+    /// * `include_current` - 是否也重新生成当前播放列表
+    ///
+    /// # 返回
+    /// 当前播放列表的曲目是否发生了变化
+    fn refresh_smart_playlists(&mut self, include_current: bool) -> bool {
+        let history = &self.play_history;
+        self.playlist_manager.refresh_smart_playlists(&self.library, &|path: &str| history.stats(path), include_current)
+    }
+
+    /// 结束正在进行的播放，写入播放历史并按新的统计更新智能播放列表
+    ///
+    /// # 参数
+    /// * `completed` - 是否播放完毕，否则记为跳过
+    fn finish_play(&mut self, completed: bool) {
+        let Some(session) = self.play_session.take() else { return; };
+        let mut record = session.finish(completed);
+        if let Some(track) = self.library.get(&record.path) {
+            record.title = track.title.clone();
+            record.artist = track.display_artist().map(str::to_string);
+            record.duration = track.duration;
+        } else if let Some(metadata) = self.playlist_manager.get_cached_audio_metadata(&record.path) {
+            record.title = metadata.title.clone();
+            record.artist = metadata.artist.clone();
+            record.duration = self.playlist_manager.get_cached_audio_duration(&record.path);
+        }
+        self.pending_play_records.push(record.clone());
+        self.play_history.record(record);
+        // 正在播放的智能播放列表不随播放统计变化，避免列表在播放过程中重排
+        self.refresh_smart_playlists(false);
+    }
+
+    /// 把新的播放记录追加到播放历史文件，在阻塞线程中执行
+    fn save_play_history(&mut self) -> Task<Message> {
+        if self.pending_play_records.is_empty() {
+            return Task::none();
+        }
+        let records = std::mem::take(&mut self.pending_play_records);
+        Task::future(async move {
+            let _ = tokio::task::spawn_blocking(move || PlayHistory::append_safe(&records)).await;
+        }).discard()
+    }
+
+    fn handle_history_export_path_selected(&mut self, target: Option<String>) -> Task<Message> {
+        let Some(mut target) = target else { return Task::none(); };
+        // 没有填写扩展名时默认导出为CSV
+        if std::path::Path::new(&target).extension().is_none() {
+            target.push_str(".csv");
+        }
+        if let Err(e) = self.play_history.export(&target) {
+            eprintln!("导出播放历史失败: {}", e);
+        }
+        Task::none()
+    }
+
+    /// 需要监视的音乐库文件夹和播放列表目录
+    fn watch_targets(&self) -> WatchTargets {
+        WatchTargets {
+            library_folders: self.app_config.library.folders.clone(),
+            playlist_dir: PlaylistManager::config_playlist_dir(),
+        }
+    }
+
+    fn handle_files_changed(&mut self, event: WatchEvent) -> Task<Message> {
+        match event {
+            WatchEvent::LibraryChanged(paths) => self.start_library_update(Some(paths)),
+            WatchEvent::PlaylistsChanged(paths) => {
+                let current_path = self.playlist_manager.current_playlist_path().map(str::to_string);
+                let mut current_changed = false;
+                for path in paths {
+                    if self.playlist_manager.reload_playlist(&path) {
+                        println!("播放列表文件已变化，重新加载: {}", path);
+                        current_changed |= current_path.as_deref() == Some(path.as_str());
+                    }
+                }
+                current_changed |= self.refresh_smart_playlists(true);
+                // 当前播放列表被修改或删除后，下一首可能已经改变
+                if current_changed {
+                    self.queue_next_track();
+                }
+                self.refresh_search();
+                Task::none()
+            }
+        }
+    }
+
+    fn handle_library_scanned(&mut self, result: Option<(Library, ScanSummary)>) -> Task<Message> {
+        self.library_scanning = false;
+        match result {
+            Some((library, summary)) => {
+                println!("音乐库扫描完成: 新增 {}，更新 {}，移除 {}，未变化 {}", summary.added, summary.updated, summary.removed, summary.unchanged);
+                self.library = library;
+                self.library_last_scan = Some(summary);
+                if self.refresh_smart_playlists(true) {
+                    self.queue_next_track();
+                }
+                self.refresh_search();
+            }
+            None => eprintln!("音乐库扫描任务失败"),
+        }
+        if std::mem::take(&mut self.library_rescan_pending) {
+            return self.start_library_scan();
+        }
+        Task::none()
+    }
+
+    /// 把选中分组的曲目作为临时播放列表，从指定曲目开始播放
+    fn handle_library_track_selected(&mut self, index: usize) -> Task<Message> {
+        let Some(group) = self.library_group.as_deref() else { return Task::none(); };
+        let playlist = Playlist::create_from_library_tracks(&self.library.group_tracks(self.library_grouping, group));
+        self.play_temporary_playlist(playlist, index)
+    }
+
+    /// 把临时播放列表设为当前播放列表，从指定曲目开始播放
+    fn play_temporary_playlist(&mut self, mut playlist: Playlist, index: usize) -> Task<Message> {
+        let Some(file_path) = playlist.set_current_index(index).cloned() else { return Task::none(); };
+
+        self.playlist_manager.insert_and_set_current_playlist(playlist);
+        self.playlist_loaded = true;
+        self.current_view = ViewType::Playlist;
+        self.app_config.ui.current_view = self.current_view.clone().into();
+        self.app_config.save_safe();
+        let background_task = self.start_background_audio_duration_loading();
+        self.update_ui_for_track(&file_path);
+        self.stop_current_playback();
+        let playback_task = self.start_audio_playback_task(file_path);
+        Task::batch([background_task, playback_task])
+    }
+
+    /// 按当前搜索文本重新搜索音乐库和播放列表
+    fn refresh_search(&mut self) {
+        let lyrics = self.search_lyrics.then_some(&self.search_lyrics_texts);
+        self.search_results = crate::search::search(&self.search_query, &self.library, &self.playlist_manager, lyrics);
+    }
+
+    /// 需要搜索歌词时，在后台加载还没有加载过的歌词文本
+    fn load_search_lyrics(&mut self) -> Task<Message> {
+        if !self.search_lyrics || self.search_lyrics_loading || self.search_query.trim().is_empty() {
+            return Task::none();
+        }
+        let paths: Vec<String> = crate::search::searchable_paths(&self.library, &self.playlist_manager)
+            .into_iter()
+            .filter(|path| !self.search_lyrics_texts.contains_key(path))
+            .collect();
+        if paths.is_empty() {
+            return Task::none();
+        }
+
+        self.search_lyrics_loading = true;
+        Task::perform(async move {
+            // 读取歌词文件和音频标签，放到阻塞线程中执行
+            tokio::task::spawn_blocking(move || crate::search::load_lyrics_text(&paths)).await.unwrap_or_default()
+        }, Message::SearchLyricsLoaded)
+    }
+
+    /// 播放选中的搜索结果
+    ///
+    /// 播放列表中的结果切换到该播放列表并跳转到对应曲目，音乐库中的结果作为临时播放列表播放。
+    fn handle_search_result_selected(&mut self, index: usize) -> Task<Message> {
+        let Some(result) = self.search_results.get(index).cloned() else { return Task::none(); };
+        match result.source {
+            SearchSource::Library => {
+                let Some(track) = self.library.get(&result.path) else { return Task::none(); };
+                let playlist = Playlist::create_from_library_tracks(&[track]);
+                self.play_temporary_playlist(playlist, 0)
+            }
+            SearchSource::Playlist { path, index, .. } => {
+                let switch_task = if self.playlist_loaded && self.playlist_manager.current_playlist_path() == Some(path.as_str()) {
+                    self.current_view = ViewType::Playlist;
+                    self.app_config.ui.current_view = self.current_view.clone().into();
+                    self.app_config.save_safe();
+                    Task::none()
+                } else {
+                    self.handle_playlist_card_toggled(path.clone())
+                };
+
+                // 搜索之后播放列表可能被编辑过，索引不再对应时按文件路径查找
+                let index = self.playlist_manager.current_playlist_ref()
+                    .filter(|_| self.playlist_manager.current_playlist_path() == Some(path.as_str()))
+                    .and_then(|playlist| {
+                        let paths = playlist.file_paths();
+                        if paths.get(index) == Some(&result.path) {
+                            Some(index)
+                        } else {
+                            paths.iter().position(|file_path| *file_path == result.path)
+                        }
+                    });
+                match index {
+                    Some(index) => Task::batch([switch_task, self.handle_playlist_item_selected(index)]),
+                    None => switch_task,
+                }
+            }
+        }
     }
 
     fn handle_track_finished(&mut self) -> Task<Message> {
+        self.finish_play(true);
         self.playback_state.current_time = self.playback_state.total_duration;
         self.cleanup_playback_state();
         
+        // 单曲循环时继续重复当前曲目，队列留到手动切换下一首时播放
+        if self.play_mode != PlayMode::SingleLoop {
+            if let Some(task) = self.play_from_queue() {
+                return task;
+            }
+        }
+        
         if self.playlist_loaded {
             if let Some(playlist) = self.playlist_manager.current_playlist() {
                 let (next_item, should_restart) = playlist.next_file_with_mode(&self.play_mode);
@@ -1199,6 +2355,7 @@ impl PlayerApp {
             simple_time_view(&self.playback_state),
             compact_file_button(),
             compact_play_mode_button(self.play_mode.clone()),
+            compact_speed_picker(self.app_config.player.playback_speed),
             compact_view_toggle_button(self.current_view.clone()),
         ]
         .spacing(constants::SPACING_SMALL)
@@ -1208,7 +2365,7 @@ impl PlayerApp {
         let bottom_bar = StyledContainer::new(
             row![
                 left_info,
-                container(control_buttons_view(self.is_playing))
+                container(control_buttons_view(self.is_playing, self.volume, self.is_muted))
                     .width(Length::Fill)
                     .align_x(Horizontal::Center),
                 right_controls,
@@ -1247,6 +2404,13 @@ impl PlayerApp {
             let empty_playlist = Playlist::new();
             playlist_view(&empty_playlist, false, self.is_playing, &self.playlist_manager)
         };
+        let playlist_content = if self.play_queue.is_empty() {
+            playlist_content
+        } else {
+            column![queue_view(&self.play_queue, &self.playlist_manager), playlist_content]
+                .spacing(constants::SPACING_MEDIUM)
+                .into()
+        };
         let lyrics_content = lyrics_view(&self.file_path, self.is_playing, self.playback_state.current_time, self.current_lyrics.clone(), self.window_size.1);
 
         match self.current_view {
@@ -1257,7 +2421,16 @@ impl PlayerApp {
 
     fn create_main_player_view(&self) -> Element<Message> {
         // 主内容（不包含底部栏与进度条，由首页统一布局承载）
-        let main_content = self.create_sliding_animation_view();
+        let mut main_content = column![container(self.create_sliding_animation_view()).height(Length::Fill)]
+            .spacing(constants::SPACING_MEDIUM);
+        if !self.file_path.is_empty() {
+            main_content = main_content.push(practice_panel(
+                self.loop_start,
+                self.loop_end,
+                self.bookmarks.bookmarks(&self.file_path),
+                &self.bookmark_name,
+            ));
+        }
 
         StyledContainer::new(container(main_content).height(Length::Fill).width(Length::Fill))
             .style(super::widgets::styled_container::ContainerStyle::Transparent)
@@ -1300,9 +2473,10 @@ impl PlayerApp {
 
 /// 打开文件对话框（支持音频文件多选和播放列表单选）
 async fn open_audio_files_dialog() -> Vec<String> {
+    let playlist_extensions = crate::playlist::format::supported_extensions();
     let files = rfd::AsyncFileDialog::new()
         .add_filter("Audio Files", &["mp3", "flac", "wav", "ogg", "aac", "m4a", "m4s"])
-        .add_filter("Playlist Files", &["m3u", "m3u8"])
+        .add_filter("Playlist Files", playlist_extensions.as_slice())
         .add_filter("All Files", &["*"])
         .pick_files()
         .await;
@@ -1314,6 +2488,37 @@ async fn open_audio_files_dialog() -> Vec<String> {
     ).unwrap_or_default()
 }
 
+/// 打开导出播放列表的保存对话框，每种可写入的格式一个过滤器
+async fn save_playlist_dialog(file_name: String) -> Option<String> {
+    let dialog = crate::playlist::format::FORMATS
+        .iter()
+        .filter(|format| format.is_writable())
+        .fold(rfd::AsyncFileDialog::new().set_file_name(file_name), |dialog, format| {
+            dialog.add_filter(format.name(), format.extensions())
+        });
+
+    dialog.save_file().await.map(|f| f.path().to_string_lossy().to_string())
+}
+
+/// 打开导出播放历史的保存对话框
+async fn save_history_dialog() -> Option<String> {
+    rfd::AsyncFileDialog::new()
+        .set_file_name("history.csv")
+        .add_filter("CSV", &["csv"])
+        .add_filter("JSON", &["json"])
+        .save_file()
+        .await
+        .map(|f| f.path().to_string_lossy().to_string())
+}
+
+/// 打开选择音乐库文件夹的对话框
+async fn pick_folder_dialog() -> Option<String> {
+    rfd::AsyncFileDialog::new()
+        .pick_folder()
+        .await
+        .map(|folder| folder.path().to_string_lossy().to_string())
+}
+
 /// 打开仅限音频文件的多选对话框
 async fn open_audio_only_files_dialog() -> Vec<String> {
     let files = rfd::AsyncFileDialog::new()
@@ -1329,4 +2534,28 @@ async fn open_audio_only_files_dialog() -> Vec<String> {
     ).unwrap_or_default()
 }
 
- 
\ No newline at end of file
+ 
+
+/// 远程控制命令订阅
+#[cfg(unix)]
+fn ipc_subscription() -> Subscription<Message> {
+    Subscription::run(crate::ipc::listen).map(Message::Ipc)
+}
+
+/// 远程控制命令订阅（仅支持Unix）
+#[cfg(not(unix))]
+fn ipc_subscription() -> Subscription<Message> {
+    Subscription::none()
+}
+
+/// MPRIS总线服务订阅
+#[cfg(target_os = "linux")]
+fn mpris_subscription() -> Subscription<Message> {
+    Subscription::run(crate::mpris::listen).map(Message::Mpris)
+}
+
+/// MPRIS总线服务订阅（仅支持Linux）
+#[cfg(not(target_os = "linux"))]
+fn mpris_subscription() -> Subscription<Message> {
+    Subscription::none()
+}
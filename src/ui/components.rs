@@ -3,7 +3,7 @@
 //! 包含可重用的UI组件和通用样式。
 
 use iced::{
-    widget::{column, row, text, slider, scrollable, Space, container, tooltip, svg},
+    widget::{column, row, text, slider, vertical_slider, scrollable, Space, container, tooltip, svg, pick_list, text_input, toggler},
     Element, Length, Border, Shadow, Background, Color,
     alignment::{Horizontal, Vertical},
     border::Radius,
@@ -11,8 +11,15 @@ use iced::{
 use iced::advanced::text::Shaping;
 
 use crate::audio::{AudioInfo, PlaybackState};
+use crate::audio::equalizer::{BUILTIN_PRESETS, EQ_BAND_COUNT, EQ_FREQUENCIES, MAX_EQ_GAIN_DB};
+use crate::bookmarks::Bookmark;
+use crate::config::{EqualizerConfig, ResampleQuality};
+use crate::history::PlayHistory;
+use crate::library::{Library, LibraryGrouping, ScanSummary};
 use crate::playlist::Playlist;
-use crate::utils::{extract_filename, format_duration};
+use crate::queue::PlayQueue;
+use crate::search::{SearchField, SearchResult, SearchSource};
+use crate::utils::{extract_filename, format_duration, format_timestamp};
 
 use super::Message;
 use super::theme::{AppTheme, AppThemeVariant, AppColors};
@@ -74,6 +81,13 @@ pub mod icons {
     pub const RANDOM_PLAY: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="m3 17 6-4-6-4v8Z" fill="currentColor"/><path d="M14 6h5v5" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"/><path d="M19 6 9 16" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/><path d="M14 18h5v-5" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"/><path d="M19 18 9 8" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/></svg>"#;
     pub const MUSIC_NOTE: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><circle cx="7" cy="17" r="3" stroke="currentColor" stroke-width="1.5"/><circle cx="17" cy="15" r="3" stroke="currentColor" stroke-width="1.5"/><path d="M10 17V5l10-2v12" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"/><path d="M10 9l10-2" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"/></svg>"#;
     pub const LIST_VIEW: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M3 6h18M3 12h18M3 18h18" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/></svg>"#;
+    pub const PLAY_NEXT: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M3 6h12M3 12h8M3 18h8" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/><path d="M15 11v8l6-4-6-4Z" fill="currentColor"/></svg>"#;
+    pub const ADD_TO_QUEUE: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M3 6h14M3 12h10M3 18h8" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/><path d="M18 13v8M14 17h8" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/></svg>"#;
+    pub const ARROW_UP: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M12 19V5M6 11l6-6 6 6" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"/></svg>"#;
+    pub const ARROW_DOWN: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M12 5v14M6 13l6 6 6-6" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"/></svg>"#;
+    pub const HISTORY: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M4 12a8 8 0 1 0 2.3-5.6M4 4v3.5h3.5" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"/><path d="M12 8v4l3 2" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"/></svg>"#;
+    pub const SEARCH: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><circle cx="11" cy="11" r="6.5" stroke="currentColor" stroke-width="1.5"/><path d="m16 16 4.5 4.5" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/></svg>"#;
+    pub const CLOSE: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M6 6l12 12M18 6 6 18" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/></svg>"#;
     pub const CD_ICON: &str = r#"<?xml version="1.0" encoding="utf-8"?>
 <!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd">
 <!-- Uploaded to: SVG Repo, www.svgrepo.com, Generator: SVG Repo Mixer Tools -->
@@ -97,6 +111,8 @@ pub mod icons {
     pub const NEXT: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><rect x="4" y="5" width="2" height="14" rx="1" fill="currentColor"/><path d="M18 12L8 5v14l10-7Z" fill="currentColor"/></svg>"#;
     pub const PLAY: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M8 5v14l11-7L8 5Z" fill="currentColor"/></svg>"#;
     pub const PAUSE: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><rect x="6" y="4" width="4" height="16" rx="2" fill="currentColor"/><rect x="14" y="4" width="4" height="16" rx="2" fill="currentColor"/></svg>"#;
+    pub const VOLUME: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M4 9.5v5h3.5L12 19V5L7.5 9.5H4Z" fill="currentColor"/><path d="M15.5 8.5a5 5 0 0 1 0 7M18 6a8.5 8.5 0 0 1 0 12" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/></svg>"#;
+    pub const VOLUME_MUTED: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M4 9.5v5h3.5L12 19V5L7.5 9.5H4Z" fill="currentColor"/><path d="m16 9.5 5 5M21 9.5l-5 5" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/></svg>"#;
     pub const HOME: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M3 12l2-2m0 0l7-7 7 7M5 10v10a1 1 0 001 1h3m0 0V11a1 1 0 011-1h2a1 1 0 011 1v10m0 0h3a1 1 0 001-1V10" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"/></svg>"#;
     pub const SETTINGS: &str = r#"<svg viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M12 15a3 3 0 100-6 3 3 0 000 6z" stroke="currentColor" stroke-width="1.5"/><path d="M19.4 15a1.65 1.65 0 00.33 1.82l.06.06a2 2 0 010 2.83 2 2 0 01-2.83 0l-.06-.06a1.65 1.65 0 00-1.82-.33 1.65 1.65 0 00-1 1.51V21a2 2 0 01-2 2 2 2 0 01-2-2v-.09A1.65 1.65 0 009 19.4a1.65 1.65 0 00-1.82.33l-.06.06a2 2 0 01-2.83 0 2 2 0 010-2.83l.06-.06a1.65 1.65 0 00.33-1.82 1.65 1.65 0 00-1.51-1H3a2 2 0 01-2-2 2 2 0 012-2h.09A1.65 1.65 0 004.6 9a1.65 1.65 0 00-.33-1.82l-.06-.06a2 2 0 010-2.83 2 2 0 012.83 0l.06.06a1.65 1.65 0 001.82.33H9a1.65 1.65 0 001-1.51V3a2 2 0 012-2 2 2 0 012 2v.09a1.65 1.65 0 001 1.51 1.65 1.65 0 001.82-.33l.06-.06a2 2 0 012.83 0 2 2 0 010 2.83l-.06.06a1.65 1.65 0 00-.33 1.82V9a1.65 1.65 0 001.51 1H21a2 2 0 012 2 2 2 0 01-2 2h-.09a1.65 1.65 0 00-1.51 1z" stroke="currentColor" stroke-width="1.5"/></svg>"#;
 }
@@ -224,7 +240,7 @@ fn icon_button(
 // ============================================================================
 
 #[derive(Debug, Clone, PartialEq, Default)]
-pub enum PageType { #[default] Home, Settings }
+pub enum PageType { #[default] Home, Library, History, Settings }
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum ViewType { #[default] Playlist, Lyrics }
@@ -232,6 +248,84 @@ pub enum ViewType { #[default] Playlist, Lyrics }
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum PlayMode { #[default] ListLoop, SingleLoop, Random }
 
+/// 输出设备选项
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum OutputDevice {
+    /// 系统默认设备
+    #[default]
+    Default,
+    /// 按名称指定的设备
+    Named(String),
+}
+
+impl OutputDevice {
+    /// 从配置中的设备名称创建
+    pub fn from_name(name: Option<String>) -> Self {
+        name.map_or(OutputDevice::Default, OutputDevice::Named)
+    }
+
+    /// 设备名称，默认设备返回None
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            OutputDevice::Default => None,
+            OutputDevice::Named(name) => Some(name),
+        }
+    }
+}
+
+/// 播放速度选项
+pub const PLAYBACK_SPEEDS: [f32; 7] = [0.5, 0.75, 1.0, 1.25, 1.5, 1.75, 2.0];
+
+/// 播放速度（用于速度选择列表）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaybackSpeed(pub f32);
+
+impl std::fmt::Display for PlaybackSpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x", self.0)
+    }
+}
+
+/// 均衡器预设选项（内置预设或用户预设的名称）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EqualizerPresetOption(pub String);
+
+impl EqualizerPresetOption {
+    /// 是否为内置预设
+    pub fn is_builtin(&self) -> bool {
+        BUILTIN_PRESETS.iter().any(|(name, _)| *name == self.0)
+    }
+}
+
+impl std::fmt::Display for EqualizerPresetOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 内置预设名称需要翻译，用户预设按原样显示
+        if self.is_builtin() {
+            write!(f, "{}", t!(self.0.as_str()))
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl std::fmt::Display for ResampleQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResampleQuality::Linear => write!(f, "{}", t!("Linear (Fast)")),
+            ResampleQuality::Sinc => write!(f, "{}", t!("Sinc (High Quality)")),
+        }
+    }
+}
+
+impl std::fmt::Display for OutputDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputDevice::Default => write!(f, "{}", t!("System Default")),
+            OutputDevice::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
 impl PlayMode {
     pub fn icon(&self) -> &'static str {
         match self {
@@ -289,6 +383,8 @@ pub fn navigation_sidebar(current_page: &PageType) -> Element<'static, Message>
     StyledContainer::new(
         column![
             nav_button(icons::HOME, t!("Home").to_string(), PageType::Home, *current_page == PageType::Home),
+            nav_button(icons::MUSIC_NOTE, t!("Browse Music Library").to_string(), PageType::Library, *current_page == PageType::Library),
+            nav_button(icons::HISTORY, t!("Listening History").to_string(), PageType::History, *current_page == PageType::History),
             nav_button(icons::SETTINGS, t!("Settings").to_string(), PageType::Settings, *current_page == PageType::Settings),
             Space::with_height(Length::Fill),
             StyledContainer::new(
@@ -307,7 +403,23 @@ pub fn navigation_sidebar(current_page: &PageType) -> Element<'static, Message>
 }
 
 /// 设置页面
-pub fn settings_page(current_theme: &AppThemeVariant, current_language: &str) -> Element<'static, Message> {
+/// 
+/// # 参数
+/// * `current_theme` - 当前主题
+/// * `current_language` - 当前语言
+/// * `output_devices` - 可选的输出设备名称
+/// * `selected_device` - 当前选择的输出设备
+/// * `device_error` - 最近一次输出设备错误
+/// * `resample_quality` - 采样率转换质量
+pub fn settings_page(
+    current_theme: &AppThemeVariant,
+    current_language: &str,
+    output_devices: &[String],
+    selected_device: &OutputDevice,
+    device_error: Option<&str>,
+    resample_quality: ResampleQuality,
+    equalizer_panel: Element<'static, Message>,
+) -> Element<'static, Message> {
     let theme_setting = row![
         StyledText::new(match current_theme {
             AppThemeVariant::Light => "Light",
@@ -335,6 +447,23 @@ pub fn settings_page(current_theme: &AppThemeVariant, current_language: &str) ->
             .build()
     ].align_y(Vertical::Center);
 
+    // 已保存的设备当前不可用时仍显示在列表中，播放时回退到默认设备
+    let mut device_options = vec![OutputDevice::Default];
+    device_options.extend(output_devices.iter().cloned().map(OutputDevice::Named));
+    if !device_options.contains(selected_device) {
+        device_options.push(selected_device.clone());
+    }
+    let device_setting = pick_list(device_options, Some(selected_device.clone()), Message::OutputDeviceSelected)
+        .text_size(constants::TEXT_NORMAL)
+        .width(Length::Fill);
+    let resample_setting = pick_list(
+        [ResampleQuality::Linear, ResampleQuality::Sinc],
+        Some(resample_quality),
+        Message::ResampleQualityChanged,
+    )
+    .text_size(constants::TEXT_NORMAL)
+    .width(Length::Fill);
+
     StyledContainer::new(
         column![
             //StyledContainer::new(
@@ -381,6 +510,50 @@ pub fn settings_page(current_theme: &AppThemeVariant, current_language: &str) ->
                 .build()
             ].spacing(constants::SPACING_SMALL),
 
+            column![
+                StyledText::new(t!("Audio")).size(constants::TEXT_LARGE)
+                    .style(super::widgets::styled_text::TextStyle::Secondary)
+                    .build(),
+                StyledContainer::new(
+                    column![
+                        row![
+                            StyledText::new(t!("Output Device")).size(constants::TEXT_MEDIUM)
+                                .width(Length::Fixed(150.0))
+                                .build(),
+                            device_setting
+                        ].align_y(Vertical::Center).spacing(constants::SPACING_MEDIUM),
+                        row![
+                            StyledText::new(t!("Resampling")).size(constants::TEXT_MEDIUM)
+                                .width(Length::Fixed(150.0))
+                                .build(),
+                            resample_setting
+                        ].align_y(Vertical::Center).spacing(constants::SPACING_MEDIUM),
+                    ]
+                    .push(device_error.map(|error| {
+                        StyledText::new(format!("{}: {}", t!("Output device error, switched to default device"), error))
+                            .size(constants::TEXT_SMALL)
+                            .style(super::widgets::styled_text::TextStyle::Hint)
+                            .build()
+                    }))
+                    .spacing(constants::SPACING_SMALL).padding(constants::PADDING_SMALL)
+                )
+                .style(super::widgets::styled_container::ContainerStyle::Card)
+                .padding(constants::PADDING_MEDIUM)
+                .width(Length::Fill)
+                .build()
+            ].spacing(constants::SPACING_SMALL),
+
+            column![
+                StyledText::new(t!("Equalizer")).size(constants::TEXT_LARGE)
+                    .style(super::widgets::styled_text::TextStyle::Secondary)
+                    .build(),
+                StyledContainer::new(equalizer_panel)
+                    .style(super::widgets::styled_container::ContainerStyle::Card)
+                    .padding(constants::PADDING_MEDIUM)
+                    .width(Length::Fill)
+                    .build()
+            ].spacing(constants::SPACING_SMALL),
+
             column![
                 StyledText::new("Advanced Settings").size(constants::TEXT_LARGE)
                     .style(super::widgets::styled_text::TextStyle::Secondary)
@@ -432,13 +605,106 @@ pub fn settings_page(current_theme: &AppThemeVariant, current_language: &str) ->
     .build()
 }
 
+/// 创建均衡器面板
+/// 
+/// # 参数
+/// * `config` - 均衡器配置
+/// * `preset_name` - 保存预设时输入的名称
+/// 
+/// # 返回
+/// 开关、预设选择、各频段增益滑块和预设管理按钮
+pub fn equalizer_panel(config: &EqualizerConfig, preset_name: &str) -> Element<'static, Message> {
+    let mut presets: Vec<EqualizerPresetOption> = BUILTIN_PRESETS
+        .iter()
+        .map(|(name, _)| EqualizerPresetOption(name.to_string()))
+        .collect();
+    presets.extend(config.user_presets.iter().map(|preset| EqualizerPresetOption(preset.name.clone())));
+    let selected = config.preset.clone().map(EqualizerPresetOption);
+    let is_user_preset = selected.as_ref().is_some_and(|preset| !preset.is_builtin());
+
+    let header = row![
+        toggler(config.enabled)
+            .label(t!("Equalizer").to_string())
+            .text_size(constants::TEXT_MEDIUM)
+            .on_toggle(Message::EqualizerToggled),
+        Space::with_width(Length::Fill),
+        pick_list(presets, selected, Message::EqualizerPresetSelected)
+            .placeholder(t!("Custom").to_string())
+            .text_size(constants::TEXT_NORMAL)
+            .width(Length::Fixed(200.0)),
+    ].align_y(Vertical::Center).spacing(constants::SPACING_MEDIUM);
+
+    let mut bands = row![].spacing(constants::SPACING_SMALL);
+    for (band, frequency) in EQ_FREQUENCIES.iter().enumerate().take(EQ_BAND_COUNT) {
+        let gain = config.gains.get(band).copied().unwrap_or(0.0);
+        let label = if *frequency >= 1000.0 {
+            format!("{}k", frequency / 1000.0)
+        } else {
+            format!("{}", frequency)
+        };
+        bands = bands.push(
+            column![
+                StyledText::new(format!("{:+.1}", gain)).size(constants::TEXT_SMALL)
+                    .style(super::widgets::styled_text::TextStyle::Hint)
+                    .build(),
+                vertical_slider(-MAX_EQ_GAIN_DB..=MAX_EQ_GAIN_DB, gain, move |gain| Message::EqualizerBandChanged(band, gain))
+                    .on_release(Message::EqualizerBandReleased)
+                    .step(0.5)
+                    .height(Length::Fixed(120.0))
+                    .style(AppTheme::progress_slider()),
+                StyledText::new(label).size(constants::TEXT_SMALL).build(),
+            ]
+            .align_x(Horizontal::Center)
+            .spacing(constants::SPACING_SMALL)
+            .width(Length::Fill)
+        );
+    }
+
+    let save_button = StyledButton::new(StyledText::new(t!("Save Preset")).size(constants::TEXT_NORMAL).build())
+        .button_type(super::widgets::styled_button::ButtonType::Default)
+        .color(super::widgets::styled_button::ButtonColor::Primary)
+        .padding(constants::PADDING_SMALL);
+    let delete_button = StyledButton::new(StyledText::new(t!("Delete Preset")).size(constants::TEXT_NORMAL).build())
+        .button_type(super::widgets::styled_button::ButtonType::Default)
+        .color(super::widgets::styled_button::ButtonColor::Danger)
+        .padding(constants::PADDING_SMALL);
+    let presets_row = row![
+        text_input(t!("Preset Name").as_ref(), preset_name)
+            .on_input(Message::EqualizerPresetNameChanged)
+            .on_submit(Message::SaveEqualizerPreset)
+            .size(constants::TEXT_NORMAL)
+            .width(Length::Fill),
+        // 内置预设不能被覆盖或删除
+        if preset_name.trim().is_empty() || EqualizerPresetOption(preset_name.trim().to_string()).is_builtin() {
+            save_button.build()
+        } else {
+            save_button.on_press(Message::SaveEqualizerPreset).build()
+        },
+        if is_user_preset {
+            delete_button.on_press(Message::DeleteEqualizerPreset).build()
+        } else {
+            delete_button.build()
+        },
+    ].align_y(Vertical::Center).spacing(constants::SPACING_MEDIUM);
+
+    column![header, bands, presets_row]
+        .spacing(constants::SPACING_MEDIUM)
+        .padding(constants::PADDING_SMALL)
+        .into()
+}
+
 /// 控制按钮组
-pub fn control_buttons_view(is_playing: bool) -> Element<'static, Message> {
+pub fn control_buttons_view(is_playing: bool, volume: f32, is_muted: bool) -> Element<'static, Message> {
     let (play_icon, play_tooltip) = if is_playing {
         (icons::PAUSE, t!("Pause").to_string())
     } else {
         (icons::PLAY, t!("Play").to_string())
     };
+    let (volume_icon, volume_tooltip) = if is_muted {
+        (icons::VOLUME_MUTED, t!("Unmute").to_string())
+    } else {
+        (icons::VOLUME, t!("Mute").to_string())
+    };
 
     row![
         IconButton::new(icons::PREVIOUS, t!("Previous Track").to_string())
@@ -459,6 +725,18 @@ pub fn control_buttons_view(is_playing: bool) -> Element<'static, Message> {
             .icon_size(constants::ICON_SIZE_SMALL)
             .style(super::widgets::styled_button::ButtonType::Default, super::widgets::styled_button::ButtonColor::Default)
             .build(),
+        IconButton::new(volume_icon, volume_tooltip)
+            .on_press(Message::ToggleMute)
+            .size(constants::BUTTON_SIZE_SMALL)
+            .icon_size(constants::ICON_SIZE_SMALL)
+            .style(super::widgets::styled_button::ButtonType::Default, super::widgets::styled_button::ButtonColor::Default)
+            .build(),
+        // 静音时滑块仍显示原音量，取消静音后恢复
+        slider(0.0..=1.0, volume, Message::VolumeChanged)
+            .on_release(Message::VolumeReleased)
+            .step(0.01)
+            .style(AppTheme::progress_slider())
+            .width(Length::Fixed(100.0)),
     ]
     .spacing(constants::SPACING_SMALL)
     .align_y(Vertical::Center)
@@ -470,6 +748,13 @@ pub fn compact_play_mode_button(current_mode: PlayMode) -> Element<'static, Mess
     icon_button(current_mode.icon(), current_mode.name(), Message::TogglePlayMode, constants::BUTTON_SIZE_SMALL, constants::ICON_SIZE_SMALL, AppTheme::file_button)
 }
 
+pub fn compact_speed_picker(speed: f32) -> Element<'static, Message> {
+    pick_list(PLAYBACK_SPEEDS.map(PlaybackSpeed), Some(PlaybackSpeed(speed)), |speed| Message::PlaybackSpeedChanged(speed.0))
+        .text_size(constants::TEXT_NORMAL)
+        .width(Length::Fixed(72.0))
+        .into()
+}
+
 pub fn compact_file_button() -> Element<'static, Message> {
     icon_button(icons::FILE_FOLDER, t!("Open Files").to_string(), Message::OpenFile, constants::BUTTON_SIZE_SMALL, constants::ICON_SIZE_SMALL, AppTheme::file_button)
 }
@@ -532,6 +817,97 @@ pub fn simple_time_view(playback_state: &PlaybackState) -> Element<'static, Mess
     .into()
 }
 
+/// 练习面板：A-B循环和书签
+/// 
+/// # 参数
+/// * `loop_start` - A点（秒）
+/// * `loop_end` - B点（秒）
+/// * `bookmarks` - 当前文件的书签
+/// * `bookmark_name` - 添加书签时输入的名称
+/// 
+/// # 返回
+/// A-B循环控制、添加书签输入框和可跳转的书签列表
+pub fn practice_panel(
+    loop_start: Option<f64>,
+    loop_end: Option<f64>,
+    bookmarks: &[Bookmark],
+    bookmark_name: &str,
+) -> Element<'static, Message> {
+    let small_button = |label: String, message: Option<Message>, color: super::widgets::styled_button::ButtonColor| {
+        let button = StyledButton::new(StyledText::new(label).size(constants::TEXT_SMALL).build())
+            .button_type(super::widgets::styled_button::ButtonType::Default)
+            .color(color)
+            .padding(constants::PADDING_SMALL);
+        match message {
+            Some(message) => button.on_press(message).build(),
+            None => button.build(),
+        }
+    };
+    let default_color = super::widgets::styled_button::ButtonColor::Default;
+
+    let loop_status = match (loop_start, loop_end) {
+        (Some(start), Some(end)) => format!("A {} → B {}", format_duration(start), format_duration(end)),
+        (Some(start), None) => format!("A {} → B --:--", format_duration(start)),
+        _ => String::new(),
+    };
+    let loop_row = row![
+        StyledText::new(t!("A-B Repeat")).size(constants::TEXT_NORMAL)
+            .style(super::widgets::styled_text::TextStyle::Secondary)
+            .build(),
+        small_button(t!("Set A").to_string(), Some(Message::SetLoopStart), default_color),
+        // 先设置A点才能设置B点
+        small_button(t!("Set B").to_string(), loop_start.map(|_| Message::SetLoopEnd), default_color),
+        small_button(t!("Clear Loop").to_string(), loop_start.map(|_| Message::ClearLoop), default_color),
+        StyledText::new(loop_status).size(constants::TEXT_SMALL)
+            .style(super::widgets::styled_text::TextStyle::Hint)
+            .build(),
+    ].align_y(Vertical::Center).spacing(constants::SPACING_SMALL);
+
+    let add_row = row![
+        StyledText::new(t!("Bookmarks")).size(constants::TEXT_NORMAL)
+            .style(super::widgets::styled_text::TextStyle::Secondary)
+            .build(),
+        text_input(t!("Bookmark Name").as_ref(), bookmark_name)
+            .on_input(Message::BookmarkNameChanged)
+            .on_submit(Message::AddBookmark)
+            .size(constants::TEXT_SMALL)
+            .width(Length::Fill),
+        small_button(t!("Add Bookmark").to_string(), Some(Message::AddBookmark), super::widgets::styled_button::ButtonColor::Primary),
+    ].align_y(Vertical::Center).spacing(constants::SPACING_SMALL);
+
+    let list: Element<'static, Message> = if bookmarks.is_empty() {
+        StyledText::new(t!("No bookmarks")).size(constants::TEXT_SMALL)
+            .style(super::widgets::styled_text::TextStyle::Hint)
+            .build()
+    } else {
+        let items = bookmarks.iter().enumerate().fold(column![].spacing(2), |list, (index, bookmark)| {
+            list.push(
+                row![
+                    StyledButton::new(
+                        row![
+                            StyledText::new(format_duration(bookmark.position)).size(constants::TEXT_SMALL)
+                                .style(super::widgets::styled_text::TextStyle::Hint)
+                                .build(),
+                            StyledText::new(bookmark.name.clone()).size(constants::TEXT_SMALL).build(),
+                        ].spacing(constants::SPACING_SMALL)
+                    )
+                    .button_type(super::widgets::styled_button::ButtonType::Text)
+                    .on_press(Message::JumpToBookmark(bookmark.position))
+                    .width(Length::Fill)
+                    .padding(4)
+                    .build(),
+                    small_button(t!("Delete").to_string(), Some(Message::DeleteBookmark(index)), super::widgets::styled_button::ButtonColor::Danger),
+                ].align_y(Vertical::Center).spacing(constants::SPACING_SMALL)
+            )
+        });
+        scrollable(items).height(Length::Fixed(96.0)).into()
+    };
+
+    column![loop_row, add_row, list]
+        .spacing(constants::SPACING_SMALL)
+        .into()
+}
+
 /// 播放列表视图
 pub fn playlist_view(playlist: &Playlist, playlist_loaded: bool, is_playing: bool, playlist_manager: &crate::playlist::PlaylistManager) -> Element<'static, Message> {
     if !playlist_loaded {
@@ -566,6 +942,11 @@ pub fn playlist_view(playlist: &Playlist, playlist_loaded: bool, is_playing: boo
         let display_name = extra
             .and_then(|e| e.name.clone())
             .unwrap_or_else(|| extract_filename(file_path));
+        // 整轨镜像注明文件中包含的曲目数
+        let display_name = match extra.and_then(|e| e.track_count) {
+            Some(count) => t!("messages.CueTracks", title = display_name, count = format!("{}", count)).to_string(),
+            None => display_name,
+        };
         let duration = extra
             .and_then(|e| e.duration)
             .or_else(|| {
@@ -575,7 +956,10 @@ pub fn playlist_view(playlist: &Playlist, playlist_loaded: bool, is_playing: boo
         (index, display_name, duration, file_path.clone())
     }).collect();
 
-    let items: Vec<Element<Message>> = display_items.into_iter().map(|(index, display_name, duration, _file_path)| {
+    let last = file_paths.len().saturating_sub(1);
+    // 智能播放列表的曲目由规则生成，CUE等格式只读，不能手动调整
+    let editable = !playlist.is_read_only();
+    let items: Vec<Element<Message>> = display_items.into_iter().map(|(index, display_name, duration, file_path)| {
         let is_current = playlist.current_index() == Some(index);
         let is_playing_current = is_current && is_playing;
         
@@ -611,12 +995,35 @@ pub fn playlist_view(playlist: &Playlist, playlist_loaded: bool, is_playing: boo
         .width(Length::Fill)
         .build();
         
-        StyledButton::new(content)
+        let item_button = StyledButton::new(content)
             .on_press(Message::PlaylistItemSelected(index))
             .width(Length::Fill)
             .button_type(if is_playing_current { super::widgets::styled_button::ButtonType::Default } else if is_current { super::widgets::styled_button::ButtonType::Dashed } else { super::widgets::styled_button::ButtonType::Text })
             .color(if is_playing_current { super::widgets::styled_button::ButtonColor::Primary } else if is_current { super::widgets::styled_button::ButtonColor::Primary } else { super::widgets::styled_button::ButtonColor::Default })
-            .build()
+            .build();
+
+        let move_up = IconButton::new(icons::ARROW_UP, t!("Move Up").to_string())
+            .size(28.0)
+            .icon_size(16.0)
+            .style(super::widgets::styled_button::ButtonType::Text, super::widgets::styled_button::ButtonColor::Default);
+        let move_down = IconButton::new(icons::ARROW_DOWN, t!("Move Down").to_string())
+            .size(28.0)
+            .icon_size(16.0)
+            .style(super::widgets::styled_button::ButtonType::Text, super::widgets::styled_button::ButtonColor::Default);
+        let remove = IconButton::new(icons::CLOSE, t!("Remove from Playlist").to_string())
+            .size(28.0)
+            .icon_size(16.0)
+            .style(super::widgets::styled_button::ButtonType::Text, super::widgets::styled_button::ButtonColor::Default);
+
+        row![
+            item_button,
+            queue_action_button(icons::PLAY_NEXT, t!("Play Next").to_string(), Message::QueuePlayNext(file_path.clone())),
+            queue_action_button(icons::ADD_TO_QUEUE, t!("Add to Queue").to_string(), Message::QueueAdd(file_path)),
+            if editable && index > 0 { move_up.on_press(Message::PlaylistItemMove(index, true)).build() } else { move_up.build() },
+            if editable && index < last { move_down.on_press(Message::PlaylistItemMove(index, false)).build() } else { move_down.build() },
+            // 正在播放的曲目不能移除
+            if !editable || is_current { remove.build() } else { remove.on_press(Message::PlaylistItemRemove(index)).build() },
+        ].spacing(2).align_y(Vertical::Center).into()
     }).collect();
     
     StyledContainer::new(
@@ -642,6 +1049,424 @@ pub fn playlist_view(playlist: &Playlist, playlist_loaded: bool, is_playing: boo
     .build()
 }
 
+/// 播放队列中曲目的小图标按钮
+fn queue_action_button(icon: &'static str, tooltip_text: String, message: Message) -> Element<'static, Message> {
+    IconButton::new(icon, tooltip_text)
+        .on_press(message)
+        .size(28.0)
+        .icon_size(16.0)
+        .style(super::widgets::styled_button::ButtonType::Text, super::widgets::styled_button::ButtonColor::Default)
+        .build()
+}
+
+/// 播放队列视图（接下来播放的曲目，可调整顺序和移除）
+pub fn queue_view(queue: &PlayQueue, playlist_manager: &crate::playlist::PlaylistManager) -> Element<'static, Message> {
+    let last = queue.len().saturating_sub(1);
+    let items = queue.items().iter().enumerate().fold(column![].spacing(2), |list, (index, file_path)| {
+        let duration = playlist_manager.get_cached_audio_duration(file_path);
+        let move_up = IconButton::new(icons::ARROW_UP, t!("Move Up").to_string())
+            .size(28.0)
+            .icon_size(16.0)
+            .style(super::widgets::styled_button::ButtonType::Text, super::widgets::styled_button::ButtonColor::Default);
+        let move_down = IconButton::new(icons::ARROW_DOWN, t!("Move Down").to_string())
+            .size(28.0)
+            .icon_size(16.0)
+            .style(super::widgets::styled_button::ButtonType::Text, super::widgets::styled_button::ButtonColor::Default);
+        list.push(
+            row![
+                StyledText::new(format!("{}.", index + 1)).size(constants::TEXT_SMALL)
+                    .style(super::widgets::styled_text::TextStyle::Hint)
+                    .build(),
+                StyledContainer::new(
+                    truncated_text(extract_filename(file_path), constants::TEXT_TRUNCATE_DEFAULT, constants::TEXT_NORMAL, Color { r: 0.4, g: 0.4, b: 0.4, a: 1.0 })
+                ).style(super::widgets::styled_container::ContainerStyle::Transparent).width(Length::Fill).build(),
+                StyledText::new(duration.map_or("--:--".to_string(), format_duration)).size(constants::TEXT_SMALL)
+                    .style(super::widgets::styled_text::TextStyle::WithAlpha(0.7))
+                    .build(),
+                if index > 0 { move_up.on_press(Message::QueueMove(index, true)).build() } else { move_up.build() },
+                if index < last { move_down.on_press(Message::QueueMove(index, false)).build() } else { move_down.build() },
+                queue_action_button(icons::CLOSE, t!("Remove from Queue").to_string(), Message::QueueRemove(index)),
+            ].spacing(constants::SPACING_SMALL).align_y(Vertical::Center)
+        )
+    });
+
+    column![
+        row![
+            StyledText::new(t!("messages.UpNext", count = format!("{}", queue.len())))
+                .size(constants::TEXT_MEDIUM)
+                .style(super::widgets::styled_text::TextStyle::Primary)
+                .width(Length::Fill)
+                .build(),
+            StyledButton::new(StyledText::new(t!("Clear Queue")).size(constants::TEXT_SMALL).build())
+                .button_type(super::widgets::styled_button::ButtonType::Default)
+                .padding(constants::PADDING_SMALL)
+                .on_press(Message::QueueClear)
+                .build(),
+        ].align_y(Vertical::Center),
+        // 队列较长时限制高度，避免挤占播放列表
+        container(scrollable(items.padding([0, constants::PADDING_SMALL]))).max_height(160.0),
+    ]
+    .spacing(constants::SPACING_SMALL)
+    .padding(constants::PADDING_SMALL)
+    .into()
+}
+
+/// 音乐库页面
+///
+/// # 参数
+/// * `library` - 音乐库
+/// * `folders` - 扫描的音乐文件夹
+/// * `grouping` - 当前浏览方式
+/// * `selected_group` - 当前选中的分组名称
+/// * `scanning` - 是否正在扫描
+/// * `last_scan` - 最近一次扫描的结果
+/// * `current_file` - 正在播放的文件路径
+pub fn library_page(
+    library: &Library,
+    folders: &[String],
+    grouping: LibraryGrouping,
+    selected_group: Option<&str>,
+    scanning: bool,
+    last_scan: Option<ScanSummary>,
+    current_file: &str,
+) -> Element<'static, Message> {
+    let scan_status = if scanning {
+        t!("Scanning...").to_string()
+    } else if let Some(summary) = last_scan {
+        t!("messages.LibraryScanResult",
+            added = format!("{}", summary.added),
+            updated = format!("{}", summary.updated),
+            removed = format!("{}", summary.removed)).to_string()
+    } else {
+        t!("messages.LibrarySongs", count = format!("{}", library.len())).to_string()
+    };
+
+    let rescan_button = StyledButton::new(StyledText::new(t!("Rescan")).size(constants::TEXT_NORMAL).build())
+        .button_type(super::widgets::styled_button::ButtonType::Default)
+        .color(super::widgets::styled_button::ButtonColor::Primary)
+        .padding(constants::PADDING_SMALL);
+    let header = row![
+        StyledText::new(t!("Music Library")).size(constants::TEXT_TITLE + 4)
+            .style(super::widgets::styled_text::TextStyle::Emphasis)
+            .build(),
+        StyledText::new(scan_status).size(constants::TEXT_NORMAL)
+            .style(super::widgets::styled_text::TextStyle::Hint)
+            .width(Length::Fill)
+            .build(),
+        StyledButton::new(StyledText::new(t!("Add Folder")).size(constants::TEXT_NORMAL).build())
+            .on_press(Message::LibraryAddFolder)
+            .button_type(super::widgets::styled_button::ButtonType::Default)
+            .padding(constants::PADDING_SMALL)
+            .build(),
+        // 扫描过程中不能重复扫描
+        if scanning || folders.is_empty() { rescan_button.build() } else { rescan_button.on_press(Message::LibraryRescan).build() },
+    ].spacing(constants::SPACING_MEDIUM).align_y(Vertical::Center);
+
+    let folder_list = folders.iter().enumerate().fold(column![].spacing(2), |list, (index, folder)| {
+        list.push(
+            row![
+                svg_icon(icons::FILE_FOLDER, 16.0, constants::ICON_COLOR),
+                StyledContainer::new(
+                    truncated_text(folder.clone(), constants::TEXT_TRUNCATE_LONG * 2, constants::TEXT_NORMAL, Color { r: 0.4, g: 0.4, b: 0.4, a: 1.0 })
+                ).style(super::widgets::styled_container::ContainerStyle::Transparent).width(Length::Fill).build(),
+                queue_action_button(icons::CLOSE, t!("Remove Folder").to_string(), Message::LibraryRemoveFolder(index)),
+            ].spacing(constants::SPACING_SMALL).align_y(Vertical::Center)
+        )
+    });
+    let folder_section: Element<Message> = if folders.is_empty() {
+        StyledText::new(t!("Add a music folder to build your library")).size(constants::TEXT_NORMAL)
+            .style(super::widgets::styled_text::TextStyle::Hint)
+            .build()
+    } else {
+        container(scrollable(folder_list)).max_height(96.0).into()
+    };
+
+    let grouping_tabs = row(LibraryGrouping::ALL.iter().map(|&option| {
+        let label = match option {
+            LibraryGrouping::Artist => t!("Artist"),
+            LibraryGrouping::Album => t!("Album"),
+            LibraryGrouping::Genre => t!("Genre"),
+        };
+        StyledButton::new(StyledText::new(label).size(constants::TEXT_NORMAL).build())
+            .on_press(Message::LibraryGroupingChanged(option))
+            .button_type(if option == grouping { super::widgets::styled_button::ButtonType::Primary } else { super::widgets::styled_button::ButtonType::Text })
+            .color(super::widgets::styled_button::ButtonColor::Primary)
+            .padding(constants::PADDING_SMALL)
+            .build()
+    })).spacing(constants::SPACING_SMALL);
+
+    let groups = library.groups(grouping).into_iter().fold(column![].spacing(2), |list, (name, count)| {
+        let is_selected = selected_group.is_some_and(|selected| selected.to_lowercase() == name.to_lowercase());
+        let label = if name.is_empty() { t!("Unknown").to_string() } else { name.clone() };
+        list.push(
+            StyledButton::new(
+                row![
+                    StyledContainer::new(
+                        truncated_text(label, constants::TEXT_TRUNCATE_DEFAULT, constants::TEXT_MEDIUM, Color { r: 0.4, g: 0.4, b: 0.4, a: 1.0 })
+                    ).style(super::widgets::styled_container::ContainerStyle::Transparent).width(Length::Fill).build(),
+                    StyledText::new(format!("{}", count)).size(constants::TEXT_SMALL)
+                        .style(super::widgets::styled_text::TextStyle::WithAlpha(0.7))
+                        .build(),
+                ].spacing(constants::SPACING_SMALL).align_y(Vertical::Center)
+            )
+            .on_press(Message::LibraryGroupSelected(name))
+            .width(Length::Fill)
+            .button_type(if is_selected { super::widgets::styled_button::ButtonType::Dashed } else { super::widgets::styled_button::ButtonType::Text })
+            .color(if is_selected { super::widgets::styled_button::ButtonColor::Primary } else { super::widgets::styled_button::ButtonColor::Default })
+            .padding(constants::PADDING_SMALL)
+            .build()
+        )
+    });
+
+    let tracks = selected_group
+        .map(|name| library.group_tracks(grouping, name))
+        .unwrap_or_default();
+    let track_list = tracks.into_iter().enumerate().fold(column![].spacing(2), |list, (index, track)| {
+        let is_current = track.path == current_file;
+        let subtitle = [track.display_artist(), track.album.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ");
+        list.push(
+            row![
+                StyledButton::new(
+                    row![
+                        column![
+                            truncated_text(track.display_title(), constants::TEXT_TRUNCATE_LONG, constants::TEXT_MEDIUM,
+                                if is_current { Color { r: 0.0, g: 0.6, b: 1.0, a: 1.0 } } else { Color { r: 0.4, g: 0.4, b: 0.4, a: 1.0 } }),
+                            StyledText::new(subtitle).size(constants::TEXT_SMALL)
+                                .style(super::widgets::styled_text::TextStyle::Hint)
+                                .build(),
+                        ].width(Length::Fill),
+                        StyledText::new(track.duration.map_or("--:--".to_string(), format_duration))
+                            .size(constants::TEXT_NORMAL)
+                            .style(super::widgets::styled_text::TextStyle::WithAlpha(0.7))
+                            .build(),
+                    ].spacing(constants::SPACING_MEDIUM).align_y(Vertical::Center)
+                )
+                .on_press(Message::LibraryTrackSelected(index))
+                .width(Length::Fill)
+                .button_type(if is_current { super::widgets::styled_button::ButtonType::Dashed } else { super::widgets::styled_button::ButtonType::Text })
+                .color(if is_current { super::widgets::styled_button::ButtonColor::Primary } else { super::widgets::styled_button::ButtonColor::Default })
+                .padding(constants::PADDING_SMALL)
+                .build(),
+                queue_action_button(icons::PLAY_NEXT, t!("Play Next").to_string(), Message::QueuePlayNext(track.path.clone())),
+                queue_action_button(icons::ADD_TO_QUEUE, t!("Add to Queue").to_string(), Message::QueueAdd(track.path.clone())),
+            ].spacing(2).align_y(Vertical::Center)
+        )
+    });
+
+    StyledContainer::new(
+        column![
+            header,
+            folder_section,
+            grouping_tabs,
+            row![
+                StyledContainer::new(scrollable(groups.padding([0, constants::PADDING_SMALL])).height(Length::Fill))
+                    .style(super::widgets::styled_container::ContainerStyle::Card)
+                    .padding(constants::PADDING_SMALL)
+                    .width(Length::Fixed(260.0))
+                    .height(Length::Fill)
+                    .build(),
+                StyledContainer::new(scrollable(track_list.padding([0, constants::PADDING_SMALL])).height(Length::Fill))
+                    .style(super::widgets::styled_container::ContainerStyle::Card)
+                    .padding(constants::PADDING_SMALL)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .build(),
+            ].spacing(constants::SPACING_MEDIUM).height(Length::Fill),
+        ].spacing(constants::SPACING_MEDIUM)
+    )
+    .style(super::widgets::styled_container::ContainerStyle::Transparent)
+    .padding(constants::PADDING_SMALL)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .build()
+}
+
+/// 播放历史页面显示的最近播放记录数量
+const HISTORY_PAGE_RECORDS: usize = 200;
+
+/// 播放历史页面
+///
+/// # 参数
+/// * `history` - 播放历史
+/// * `current_file` - 正在播放的文件路径
+pub fn history_page(history: &PlayHistory, current_file: &str) -> Element<'static, Message> {
+    let export_button = StyledButton::new(StyledText::new(t!("Export")).size(constants::TEXT_NORMAL).build())
+        .button_type(super::widgets::styled_button::ButtonType::Default)
+        .color(super::widgets::styled_button::ButtonColor::Primary)
+        .padding(constants::PADDING_SMALL);
+    let header = row![
+        StyledText::new(t!("Listening History")).size(constants::TEXT_TITLE + 4)
+            .style(super::widgets::styled_text::TextStyle::Emphasis)
+            .build(),
+        StyledText::new(t!("messages.HistoryRecords", count = format!("{}", history.len()))).size(constants::TEXT_NORMAL)
+            .style(super::widgets::styled_text::TextStyle::Hint)
+            .width(Length::Fill)
+            .build(),
+        if history.is_empty() { export_button.build() } else { export_button.on_press(Message::HistoryExport).build() },
+    ].spacing(constants::SPACING_MEDIUM).align_y(Vertical::Center);
+
+    let records: Element<Message> = if history.is_empty() {
+        StyledText::new(t!("No plays yet")).size(constants::TEXT_MEDIUM)
+            .style(super::widgets::styled_text::TextStyle::Hint)
+            .build()
+    } else {
+        let list = history.recent().take(HISTORY_PAGE_RECORDS).fold(column![].spacing(2), |list, record| {
+            let is_current = record.path == current_file;
+            let title = record.title.clone().unwrap_or_else(|| extract_filename(&record.path));
+            let subtitle = [record.artist.clone(), Some(format_timestamp(record.started))]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" · ");
+            let listened = format!("{} / {}", format_duration(record.listened), record.duration.map_or("--:--".to_string(), format_duration));
+            let (status, status_style) = if record.completed {
+                (t!("Completed"), super::widgets::styled_text::TextStyle::WithAlpha(0.7))
+            } else {
+                (t!("Skipped"), super::widgets::styled_text::TextStyle::Hint)
+            };
+            list.push(
+                row![
+                    column![
+                        truncated_text(title, constants::TEXT_TRUNCATE_LONG, constants::TEXT_MEDIUM,
+                            if is_current { Color { r: 0.0, g: 0.6, b: 1.0, a: 1.0 } } else { Color { r: 0.4, g: 0.4, b: 0.4, a: 1.0 } }),
+                        StyledText::new(subtitle).size(constants::TEXT_SMALL)
+                            .style(super::widgets::styled_text::TextStyle::Hint)
+                            .build(),
+                    ].width(Length::Fill),
+                    StyledText::new(t!("messages.PlayCount", count = format!("{}", history.stats(&record.path).play_count)))
+                        .size(constants::TEXT_SMALL)
+                        .style(super::widgets::styled_text::TextStyle::Hint)
+                        .build(),
+                    StyledText::new(status).size(constants::TEXT_NORMAL)
+                        .style(status_style)
+                        .width(Length::Fixed(80.0))
+                        .align(Horizontal::Right)
+                        .build(),
+                    StyledText::new(listened).size(constants::TEXT_NORMAL)
+                        .style(super::widgets::styled_text::TextStyle::WithAlpha(0.7))
+                        .width(Length::Fixed(110.0))
+                        .align(Horizontal::Right)
+                        .build(),
+                    queue_action_button(icons::PLAY_NEXT, t!("Play Next").to_string(), Message::QueuePlayNext(record.path.clone())),
+                    queue_action_button(icons::ADD_TO_QUEUE, t!("Add to Queue").to_string(), Message::QueueAdd(record.path.clone())),
+                ].spacing(constants::SPACING_MEDIUM).align_y(Vertical::Center).padding(constants::PADDING_SMALL)
+            )
+        });
+        scrollable(list.padding([0, constants::PADDING_SMALL])).height(Length::Fill).into()
+    };
+
+    StyledContainer::new(
+        column![
+            header,
+            StyledContainer::new(records)
+                .style(super::widgets::styled_container::ContainerStyle::Card)
+                .padding(constants::PADDING_SMALL)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .build(),
+        ].spacing(constants::SPACING_MEDIUM)
+    )
+    .style(super::widgets::styled_container::ContainerStyle::Transparent)
+    .padding(constants::PADDING_SMALL)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .build()
+}
+
+/// 首页搜索框
+pub fn search_bar(query: &str, search_lyrics: bool, lyrics_loading: bool) -> Element<'static, Message> {
+    let mut bar = row![
+        svg_icon(icons::SEARCH, 18.0, constants::ICON_COLOR),
+        text_input(t!("Search songs, artists, albums").as_ref(), query)
+            .on_input(Message::SearchQueryChanged)
+            .size(constants::TEXT_NORMAL)
+            .padding(constants::PADDING_SMALL)
+            .width(Length::Fill),
+        toggler(search_lyrics)
+            .label(t!("Lyrics").to_string())
+            .text_size(constants::TEXT_NORMAL)
+            .on_toggle(Message::SearchLyricsToggled),
+    ].spacing(constants::SPACING_MEDIUM).align_y(Vertical::Center);
+
+    if !query.is_empty() {
+        bar = bar.push(queue_action_button(icons::CLOSE, t!("Clear").to_string(), Message::SearchQueryChanged(String::new())));
+    }
+    if lyrics_loading {
+        bar = bar.push(
+            StyledText::new(t!("Loading lyrics...")).size(constants::TEXT_SMALL)
+                .style(super::widgets::styled_text::TextStyle::Hint)
+                .build()
+        );
+    }
+    bar.into()
+}
+
+/// 搜索结果列表（替代首页的播放列表网格）
+pub fn search_results_view(results: &[SearchResult], current_file: &str) -> Element<'static, Message> {
+    if results.is_empty() {
+        return StyledContainer::new(
+            StyledText::new(t!("No results")).size(constants::TEXT_MEDIUM)
+                .style(super::widgets::styled_text::TextStyle::Hint)
+                .build()
+        )
+        .style(super::widgets::styled_container::ContainerStyle::Transparent)
+        .padding(constants::PADDING_MEDIUM)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(Horizontal::Center)
+        .build()
+        .into();
+    }
+
+    let list = results.iter().enumerate().fold(column![].spacing(2), |list, (index, result)| {
+        let is_current = result.path == current_file;
+        let source = match &result.source {
+            SearchSource::Library => t!("Music Library").to_string(),
+            SearchSource::Playlist { name, .. } => name.clone(),
+        };
+        let subtitle = [result.artist.clone(), Some(source)]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ");
+        let mut details = column![
+            truncated_text(result.title.clone(), constants::TEXT_TRUNCATE_LONG, constants::TEXT_MEDIUM,
+                if is_current { Color { r: 0.0, g: 0.6, b: 1.0, a: 1.0 } } else { Color { r: 0.4, g: 0.4, b: 0.4, a: 1.0 } }),
+            StyledText::new(subtitle).size(constants::TEXT_SMALL)
+                .style(super::widgets::styled_text::TextStyle::Hint)
+                .build(),
+        ].width(Length::Fill);
+        // 按歌词匹配时显示匹配到的歌词
+        if let (SearchField::Lyrics, Some(line)) = (result.field, &result.lyric_line) {
+            details = details.push(
+                truncated_text(format!("♪ {}", line), constants::TEXT_TRUNCATE_LONG, constants::TEXT_SMALL, Color { r: 0.0, g: 0.6, b: 1.0, a: 0.8 })
+            );
+        }
+        list.push(
+            row![
+                StyledButton::new(details)
+                    .on_press(Message::SearchResultSelected(index))
+                    .width(Length::Fill)
+                    .button_type(if is_current { super::widgets::styled_button::ButtonType::Dashed } else { super::widgets::styled_button::ButtonType::Text })
+                    .color(if is_current { super::widgets::styled_button::ButtonColor::Primary } else { super::widgets::styled_button::ButtonColor::Default })
+                    .padding(constants::PADDING_SMALL)
+                    .build(),
+                queue_action_button(icons::PLAY_NEXT, t!("Play Next").to_string(), Message::QueuePlayNext(result.path.clone())),
+                queue_action_button(icons::ADD_TO_QUEUE, t!("Add to Queue").to_string(), Message::QueueAdd(result.path.clone())),
+            ].spacing(2).align_y(Vertical::Center)
+        )
+    });
+
+    scrollable(list.padding([0, constants::PADDING_SMALL]))
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+}
+
 /// 歌词视图
 pub fn lyrics_view(file_path: &str, is_playing: bool, current_time: f64, lyrics: Option<crate::lyrics::Lyrics>, window_height: f32) -> Element<'static, Message> {
     if file_path.is_empty() {
@@ -951,6 +1776,8 @@ pub fn playlist_files_grid_view(
             .show_menu(menu_playlist_path == Some(playlist_info.path.as_str()))
             .renaming(renaming_playlist_path == Some(playlist_info.path.as_str()))
             .renaming_name(renaming_playlist_name)
+            .smart(playlist_info.is_smart)
+            .read_only(playlist_info.is_read_only)
             .width(170.0)
             .height(240.0)
             .build();
@@ -1018,6 +1845,8 @@ struct PlaylistFileInfo {
     path: String,
     name: String,
     song_count: usize,
+    is_smart: bool,
+    is_read_only: bool,
 }
 
 /// 从PlaylistManager获取播放列表文件信息（只包含持久播放列表，不包含临时播放列表）
@@ -1036,6 +1865,8 @@ fn get_playlist_files_info_from_manager(playlist_manager: &crate::playlist::Play
                 path: playlist_path.to_string(),
                 name,
                 song_count: playlist.len(),
+                is_smart: playlist.is_smart(),
+                is_read_only: playlist.is_read_only(),
             });
         }
     }
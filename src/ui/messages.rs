@@ -2,11 +2,18 @@
 //! 
 //! 定义应用程序中使用的所有UI消息类型。
 
+use std::collections::HashMap;
+
 use iced::event::Event;
 use tokio::sync::mpsc;
 
-use crate::audio::{PlaybackCommand, PlaybackState};
-use super::components::PageType;
+use crate::audio::{PlaybackCommand, PlaybackEvent, PlaybackState};
+use crate::config::ResampleQuality;
+use crate::ipc::IpcCall;
+use crate::library::{Library, LibraryGrouping, ScanSummary};
+use crate::watcher::WatchEvent;
+use crate::mpris::MprisEvent;
+use super::components::{EqualizerPresetOption, OutputDevice, PageType};
 
 /// 应用程序消息类型
 #[derive(Debug, Clone)]
@@ -19,6 +26,10 @@ pub enum Message {
     MultipleAudioFilesSelected(Vec<String>),
     /// 播放列表项目选择
     PlaylistItemSelected(usize),
+    /// 在当前播放列表中移动曲目（索引，是否向开头移动）
+    PlaylistItemMove(usize, bool),
+    /// 从当前播放列表中移除曲目
+    PlaylistItemRemove(usize),
     /// 播放列表卡片选中切换（用于视觉选中效果）
     PlaylistCardToggled(String),
     /// 播放列表卡片的更多菜单按钮被点击（占位，后续实现）
@@ -38,6 +49,10 @@ pub enum Message {
     PlaylistCardActionAddMusic(String),
     /// 添加音乐选择完成（携带播放列表路径和所选文件）
     PlaylistAddMusicFilesSelected(String, Vec<String>),
+    /// 导出播放列表（打开保存对话框）
+    PlaylistCardActionExport(String),
+    /// 导出位置选择完成（播放列表路径，目标文件路径）
+    PlaylistExportPathSelected(String, Option<String>),
 
     /// 开始创建播放列表（显示输入框）
     StartCreatePlaylist,
@@ -52,12 +67,12 @@ pub enum Message {
     NextTrack,
     /// 上一首
     PreviousTrack,
-    /// 定时器触发（用于更新进度）
-    Tick,
     /// 播放状态更新
     PlaybackStateUpdate(PlaybackState),
     /// 音频会话启动
     AudioSessionStarted(mpsc::UnboundedSender<PlaybackCommand>),
+    /// 播放引擎事件（播放会话编号，事件）
+    PlaybackEvent(u64, PlaybackEvent),
     /// 系统事件
     EventOccurred(Event),
     /// 切换播放列表/歌词显示视图
@@ -66,6 +81,12 @@ pub enum Message {
     WindowResized(f32, f32),
     /// 进度条变化（值为0.0-1.0的比例）
     ProgressChanged(f32),
+    /// 音量变化（值为0.0-1.0）
+    VolumeChanged(f32),
+    /// 松开音量滑块（保存音量）
+    VolumeReleased,
+    /// 静音/取消静音切换
+    ToggleMute,
     /// 切换主题
     ToggleTheme,
     /// 页面切换
@@ -78,8 +99,86 @@ pub enum Message {
     LanguageChanged(String),
     /// 配置重置
     ResetConfig,
+    /// 选择输出设备
+    OutputDeviceSelected(OutputDevice),
+    /// 采样率转换质量变化
+    ResampleQualityChanged(ResampleQuality),
+    /// 播放速度变化
+    PlaybackSpeedChanged(f32),
+    /// 把当前位置设为A-B循环的A点
+    SetLoopStart,
+    /// 把当前位置设为A-B循环的B点并开始循环
+    SetLoopEnd,
+    /// 取消A-B循环
+    ClearLoop,
+    /// 书签名称输入变化
+    BookmarkNameChanged(String),
+    /// 在当前位置添加书签
+    AddBookmark,
+    /// 跳转到书签位置（秒）
+    JumpToBookmark(f64),
+    /// 删除当前文件的书签（索引）
+    DeleteBookmark(usize),
+    /// 启用或关闭均衡器
+    EqualizerToggled(bool),
+    /// 均衡器频段增益变化（频段索引，增益dB）
+    EqualizerBandChanged(usize, f32),
+    /// 松开均衡器频段滑块（保存增益）
+    EqualizerBandReleased,
+    /// 选择均衡器预设
+    EqualizerPresetSelected(EqualizerPresetOption),
+    /// 预设名称输入变化
+    EqualizerPresetNameChanged(String),
+    /// 将当前增益保存为用户预设
+    SaveEqualizerPreset,
+    /// 删除当前选中的用户预设
+    DeleteEqualizerPreset,
     /// AudioFile 后台加载完成（文件路径，加载是否成功）
     AudioFileLoaded(String, bool),
     /// 异步估算时长完成（文件路径，估算的时长）
     AudioDurationEstimated(String, Option<f64>),
+    /// 将曲目插入队首，作为下一首播放
+    QueuePlayNext(String),
+    /// 将曲目添加到播放队列末尾
+    QueueAdd(String),
+    /// 在播放队列中移动曲目（索引，是否向队首移动）
+    QueueMove(usize, bool),
+    /// 从播放队列中移除曲目
+    QueueRemove(usize),
+    /// 清空播放队列
+    QueueClear,
+    /// 添加音乐库文件夹（打开文件夹对话框）
+    LibraryAddFolder,
+    /// 音乐库文件夹选择完成
+    LibraryFolderSelected(Option<String>),
+    /// 移除音乐库文件夹（索引）
+    LibraryRemoveFolder(usize),
+    /// 重新扫描音乐库
+    LibraryRescan,
+    /// 后台扫描完成（扫描后的音乐库及结果统计，扫描任务失败时为None）
+    LibraryScanned(Option<(Library, ScanSummary)>),
+    /// 切换音乐库浏览方式
+    LibraryGroupingChanged(LibraryGrouping),
+    /// 选择音乐库中的分组（分组名称）
+    LibraryGroupSelected(String),
+    /// 播放当前分组中的曲目（分组内索引）
+    LibraryTrackSelected(usize),
+    /// 搜索框文本变化
+    SearchQueryChanged(String),
+    /// 是否同时搜索歌词
+    SearchLyricsToggled(bool),
+    /// 后台加载歌词文本完成（文件路径 -> 歌词文本）
+    SearchLyricsLoaded(HashMap<String, String>),
+    /// 选择搜索结果（结果索引）
+    SearchResultSelected(usize),
+    /// 导出播放历史（打开保存对话框）
+    HistoryExport,
+    /// 导出位置选择完成
+    HistoryExportPathSelected(Option<String>),
+    /// 监视的音乐库文件夹或播放列表文件有变化
+    FilesChanged(WatchEvent),
+    /// 收到远程控制命令
+    Ipc(IpcCall),
+    /// MPRIS总线服务事件
+    Mpris(MprisEvent),
 } 
\ No newline at end of file
@@ -3,7 +3,7 @@
 //! 用于显示播放列表信息的可重用卡片组件
 
 use iced::{
-    widget::{button, column, row, text, text_input, Space},
+    widget::{button, column, container, row, text, text_input, Space},
     Element, Length, Border, Shadow, Background, Color,
     alignment::{Horizontal, Vertical},
     border::Radius,
@@ -36,6 +36,10 @@ pub struct PlaylistCardConfig {
     pub renaming: bool,
     /// 重命名输入中的名称
     pub renaming_name: String,
+    /// 是否为智能播放列表
+    pub smart: bool,
+    /// 是否只读（智能播放列表或CUE等只读格式，不能重命名和添加音乐）
+    pub read_only: bool,
 }
 
 impl Default for PlaylistCardConfig {
@@ -50,6 +54,8 @@ impl Default for PlaylistCardConfig {
             show_menu: false,
             renaming: false,
             renaming_name: String::new(),
+            smart: false,
+            read_only: false,
         }
     }
 }
@@ -75,12 +81,16 @@ impl PlaylistCard {
         let config = self.config;
         let is_selected = config.is_selected;
         
-        // 处理播放列表名称显示
-        let name_without_extension = std::path::Path::new(&config.name)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or(&config.name)
-            .to_string();
+        // 处理播放列表名称显示（智能播放列表的名称不带扩展名）
+        let name_without_extension = if config.smart {
+            config.name.clone()
+        } else {
+            std::path::Path::new(&config.name)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(&config.name)
+                .to_string()
+        };
         
         let display_name = if name_without_extension.chars().count() > 18 {
             format!("{}...", name_without_extension.chars().take(15).collect::<String>())
@@ -169,7 +179,7 @@ impl PlaylistCard {
             .align_y(Vertical::Center)
             .build()
         } else if config.show_menu {
-            // 三个按钮：重命名、添加音乐、删除
+            // 四个按钮：重命名、添加音乐、导出、删除（只读播放列表只有导出、删除）
             let rename_btn = button(text(t!("Rename")).size(constants::TEXT_MEDIUM))
                 .on_press(Message::PlaylistCardActionRenameStart(config.path.clone()))
                 .style(|theme: &iced::Theme, status: iced::widget::button::Status| {
@@ -188,6 +198,15 @@ impl PlaylistCard {
                         _ => iced::widget::button::Style { background: Some(Background::Color(Color::TRANSPARENT)), text_color: palette.primary.strong.color, border: Border { radius: Radius::from(8.0), width: 0.0, color: Color::TRANSPARENT }, shadow: Shadow::default(), snap: false },
                     }
                 });
+            let export_btn = button(text(t!("Export")).size(constants::TEXT_MEDIUM))
+                .on_press(Message::PlaylistCardActionExport(config.path.clone()))
+                .style(|theme: &iced::Theme, status: iced::widget::button::Status| {
+                    let palette = theme.extended_palette();
+                    match status {
+                        iced::widget::button::Status::Hovered => iced::widget::button::Style { background: Some(Background::Color(Color { a: 0.12, ..palette.primary.base.color })), text_color: palette.primary.strong.color, border: Border { radius: Radius::from(8.0), width: 0.0, color: Color::TRANSPARENT }, shadow: Shadow::default(), snap: false },
+                        _ => iced::widget::button::Style { background: Some(Background::Color(Color::TRANSPARENT)), text_color: palette.primary.strong.color, border: Border { radius: Radius::from(8.0), width: 0.0, color: Color::TRANSPARENT }, shadow: Shadow::default(), snap: false },
+                    }
+                });
             let delete_btn = button(text(t!("Delete")).size(constants::TEXT_MEDIUM))
                 .on_press(Message::PlaylistCardActionDelete(config.path.clone()))
                 .style(|theme: &iced::Theme, status: iced::widget::button::Status| {
@@ -197,8 +216,13 @@ impl PlaylistCard {
                         _ => iced::widget::button::Style { background: Some(Background::Color(Color::TRANSPARENT)), text_color: palette.background.base.text, border: Border { radius: Radius::from(8.0), width: 0.0, color: Color::TRANSPARENT }, shadow: Shadow::default(), snap: false },
                     }
                 });
+            let buttons = if config.read_only {
+                column![export_btn, delete_btn]
+            } else {
+                column![rename_btn, add_btn, export_btn, delete_btn]
+            };
             StyledContainer::new(
-                column![rename_btn, add_btn, delete_btn]
+                buttons
                     .spacing(constants::SPACING_SMALL)
                     .width(Length::Fill)
                     .align_x(Horizontal::Center)
@@ -210,9 +234,28 @@ impl PlaylistCard {
             .align_y(Vertical::Center)
             .build()
         } else {
-                StyledContainer::new(
-                svg_icon(icons::CD_ICON, 90.0, if is_selected { Color { a: 0.9, ..constants::ICON_COLOR } } else { constants::ICON_COLOR })
-                )
+                let icon = svg_icon(icons::CD_ICON, 90.0, if is_selected { Color { a: 0.9, ..constants::ICON_COLOR } } else { constants::ICON_COLOR });
+                let icon: Element<Message> = if config.smart {
+                    // 智能播放列表在图标上方显示标记
+                    let badge = container(text(t!("Smart")).size(constants::TEXT_NORMAL))
+                        .padding([2, 10])
+                        .style(|theme: &iced::Theme| {
+                            let palette = theme.extended_palette();
+                            container::Style {
+                                background: Some(Background::Color(Color { a: 0.15, ..palette.primary.base.color })),
+                                text_color: Some(palette.primary.strong.color),
+                                border: Border { radius: Radius::from(8.0), width: 0.0, color: Color::TRANSPARENT },
+                                ..Default::default()
+                            }
+                        });
+                    column![badge, icon]
+                        .spacing(constants::SPACING_SMALL)
+                        .align_x(Horizontal::Center)
+                        .into()
+                } else {
+                    icon
+                };
+                StyledContainer::new(icon)
                 .style(ContainerStyle::Decorative)
                 .width(Length::Fixed(160.0))
                 .height(Length::Fixed(160.0))
@@ -449,6 +492,18 @@ impl PlaylistCardBuilder {
         self
     }
 
+    /// 设置是否为智能播放列表
+    pub fn smart(mut self, smart: bool) -> Self {
+        self.config.smart = smart;
+        self
+    }
+
+    /// 设置是否只读
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.config.read_only = read_only;
+        self
+    }
+
     /// 构建卡片
     pub fn build(self) -> Element<'static, Message> {
         PlaylistCard::new(self.config).build()
@@ -3,6 +3,7 @@
 //! 包含播放器中使用的通用辅助函数。
 
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// 格式化时长显示
 /// 
@@ -41,6 +42,28 @@ pub fn is_m3u_playlist(file_path: &str) -> bool {
     false
 }
 
+/// 检查文件是否为支持的播放列表格式（M3U、PLS、XSPF、CUE）
+/// 
+/// # 参数
+/// * `file_path` - 文件路径
+/// 
+/// # 返回
+/// 如果扩展名对应已注册的播放列表格式则返回true
+pub fn is_playlist_file(file_path: &str) -> bool {
+    crate::playlist::format::format_for_path(file_path).is_some()
+}
+
+/// 检查文件是否为智能播放列表定义（`.smart.toml`）
+/// 
+/// # 参数
+/// * `file_path` - 文件路径
+/// 
+/// # 返回
+/// 如果文件名以 `.smart.toml` 结尾（不区分大小写）则返回true
+pub fn is_smart_playlist_file(file_path: &str) -> bool {
+    file_path.to_lowercase().ends_with(".smart.toml")
+}
+
 /// 检查文件是否为支持的音频格式
 /// 
 /// # 参数
@@ -112,6 +135,24 @@ pub fn normalize_path(path: &str, base_dir: Option<&Path>) -> String {
     }
 }
 
+/// 当前时间的Unix时间戳（秒）
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+/// 把Unix时间戳格式化为本地时间
+/// 
+/// # 参数
+/// * `timestamp` - Unix时间戳（秒）
+/// 
+/// # 返回
+/// 格式化的时间字符串（如 "2025-07-01 21:05:09"）
+pub fn format_timestamp(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,6 +173,18 @@ mod tests {
         assert!(!is_m3u_playlist("test.txt"));
     }
 
+    #[test]
+    fn test_is_playlist_file() {
+        assert!(is_playlist_file("test.m3u8"));
+        assert!(is_playlist_file("test.PLS"));
+        assert!(is_playlist_file("test.xspf"));
+        assert!(is_playlist_file("test.cue"));
+        assert!(!is_playlist_file("test.flac"));
+        assert!(is_smart_playlist_file("/config/Jazz.SMART.toml"));
+        assert!(!is_smart_playlist_file("/config/config.toml"));
+        assert!(!is_playlist_file("Jazz.smart.toml"));
+    }
+
     #[test]
     fn test_is_supported_audio_file() {
         assert!(is_supported_audio_file("test.mp3"));
@@ -0,0 +1,220 @@
+//! 文件系统监视模块
+//!
+//! 监视音乐库文件夹（递归）和配置目录下的播放列表文件（Linux上使用inotify），
+//! 短时间内的连续变化合并后发送给界面，用于更新音乐库和重新加载播放列表。
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use iced::futures::{channel::mpsc, SinkExt, Stream};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::utils::{is_playlist_file, is_smart_playlist_file, is_supported_audio_file};
+
+/// 合并连续变化的等待时间
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 一批变化最长的合并时间，持续有变化时也按时发送
+const MAX_BATCH_DELAY: Duration = Duration::from_secs(5);
+
+/// 需要监视的位置，变化时重新建立监视
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct WatchTargets {
+    /// 音乐库文件夹
+    pub library_folders: Vec<String>,
+    /// 存放播放列表文件的配置目录
+    pub playlist_dir: Option<PathBuf>,
+}
+
+/// 文件系统变化
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchEvent {
+    /// 音乐库文件夹中的音频文件或子文件夹被新增、修改、删除或改名（变化的路径）
+    LibraryChanged(Vec<String>),
+    /// 配置目录中的播放列表文件或智能播放列表定义有变化（变化的播放列表路径）
+    PlaylistsChanged(Vec<String>),
+}
+
+/// 合并中的变化
+#[derive(Debug, Default)]
+struct Changes {
+    library: BTreeSet<String>,
+    playlists: BTreeSet<String>,
+}
+
+impl Changes {
+    /// 按所属位置记录一次文件系统事件中的路径
+    fn add(&mut self, targets: &WatchTargets, event: notify::Result<notify::Event>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                eprintln!("文件监视出错: {}", e);
+                return;
+            }
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+
+        for path in event.paths {
+            let path_str = path.to_string_lossy().to_string();
+            if targets.playlist_dir.as_deref().is_some_and(|dir| path.parent() == Some(dir)) {
+                if is_playlist_file(&path_str) || is_smart_playlist_file(&path_str) {
+                    self.playlists.insert(path_str);
+                }
+            } else if targets.library_folders.iter().any(|folder| path.starts_with(folder)) {
+                // 已删除或改名的路径无法判断是否为文件夹，交给音乐库处理
+                if !path.is_file() || is_supported_audio_file(&path_str) {
+                    self.library.insert(path_str);
+                }
+            }
+        }
+    }
+
+    /// 转换为发送给界面的事件
+    fn into_events(self) -> Vec<WatchEvent> {
+        let mut events = Vec::new();
+        if !self.library.is_empty() {
+            events.push(WatchEvent::LibraryChanged(self.library.into_iter().collect()));
+        }
+        if !self.playlists.is_empty() {
+            events.push(WatchEvent::PlaylistsChanged(self.playlists.into_iter().collect()));
+        }
+        events
+    }
+}
+
+/// 监视指定位置，产生文件系统变化事件
+///
+/// 无法创建监视器时打印错误，返回的流直接结束；单个位置无法监视时跳过该位置。
+pub fn watch(targets: &WatchTargets) -> impl Stream<Item = WatchEvent> {
+    let targets = targets.clone();
+    iced::stream::channel(16, move |mut output: mpsc::Sender<WatchEvent>| async move {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut watcher = match notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("启动文件监视失败: {}", e);
+                return;
+            }
+        };
+
+        if let Some(dir) = &targets.playlist_dir {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                eprintln!("监视播放列表目录失败 {}: {}", dir.display(), e);
+            }
+        }
+        for folder in &targets.library_folders {
+            if let Err(e) = watcher.watch(Path::new(folder), RecursiveMode::Recursive) {
+                eprintln!("监视音乐文件夹失败 {}: {}", folder, e);
+            }
+        }
+
+        while let Some(changes) = next_batch(&mut receiver, &targets, DEBOUNCE, MAX_BATCH_DELAY).await {
+            for event in changes.into_events() {
+                if output.send(event).await.is_err() {
+                    return;
+                }
+            }
+        }
+    })
+}
+
+/// 等待下一批变化
+///
+/// 合并短时间内的连续变化（例如复制整张专辑）：安静 `debounce` 后结束这一批，
+/// 变化一直不停时最多合并 `max_delay`。
+///
+/// # 返回
+/// 监视器已关闭时返回None
+async fn next_batch(
+    receiver: &mut tokio::sync::mpsc::UnboundedReceiver<notify::Result<notify::Event>>,
+    targets: &WatchTargets,
+    debounce: Duration,
+    max_delay: Duration,
+) -> Option<Changes> {
+    let mut changes = Changes::default();
+    changes.add(targets, receiver.recv().await?);
+    let deadline = tokio::time::Instant::now() + max_delay;
+    loop {
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            break;
+        }
+        match tokio::time::timeout_at(deadline.min(now + debounce), receiver.recv()).await {
+            Ok(Some(event)) => changes.add(targets, event),
+            _ => break,
+        }
+    }
+    Some(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, ModifyKind, RenameMode};
+
+    fn event(kind: EventKind, paths: &[&str]) -> notify::Result<notify::Event> {
+        let event = paths.iter().fold(notify::Event::new(kind), |event, path| event.add_path(PathBuf::from(path)));
+        Ok(event)
+    }
+
+    #[test]
+    fn test_changes_are_classified() {
+        let targets = WatchTargets {
+            library_folders: vec!["/music".to_string()],
+            playlist_dir: Some(PathBuf::from("/config/summer-player")),
+        };
+        let mut changes = Changes::default();
+        changes.add(&targets, event(EventKind::Create(CreateKind::File), &["/music/a.mp3", "/elsewhere/b.mp3"]));
+        changes.add(&targets, event(EventKind::Modify(ModifyKind::Name(RenameMode::Both)), &["/music/old", "/music/new"]));
+        changes.add(&targets, event(EventKind::Modify(ModifyKind::Any), &[
+            "/config/summer-player/list.m3u",
+            "/config/summer-player/library.json",
+            "/config/summer-player/Jazz.smart.toml",
+            "/config/summer-player/sub/other.m3u",
+        ]));
+        changes.add(&targets, event(EventKind::Access(notify::event::AccessKind::Any), &["/music/c.mp3"]));
+        changes.add(&targets, Err(notify::Error::generic("lost")));
+
+        assert_eq!(changes.into_events(), [
+            WatchEvent::LibraryChanged(vec!["/music/a.mp3".to_string(), "/music/new".to_string(), "/music/old".to_string()]),
+            WatchEvent::PlaylistsChanged(vec![
+                "/config/summer-player/Jazz.smart.toml".to_string(),
+                "/config/summer-player/list.m3u".to_string(),
+            ]),
+        ]);
+        assert!(Changes::default().into_events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batch_has_deadline() {
+        let targets = WatchTargets {
+            library_folders: vec!["/music".to_string()],
+            playlist_dir: None,
+        };
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        // 持续产生变化，间隔短于合并等待时间
+        let producer = tokio::spawn(async move {
+            for index in 0.. {
+                let path = format!("/music/{}.mp3", index);
+                if sender.send(event(EventKind::Create(CreateKind::File), &[&path])).is_err() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+
+        let started = std::time::Instant::now();
+        let changes = next_batch(&mut receiver, &targets, Duration::from_millis(100), Duration::from_millis(300)).await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(!changes.library.is_empty());
+        producer.abort();
+
+        let (_, mut closed) = tokio::sync::mpsc::unbounded_channel();
+        assert!(next_batch(&mut closed, &targets, DEBOUNCE, MAX_BATCH_DELAY).await.is_none());
+    }
+}
@@ -0,0 +1,16 @@
+REM GENRE Pop
+PERFORMER "The Band"
+TITLE "Summer Album"
+FILE "01 Opening.flac" WAVE
+  TRACK 01 AUDIO
+    TITLE "Opening"
+    INDEX 01 00:00:00
+FILE "image.wav" WAVE
+  TRACK 02 AUDIO
+    TITLE "Second"
+    PERFORMER "Guest"
+    INDEX 00 03:25:00
+    INDEX 01 03:27:45
+  TRACK 03 AUDIO
+    TITLE "Third"
+    INDEX 01 07:10:00